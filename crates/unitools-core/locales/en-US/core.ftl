@@ -0,0 +1,160 @@
+## Tool categories
+
+category-file = File tools
+category-text = Text tools
+category-network = Network tools
+category-image = Image tools
+category-converter = Converters
+category-system = System tools
+category-other = Other tools
+
+## Errors
+
+error-io = I/O error: { $message }
+error-parameter = Invalid parameter: { $message }
+error-format = Format error: { $message }
+error-network = Network error: { $message }
+error-parse = Parse error: { $message }
+error-not-found = Not found: { $message }
+error-plugin = Plugin error: { $message }
+error-pipeline = Pipeline step { $step } ({ $tool }) failed: { $source }
+error-cancelled = Operation cancelled
+error-permission-denied = Permission denied: { $message }
+error-not-implemented = Not implemented: { $message }
+error-other = Error: { $message }
+error-position = At line { $line }, column { $column }
+error-hint = Hint: { $hint }
+error-caused-by = Caused by: { $error }
+hint-did-you-mean-parameter = Did you mean parameter `{ $name }`?
+hint-did-you-mean-value = Did you mean `{ $value }`?
+hint-did-you-mean-tool = Did you mean tool `{ $id }`?
+
+## Parameter validation
+
+param-not-int = Parameter '{ $name }' must be an integer: { $value }
+param-not-number = Parameter '{ $name }' must be a number: { $value }
+param-not-bool = Parameter '{ $name }' must be a boolean (true/false): { $value }
+param-invalid-choice = Invalid value for parameter '{ $name }': { $value }, expected one of: { $allowed }
+param-invalid-pattern = Invalid validation pattern for parameter '{ $name }': { $error }
+param-pattern-mismatch = Parameter '{ $name }' does not match the required format ({ $pattern }): { $value }
+param-below-min = Parameter '{ $name }' must not be less than { $min }: { $value }
+param-above-max = Parameter '{ $name }' must not be greater than { $max }: { $value }
+param-missing = Missing required parameter: { $name }
+param-unparsable = Cannot parse parameter '{ $name }': { $value }
+
+## Capabilities
+
+capability-fs-read = read any file
+capability-fs-read-path = read files in { $path }
+capability-fs-write = write any file
+capability-fs-write-path = write files in { $path }
+capability-network = access the network
+capability-process = start other programs
+capability-clipboard = read and write the clipboard
+capability-read-file = read { $path }
+capability-write-file = write { $path }
+permission-not-granted = Not allowed to { $action }
+
+## Batch processing
+
+batch-column-file = File
+batch-column-result = Result
+batch-column-detail = Output/Error
+batch-succeeded = Succeeded
+batch-failed = Failed
+batch-no-output = [no output]
+batch-started = Starting
+batch-progress = Processed { $done }/{ $total }: { $path }
+batch-finished = Done: { $succeeded } succeeded, { $failed } failed
+batch-tool-name = Batch { $tool }
+batch-tool-description =
+    { $count ->
+        [one] Process 1 file with { $tool }
+       *[other] Process { $count } files with { $tool }
+    }
+batch-invalid-pattern = Invalid file pattern '{ $pattern }': { $error }
+batch-no-files = No matching files: { $source }
+batch-outside-root = File '{ $path }' is not in directory '{ $root }', cannot determine where to write its output
+
+## Pipelines
+
+pipeline-empty = Empty pipeline
+pipeline-description = Pipeline: { $tools }
+pipeline-tool-missing = The tool of pipeline step { $step } does not exist: { $tool }
+pipeline-step-progress = Step { $step }/{ $total }: { $tool }
+pipeline-finished = Done
+
+## Tool execution
+
+input-not-utf8 = The input is not valid UTF-8 text
+output-binary = [binary data: { $mime_type }, { $size } bytes]
+output-image = [image data: { $mime_type }, { $size } bytes]
+task-aborted = The tool task terminated abnormally
+
+## Configuration sources
+
+config-source-default = default
+config-source-system = system config { $path }
+config-source-user = user config { $path }
+config-source-environment = environment variable { $name }
+config-source-command-line = command line
+
+## Tool configuration
+
+tool-config-not-object = The tool configuration is not a JSON object, using defaults
+tool-config-unknown = Ignored undeclared setting '{ $name }'
+tool-config-invalid = Setting '{ $name }' is invalid, using the default: { $error }
+tool-config-unsupported = Setting '{ $name }' must be a string, number or boolean, using the default
+
+## Plugins
+
+plugin-manifest-invalid = Invalid plugin manifest: { $error }
+plugin-manifest-missing-id = The plugin manifest has no id
+plugin-self-dependency = Plugin '{ $id }' cannot depend on itself
+plugin-app-version = Plugin '{ $id }' requires app version { $required } or later, the current version is { $current }
+plugin-undeclared-tool = Plugin '{ $id }' provides tool '{ $tool }', which is not declared in its manifest
+plugin-missing-tool = Plugin '{ $id }' does not provide tool '{ $tool }' declared in its manifest
+plugin-dependency-loaded-version = Plugin '{ $id }' requires '{ $dependency }' { $requirement }, but the loaded version is { $version }
+plugin-dependency-not-loaded = Plugin '{ $dependency }' required by plugin '{ $id }' is not loaded
+plugin-dependency-version = Plugin '{ $id }' requires '{ $dependency }' { $requirement }, but the available version is { $version }
+plugin-dependency-missing = Plugin '{ $dependency }' required by plugin '{ $id }' does not exist or cannot be loaded
+plugin-dependency-cycle = Plugin '{ $id }' has a circular dependency: { $cycle }
+plugin-dependency-in-cycle = A dependency of plugin '{ $id }' has a circular dependency: { $cycle }
+plugin-duplicate-id = Duplicate plugin id '{ $id }'
+plugin-permissions-pending = Permissions required by plugin '{ $id }' have not been approved: { $capabilities }
+plugin-already-registered = Plugin '{ $id }' is already registered
+plugin-tool-builtin = Tool '{ $tool }' has the same id as a built-in tool
+plugin-tool-registered = Tool '{ $tool }' is already registered by another plugin
+plugin-tool-duplicate = Plugin '{ $id }' provides tool '{ $tool }' more than once
+plugin-cleanup-failed = { $error }, and cleaning up the plugin failed: { $cleanup }
+plugin-health-restarted = Plugin '{ $id }' failed its health check ({ $error }) and was restarted
+plugin-health-restart-failed = Plugin '{ $id }' failed its health check ({ $error }) and could not be restarted: { $restart }
+plugin-has-dependent = Plugin '{ $id }' is required by '{ $dependent }' and cannot be unloaded
+plugin-not-found = Plugin not found: '{ $id }'
+plugin-describe-invalid = invalid plugin description: { $error }
+plugin-result-invalid = invalid execution result: { $error }
+plugin-out-of-fuel = exceeded its execution quota and was terminated
+plugin-run-failed = failed: { $error }
+protocol-error-code = { $message } (error code { $code })
+
+wasm-error = WASM plugin '{ $name }': { $message }
+wasm-compile-failed = Cannot compile WASM plugin '{ $path }': { $error }
+wasm-fuel-failed = cannot set the execution quota: { $error }
+wasm-missing-export = the module does not export { $export }
+wasm-missing-function = the module does not export { $export }: { $error }
+wasm-request-too-large = the request is too large
+wasm-write-failed = cannot write the request: { $error }
+wasm-out-of-bounds = the returned data is outside its memory
+wasm-out-of-memory = exceeded its memory limit and was terminated
+
+process-error = Process plugin '{ $name }': { $message }
+process-spawn-failed = cannot start the process: { $error }
+process-exited = the process exited unexpectedly
+process-send-failed = cannot send the request: { $error }
+process-timeout = the '{ $method }' request timed out
+
+script-error = Script '{ $name }': { $message }
+script-compile-failed = Cannot compile script '{ $path }': { $error }
+script-describe-failed = calling describe failed: { $error }
+script-describe-invalid = describe returned an invalid description: { $error }
+script-result-invalid = cannot convert the execution result: { $error }
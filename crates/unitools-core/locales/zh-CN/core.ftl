@@ -0,0 +1,156 @@
+## 工具类别
+
+category-file = 文件工具
+category-text = 文本工具
+category-network = 网络工具
+category-image = 图像工具
+category-converter = 转换工具
+category-system = 系统工具
+category-other = 其他工具
+
+## 错误
+
+error-io = IO错误: { $message }
+error-parameter = 参数错误: { $message }
+error-format = 格式错误: { $message }
+error-network = 网络错误: { $message }
+error-parse = 解析错误: { $message }
+error-not-found = 未找到: { $message }
+error-plugin = 插件错误: { $message }
+error-pipeline = 流水线第 { $step } 步 ({ $tool }) 执行失败: { $source }
+error-cancelled = 操作已取消
+error-permission-denied = 权限不足: { $message }
+error-not-implemented = 未实现: { $message }
+error-other = 其他错误: { $message }
+error-position = 位置: 第 { $line } 行，第 { $column } 列
+error-hint = 提示: { $hint }
+error-caused-by = 原因: { $error }
+hint-did-you-mean-parameter = 是否想使用参数 `{ $name }`？
+hint-did-you-mean-value = 是否想使用 `{ $value }`？
+hint-did-you-mean-tool = 是否想使用工具 `{ $id }`？
+
+## 参数校验
+
+param-not-int = 参数 '{ $name }' 应为整数: { $value }
+param-not-number = 参数 '{ $name }' 应为数字: { $value }
+param-not-bool = 参数 '{ $name }' 应为布尔值(true/false): { $value }
+param-invalid-choice = 参数 '{ $name }' 的值无效: { $value }，可选值: { $allowed }
+param-invalid-pattern = 参数 '{ $name }' 的校验规则无效: { $error }
+param-pattern-mismatch = 参数 '{ $name }' 的值不符合格式要求 ({ $pattern }): { $value }
+param-below-min = 参数 '{ $name }' 不能小于 { $min }: { $value }
+param-above-max = 参数 '{ $name }' 不能大于 { $max }: { $value }
+param-missing = 缺少必填参数: { $name }
+param-unparsable = 无法解析参数 '{ $name }': { $value }
+
+## 能力
+
+capability-fs-read = 读取任意文件
+capability-fs-read-path = 读取 { $path } 中的文件
+capability-fs-write = 写入任意文件
+capability-fs-write-path = 写入 { $path } 中的文件
+capability-network = 访问网络
+capability-process = 启动其他程序
+capability-clipboard = 读写剪贴板
+capability-read-file = 读取 { $path }
+capability-write-file = 写入 { $path }
+permission-not-granted = 未获准{ $action }
+
+## 批量处理
+
+batch-column-file = 文件
+batch-column-result = 结果
+batch-column-detail = 输出/错误
+batch-succeeded = 成功
+batch-failed = 失败
+batch-no-output = [无输出]
+batch-started = 开始处理
+batch-progress = 已处理 { $done }/{ $total }: { $path }
+batch-finished = 完成: { $succeeded } 个成功，{ $failed } 个失败
+batch-tool-name = 批量{ $tool }
+batch-tool-description = 使用 { $tool } 批量处理 { $count } 个文件
+batch-invalid-pattern = 无效的文件模式 '{ $pattern }': { $error }
+batch-no-files = 没有匹配的文件: { $source }
+batch-outside-root = 文件 '{ $path }' 不在目录 '{ $root }' 中，无法确定输出位置
+
+## 流水线
+
+pipeline-empty = 空流水线
+pipeline-description = 流水线: { $tools }
+pipeline-tool-missing = 流水线第 { $step } 步使用的工具不存在: { $tool }
+pipeline-step-progress = 第 { $step }/{ $total } 步: { $tool }
+pipeline-finished = 完成
+
+## 工具执行
+
+input-not-utf8 = 输入数据不是有效的UTF-8文本
+output-binary = [二进制数据: { $mime_type }, { $size } 字节]
+output-image = [图像数据: { $mime_type }, { $size } 字节]
+task-aborted = 工具任务异常终止
+
+## 配置来源
+
+config-source-default = 默认值
+config-source-system = 系统配置 { $path }
+config-source-user = 用户配置 { $path }
+config-source-environment = 环境变量 { $name }
+config-source-command-line = 命令行参数
+
+## 工具配置
+
+tool-config-not-object = 工具配置不是 JSON 对象，已使用默认值
+tool-config-unknown = 未声明的配置项 '{ $name }' 已被忽略
+tool-config-invalid = 配置项 '{ $name }' 无效，已使用默认值: { $error }
+tool-config-unsupported = 配置项 '{ $name }' 的值必须是字符串、数字或布尔值，已使用默认值
+
+## 插件
+
+plugin-manifest-invalid = 插件清单格式无效: { $error }
+plugin-manifest-missing-id = 插件清单缺少 id
+plugin-self-dependency = 插件 '{ $id }' 不能依赖自身
+plugin-app-version = 插件 '{ $id }' 需要应用版本 { $required } 或更高，当前版本为 { $current }
+plugin-undeclared-tool = 插件 '{ $id }' 提供了清单中未声明的工具 '{ $tool }'
+plugin-missing-tool = 插件 '{ $id }' 未提供清单中声明的工具 '{ $tool }'
+plugin-dependency-loaded-version = 插件 '{ $id }' 需要 '{ $dependency }' { $requirement }，但已加载的版本为 { $version }
+plugin-dependency-not-loaded = 插件 '{ $id }' 依赖的插件 '{ $dependency }' 未加载
+plugin-dependency-version = 插件 '{ $id }' 需要 '{ $dependency }' { $requirement }，但可用的版本为 { $version }
+plugin-dependency-missing = 插件 '{ $id }' 依赖的插件 '{ $dependency }' 不存在或无法加载
+plugin-dependency-cycle = 插件 '{ $id }' 存在循环依赖: { $cycle }
+plugin-dependency-in-cycle = 插件 '{ $id }' 依赖的插件存在循环依赖: { $cycle }
+plugin-duplicate-id = 插件标识 '{ $id }' 重复
+plugin-permissions-pending = 插件 '{ $id }' 需要的权限尚未批准: { $capabilities }
+plugin-already-registered = 插件 '{ $id }' 已经注册
+plugin-tool-builtin = 工具 '{ $tool }' 与内置工具的标识相同
+plugin-tool-registered = 工具 '{ $tool }' 已经被其他插件注册
+plugin-tool-duplicate = 插件 '{ $id }' 重复提供了工具 '{ $tool }'
+plugin-cleanup-failed = { $error }，且清理插件失败: { $cleanup }
+plugin-health-restarted = 插件 '{ $id }' 健康检查失败（{ $error }），已重启
+plugin-health-restart-failed = 插件 '{ $id }' 健康检查失败（{ $error }），重启失败: { $restart }
+plugin-has-dependent = 插件 '{ $id }' 被 '{ $dependent }' 依赖，无法卸载
+plugin-not-found = 未找到插件: '{ $id }'
+plugin-describe-invalid = 插件描述格式无效: { $error }
+plugin-result-invalid = 执行结果格式无效: { $error }
+plugin-out-of-fuel = 超出执行配额，已被终止
+plugin-run-failed = 运行失败: { $error }
+protocol-error-code = { $message } (错误码 { $code })
+
+wasm-error = WASM 插件 '{ $name }' { $message }
+wasm-compile-failed = 无法编译 WASM 插件 '{ $path }': { $error }
+wasm-fuel-failed = 无法设置执行配额: { $error }
+wasm-missing-export = 模块未导出 { $export }
+wasm-missing-function = 模块未导出 { $export }: { $error }
+wasm-request-too-large = 请求数据过大
+wasm-write-failed = 无法写入请求数据: { $error }
+wasm-out-of-bounds = 返回的数据超出内存范围
+wasm-out-of-memory = 超出内存上限，已被终止
+
+process-error = 进程插件 '{ $name }' { $message }
+process-spawn-failed = 无法启动进程: { $error }
+process-exited = 进程意外退出
+process-send-failed = 无法发送请求: { $error }
+process-timeout = '{ $method }' 请求超时
+
+script-error = 脚本 '{ $name }' { $message }
+script-compile-failed = 无法编译脚本 '{ $path }': { $error }
+script-describe-failed = 调用 describe 失败: { $error }
+script-describe-invalid = describe 返回的描述格式无效: { $error }
+script-result-invalid = 无法转换执行结果: { $error }
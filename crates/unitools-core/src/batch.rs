@@ -0,0 +1,517 @@
+use crate::error::ToolError;
+use crate::tool::{Tool, ToolCategory, ToolConfig, ToolContext, ToolOutput, ToolResult};
+use crate::tr;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// 原地修改文件时备份文件的扩展名
+pub const BACKUP_EXTENSION: &str = "bak";
+
+/// 批量处理结果的写入位置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchDestination {
+    /// 按源文件的相对路径写入另一个目录
+    Mirror(PathBuf),
+    /// 覆盖源文件，覆盖前先备份为 `<文件名>.bak`
+    InPlace,
+}
+
+/// 单个文件的处理结果
+#[derive(Debug)]
+pub struct FileResult {
+    /// 源文件
+    pub path: PathBuf,
+    /// 成功时为写入的文件，工具没有输出时为 `None`
+    pub result: Result<Option<PathBuf>, ToolError>,
+}
+
+/// 批量处理的汇总
+#[derive(Debug, Default)]
+pub struct BatchSummary {
+    /// 按文件顺序排列的处理结果
+    pub results: Vec<FileResult>,
+}
+
+impl BatchSummary {
+    /// 处理成功的文件数
+    pub fn succeeded(&self) -> usize {
+        self.results.iter().filter(|r| r.result.is_ok()).count()
+    }
+
+    /// 处理失败的文件
+    pub fn failures(&self) -> impl Iterator<Item = (&Path, &ToolError)> {
+        self.results
+            .iter()
+            .filter_map(|r| r.result.as_ref().err().map(|e| (r.path.as_path(), e)))
+    }
+
+    /// 转换为表格输出
+    pub fn to_output(&self) -> ToolOutput {
+        let rows = self
+            .results
+            .iter()
+            .map(|r| {
+                let (status, detail) = match &r.result {
+                    Ok(Some(output)) => (tr!("batch-succeeded"), output.display().to_string()),
+                    Ok(None) => (tr!("batch-succeeded"), tr!("batch-no-output")),
+                    Err(error) => (tr!("batch-failed"), error.to_string()),
+                };
+                vec![r.path.display().to_string(), status, detail]
+            })
+            .collect();
+        ToolOutput::Table {
+            headers: vec![
+                tr!("batch-column-file"),
+                tr!("batch-column-result"),
+                tr!("batch-column-detail"),
+            ],
+            rows,
+        }
+    }
+}
+
+/// 查找要批量处理的文件
+///
+/// `source` 为目录时递归包含其中的所有文件，否则按通配符模式匹配，例如 `docs/**/*.txt`。
+/// 之前原地修改时留下的备份文件和 `destination` 镜像输出目录中的文件不会被包含。
+/// 返回镜像输出时使用的根目录和排序后的文件列表。
+pub fn collect_files(
+    source: &str,
+    destination: &BatchDestination,
+) -> Result<(PathBuf, Vec<PathBuf>), ToolError> {
+    let path = Path::new(source);
+    let (root, mut files) = if path.is_dir() {
+        let mut files = Vec::new();
+        walk_dir(path, &mut files)?;
+        (path.to_path_buf(), files)
+    } else {
+        let paths = glob::glob(source).map_err(|e| {
+            ToolError::ParameterError(tr!("batch-invalid-pattern", pattern = source, error = e))
+        })?;
+        let files = paths
+            .filter_map(Result::ok)
+            .filter(|path| path.is_file())
+            .collect();
+        (glob_root(path), files)
+    };
+
+    // 输出目录不存在时其中也没有文件
+    let output = match destination {
+        BatchDestination::Mirror(dir) => fs::canonicalize(dir).ok(),
+        BatchDestination::InPlace => None,
+    };
+    files.retain(|path| {
+        !is_backup_file(path)
+            && !output.as_ref().is_some_and(|output| {
+                fs::canonicalize(path).is_ok_and(|path| path.starts_with(output))
+            })
+    });
+
+    if files.is_empty() {
+        return Err(ToolError::NotFoundError(tr!(
+            "batch-no-files",
+            source = source
+        )));
+    }
+
+    files.sort();
+    Ok((root, files))
+}
+
+/// 递归列出目录中的文件
+fn walk_dir(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), ToolError> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            walk_dir(&path, files)?;
+        } else if path.is_file() {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// 通配符模式中第一个含通配符的部分之前的目录
+fn glob_root(pattern: &Path) -> PathBuf {
+    let mut root = PathBuf::new();
+    let components: Vec<Component> = pattern.components().collect();
+    for (index, component) in components.iter().enumerate() {
+        let text = component.as_os_str().to_string_lossy();
+        // 最后一部分是文件名，即使不含通配符也不属于根目录
+        if index + 1 == components.len() || text.contains(['*', '?', '[']) {
+            break;
+        }
+        root.push(component);
+    }
+
+    if root.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        root
+    }
+}
+
+/// 批量处理，用同一个工具和参数依次处理多个文件
+///
+/// 每个文件的内容作为 `ToolContext::input_data`，多个文件在工作线程中并行处理。
+/// 批量处理本身也实现了 `Tool`，执行结果为每个文件的处理情况表格。
+#[derive(Debug, Clone)]
+pub struct Batch {
+    id: String,
+    name: String,
+    description: String,
+    tool: Arc<dyn Tool>,
+    parameters: HashMap<String, String>,
+    root: PathBuf,
+    files: Vec<PathBuf>,
+    destination: BatchDestination,
+    workers: usize,
+}
+
+impl Batch {
+    /// 创建批量处理，`root` 为镜像输出时计算相对路径的根目录
+    pub fn new(
+        tool: Arc<dyn Tool>,
+        parameters: HashMap<String, String>,
+        root: PathBuf,
+        files: Vec<PathBuf>,
+        destination: BatchDestination,
+    ) -> Self {
+        Self {
+            id: format!("batch.{}", tool.id()),
+            name: tr!("batch-tool-name", tool = tool.name()),
+            description: tr!(
+                "batch-tool-description",
+                tool = tool.name(),
+                count = files.len()
+            ),
+            tool,
+            parameters,
+            root,
+            files,
+            destination,
+            workers: default_workers(),
+        }
+    }
+
+    /// 设置并行处理的线程数，为 0 时使用 CPU 核心数
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = if workers == 0 {
+            default_workers()
+        } else {
+            workers
+        };
+        self
+    }
+
+    /// 获取要处理的文件
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// 处理所有文件并返回汇总
+    ///
+    /// 参数无效时直接返回错误；单个文件失败不影响其他文件，结果记录在汇总中。
+    /// 取消后尚未开始处理的文件记为 `ToolError::Cancelled`。
+    pub fn run(&self, ctx: &ToolContext) -> Result<BatchSummary, ToolError> {
+        let parameters = self.tool.validate_parameters(&self.parameters)?;
+        let config = ToolConfig::resolve(
+            &self.tool.config_schema(),
+            ctx.tool_configs.get(self.tool.id()),
+        )
+        .values;
+
+        let total = self.files.len();
+        let next = AtomicUsize::new(0);
+        let done = AtomicUsize::new(0);
+        let results: Mutex<Vec<Option<FileResult>>> =
+            Mutex::new((0..total).map(|_| None).collect());
+
+        ctx.progress.report_count(0, total, &tr!("batch-started"));
+        thread::scope(|scope| {
+            for _ in 0..self.workers.min(total) {
+                scope.spawn(|| {
+                    loop {
+                        let index = next.fetch_add(1, Ordering::SeqCst);
+                        let Some(path) = self.files.get(index) else {
+                            break;
+                        };
+
+                        let result = ctx
+                            .cancellation
+                            .check()
+                            .and_then(|()| self.process_file(ctx, path, &parameters, &config));
+                        results.lock().unwrap()[index] = Some(FileResult {
+                            path: path.clone(),
+                            result,
+                        });
+
+                        let done = done.fetch_add(1, Ordering::SeqCst) + 1;
+                        ctx.progress.report_count(
+                            done,
+                            total,
+                            &tr!(
+                                "batch-progress",
+                                done = done,
+                                total = total,
+                                path = path.display()
+                            ),
+                        );
+                    }
+                });
+            }
+        });
+
+        Ok(BatchSummary {
+            results: results
+                .into_inner()
+                .unwrap()
+                .into_iter()
+                .flatten()
+                .collect(),
+        })
+    }
+
+    /// 处理单个文件，返回写入的文件
+    fn process_file(
+        &self,
+        ctx: &ToolContext,
+        path: &Path,
+        parameters: &HashMap<String, String>,
+        config: &HashMap<String, String>,
+    ) -> Result<Option<PathBuf>, ToolError> {
+        let file_ctx = ToolContext {
+            input_data: Some(ctx.read_file(path)?),
+            parameters: parameters.clone(),
+            config: config.clone(),
+            cancellation: ctx.cancellation.clone(),
+            permissions: ctx.permissions.clone(),
+            ..Default::default()
+        };
+
+        let Some(output) = ctx
+            .events
+            .track_execution(self.tool.id(), || self.tool.execute(&file_ctx))?
+        else {
+            return Ok(None);
+        };
+
+        let target = match &self.destination {
+            BatchDestination::Mirror(dir) => {
+                // 不在根目录中的文件无法确定输出位置，不能回退为源文件路径，否则会覆盖源文件
+                let relative = path.strip_prefix(&self.root).map_err(|_| {
+                    ToolError::ParameterError(tr!(
+                        "batch-outside-root",
+                        path = path.display(),
+                        root = self.root.display()
+                    ))
+                })?;
+                let target = dir.join(relative);
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                target
+            }
+            BatchDestination::InPlace => {
+                let backup = backup_path(path);
+                ctx.write_file(&backup, &fs::read(path)?)?;
+                path.to_path_buf()
+            }
+        };
+
+        ctx.write_file(&target, &output.to_bytes())?;
+        Ok(Some(target))
+    }
+}
+
+/// 备份文件路径，已存在时依次尝试 `.bak.1`、`.bak.2` 等，避免覆盖之前的备份
+fn backup_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut backup = path.with_file_name(format!("{}.{}", name, BACKUP_EXTENSION));
+    let mut index = 1;
+    while backup.exists() {
+        backup = path.with_file_name(format!("{}.{}.{}", name, BACKUP_EXTENSION, index));
+        index += 1;
+    }
+    backup
+}
+
+/// 是否为 [`backup_path`] 生成的备份文件，即 `<文件名>.bak` 或 `<文件名>.bak.<序号>`
+fn is_backup_file(path: &Path) -> bool {
+    let Some(name) = path.file_name().map(|name| name.to_string_lossy()) else {
+        return false;
+    };
+    let suffix = format!(".{}", BACKUP_EXTENSION);
+    let name = match name.rsplit_once('.') {
+        Some((rest, index)) if !index.is_empty() && index.bytes().all(|b| b.is_ascii_digit()) => {
+            rest
+        }
+        _ => &name,
+    };
+    name.len() > suffix.len() && name.ends_with(&suffix)
+}
+
+/// 默认线程数
+fn default_workers() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+impl Tool for Batch {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn category(&self) -> ToolCategory {
+        self.tool.category()
+    }
+
+    fn execute(&self, ctx: &ToolContext) -> ToolResult {
+        let summary = self.run(ctx)?;
+        ctx.progress.report_count(
+            summary.results.len(),
+            summary.results.len(),
+            &tr!(
+                "batch-finished",
+                succeeded = summary.succeeded(),
+                failed = summary.failures().count()
+            ),
+        );
+        Ok(Some(summary.to_output()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 将输入转为大写的工具
+    #[derive(Debug, Clone)]
+    struct UpperTool;
+
+    impl Tool for UpperTool {
+        fn id(&self) -> &str {
+            "test.upper"
+        }
+
+        fn name(&self) -> &str {
+            "upper"
+        }
+
+        fn description(&self) -> &str {
+            ""
+        }
+
+        fn category(&self) -> ToolCategory {
+            ToolCategory::Other
+        }
+
+        fn execute(&self, ctx: &ToolContext) -> ToolResult {
+            let input = String::from_utf8_lossy(ctx.input_data.as_deref().unwrap_or_default());
+            Ok(Some(ToolOutput::Text(input.to_uppercase())))
+        }
+    }
+
+    /// 为测试创建空的临时目录
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("unitools-batch-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn run(source: &Path, destination: BatchDestination) -> BatchSummary {
+        let (root, files) = collect_files(source.to_str().unwrap(), &destination).unwrap();
+        Batch::new(
+            Arc::new(UpperTool),
+            HashMap::new(),
+            root,
+            files,
+            destination,
+        )
+        .run(&ToolContext::default())
+        .unwrap()
+    }
+
+    #[test]
+    fn in_place_twice_skips_backups() {
+        let dir = temp_dir("in-place");
+        fs::write(dir.join("a.txt"), "a").unwrap();
+
+        let first = run(&dir, BatchDestination::InPlace);
+        assert_eq!(first.results.len(), 1);
+        let second = run(&dir, BatchDestination::InPlace);
+        assert_eq!(second.results.len(), 1);
+        assert_eq!(second.results[0].path, dir.join("a.txt"));
+
+        assert_eq!(fs::read_to_string(dir.join("a.txt.bak")).unwrap(), "a");
+        assert_eq!(fs::read_to_string(dir.join("a.txt.bak.1")).unwrap(), "A");
+        assert!(!dir.join("a.txt.bak.bak").exists());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 3);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn mirror_output_inside_source_is_skipped() {
+        let dir = temp_dir("mirror-inside");
+        fs::write(dir.join("a.txt"), "a").unwrap();
+        let output = dir.join("out");
+
+        run(&dir, BatchDestination::Mirror(output.clone()));
+        let second = run(&dir, BatchDestination::Mirror(output.clone()));
+        assert_eq!(second.succeeded(), 1);
+        assert_eq!(fs::read_to_string(output.join("a.txt")).unwrap(), "A");
+        assert!(!output.join("out").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn mirror_rejects_file_outside_root() {
+        let dir = temp_dir("mirror-outside");
+        let source = dir.join("source.txt");
+        fs::write(&source, "a").unwrap();
+        let output = dir.join("out");
+
+        let batch = Batch::new(
+            Arc::new(UpperTool),
+            HashMap::new(),
+            dir.join("elsewhere"),
+            vec![source.clone()],
+            BatchDestination::Mirror(output.clone()),
+        );
+        let summary = batch.run(&ToolContext::default()).unwrap();
+        assert!(matches!(
+            summary.results[0].result,
+            Err(ToolError::ParameterError(_))
+        ));
+        assert_eq!(fs::read_to_string(&source).unwrap(), "a");
+        assert!(!output.exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn backup_file_names() {
+        for name in ["a.txt.bak", "a.txt.bak.1", "a.bak.12"] {
+            assert!(is_backup_file(Path::new(name)), "{}", name);
+        }
+        for name in ["a.txt", ".bak", "a.bak.x", "a.bak1", "bak"] {
+            assert!(!is_backup_file(Path::new(name)), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! 工具与插件的能力声明和权限检查
+//!
+//! 工具通过 [`Tool::capabilities`](crate::tool::Tool::capabilities) 声明需要访问的资源，
+//! 插件还可以在清单的 `permissions` 中声明。能力的文本形式如下：
+//!
+//! - `fs-read` / `fs-read:<路径>`：读取任意文件 / 读取指定目录下的文件
+//! - `fs-write` / `fs-write:<路径>`：写入任意文件 / 写入指定目录下的文件
+//! - `network`：访问网络
+//! - `process`：启动子进程
+//! - `clipboard`：读写剪贴板
+
+use crate::error::ToolError;
+use crate::tr;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{self, Display};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// 工具可能需要的能力
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Capability {
+    /// 读取文件，`None` 表示不限路径
+    FsRead(Option<PathBuf>),
+    /// 写入文件，`None` 表示不限路径
+    FsWrite(Option<PathBuf>),
+    /// 访问网络
+    Network,
+    /// 启动子进程
+    ProcessSpawn,
+    /// 读写剪贴板
+    Clipboard,
+}
+
+impl Capability {
+    /// 判断本能力是否涵盖所请求的能力
+    pub fn covers(&self, requested: &Capability) -> bool {
+        match (self, requested) {
+            (Capability::FsRead(scope), Capability::FsRead(path))
+            | (Capability::FsWrite(scope), Capability::FsWrite(path)) => match (scope, path) {
+                (None, _) => true,
+                (Some(_), None) => false,
+                (Some(scope), Some(path)) => normalize(path).starts_with(normalize(scope)),
+            },
+            _ => self == requested,
+        }
+    }
+
+    /// 能力的说明，使用当前界面语言，用于批准对话框
+    pub fn description(&self) -> String {
+        match self {
+            Capability::FsRead(None) => tr!("capability-fs-read"),
+            Capability::FsRead(Some(path)) => tr!("capability-fs-read-path", path = path.display()),
+            Capability::FsWrite(None) => tr!("capability-fs-write"),
+            Capability::FsWrite(Some(path)) => {
+                tr!("capability-fs-write-path", path = path.display())
+            }
+            Capability::Network => tr!("capability-network"),
+            Capability::ProcessSpawn => tr!("capability-process"),
+            Capability::Clipboard => tr!("capability-clipboard"),
+        }
+    }
+}
+
+/// 将路径转换为绝对路径并尽量解析符号链接和 `..`，不存在的文件按其所在目录解析
+fn normalize(path: &Path) -> PathBuf {
+    if let Ok(path) = path.canonicalize() {
+        return path;
+    }
+    if let (Some(parent), Some(name)) = (path.parent(), path.file_name())
+        && let Ok(parent) = parent.canonicalize()
+    {
+        return parent.join(name);
+    }
+    std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+impl Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Capability::FsRead(None) => write!(f, "fs-read"),
+            Capability::FsRead(Some(path)) => write!(f, "fs-read:{}", path.display()),
+            Capability::FsWrite(None) => write!(f, "fs-write"),
+            Capability::FsWrite(Some(path)) => write!(f, "fs-write:{}", path.display()),
+            Capability::Network => write!(f, "network"),
+            Capability::ProcessSpawn => write!(f, "process"),
+            Capability::Clipboard => write!(f, "clipboard"),
+        }
+    }
+}
+
+impl FromStr for Capability {
+    type Err = ToolError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, scope) = match s.split_once(':') {
+            Some((kind, scope)) => (kind, Some(PathBuf::from(scope))),
+            None => (s, None),
+        };
+
+        match (kind, scope) {
+            ("fs-read", scope) => Ok(Capability::FsRead(scope)),
+            ("fs-write", scope) => Ok(Capability::FsWrite(scope)),
+            ("network", None) => Ok(Capability::Network),
+            ("process", None) => Ok(Capability::ProcessSpawn),
+            ("clipboard", None) => Ok(Capability::Clipboard),
+            _ => Err(ToolError::ParseError(format!("无效的能力声明: {}", s))),
+        }
+    }
+}
+
+impl Serialize for Capability {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Capability {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// 工具执行时被授予的权限
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Permissions {
+    /// 不做限制，用于随应用一起发布的内置工具
+    #[default]
+    Unrestricted,
+    /// 只允许列出的能力
+    Granted(Vec<Capability>),
+}
+
+impl Permissions {
+    /// 判断是否允许所请求的能力
+    pub fn allows(&self, requested: &Capability) -> bool {
+        match self {
+            Permissions::Unrestricted => true,
+            Permissions::Granted(granted) => granted.iter().any(|c| c.covers(requested)),
+        }
+    }
+
+    /// 检查权限，未授予时返回 `ToolError::PermissionDenied`
+    pub fn check(&self, requested: &Capability) -> Result<(), ToolError> {
+        if self.allows(requested) {
+            return Ok(());
+        }
+
+        let action = match requested {
+            Capability::FsRead(Some(path)) => tr!("capability-read-file", path = path.display()),
+            Capability::FsWrite(Some(path)) => tr!("capability-write-file", path = path.display()),
+            capability => capability.description(),
+        };
+        Err(ToolError::PermissionDenied(tr!(
+            "permission-not-granted",
+            action = action
+        )))
+    }
+}
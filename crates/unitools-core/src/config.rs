@@ -1,11 +1,43 @@
+use crate::capability::Capability;
 use crate::error::ToolError;
+use crate::pipeline::PipelineDefinition;
+use crate::tool::{Tool, ToolConfig};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+mod format;
+mod layers;
+
+pub use format::ConfigFormat;
+pub use layers::{ConfigLayer, ConfigSource, ENV_PREFIX, LayeredConfig};
+
+/// 当前的配置格式版本
+///
+/// 修改配置结构且旧文件无法按新结构读取时递增版本号，并在 [`MIGRATIONS`] 末尾加入对应的迁移。
+pub const CONFIG_VERSION: u32 = 1;
+
+/// 保存配置时轮换保留的备份数量
+pub const CONFIG_BACKUPS: usize = 3;
+
+/// 配置迁移，将上一版本的配置修改为下一版本的结构
+type Migration = fn(&mut Map<String, Value>);
+
+/// 迁移链，第 `n` 项将版本 `n` 的配置迁移到版本 `n + 1`
+const MIGRATIONS: &[Migration] = &[migrate_v0_to_v1];
 
 /// 应用配置
+///
+/// 缺少的字段使用默认值，读取旧版本的配置文件时先按迁移链升级到当前版本。
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppConfig {
+    /// 配置格式版本，没有此字段的配置文件视为版本 0
+    pub config_version: u32,
     /// 应用主题
     pub theme: Theme,
     /// 界面语言
@@ -14,10 +46,14 @@ pub struct AppConfig {
     pub enabled_plugins: Vec<String>,
     /// 个人配置
     pub user: UserConfig,
-    /// 工具配置
+    /// 工具配置，按工具标识索引
     pub tool_configs: std::collections::HashMap<String, serde_json::Value>,
     /// 插件目录
     pub plugin_directory: Option<PathBuf>,
+    /// 保存的工具流水线
+    pub pipelines: Vec<PipelineDefinition>,
+    /// 用户批准的插件权限，按插件名称索引
+    pub plugin_permissions: std::collections::HashMap<String, Vec<Capability>>,
 }
 
 /// 应用主题
@@ -33,6 +69,7 @@ pub enum Theme {
 
 /// 用户配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct UserConfig {
     /// 用户名
     pub username: String,
@@ -40,45 +77,659 @@ pub struct UserConfig {
     pub working_directory: Option<PathBuf>,
 }
 
+impl Default for UserConfig {
+    fn default() -> Self {
+        Self {
+            username: "用户".to_string(),
+            working_directory: None,
+        }
+    }
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
+            config_version: CONFIG_VERSION,
             theme: Theme::System,
-            language: "zh-CN".to_string(),
+            language: crate::i18n::DEFAULT_LANGUAGE.to_string(),
             enabled_plugins: Vec::new(),
-            user: UserConfig {
-                username: "用户".to_string(),
-                working_directory: None,
-            },
+            user: UserConfig::default(),
             tool_configs: std::collections::HashMap::new(),
             plugin_directory: None,
+            pipelines: Vec::new(),
+            plugin_permissions: std::collections::HashMap::new(),
         }
     }
 }
 
+/// 加载配置时的处理情况
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigReport {
+    /// 从哪个版本迁移而来，未迁移时为 `None`
+    pub migrated_from: Option<u32>,
+    /// 迁移前原配置文件的备份
+    pub backup: Option<PathBuf>,
+    /// 未知字段、无效取值等不影响加载的问题
+    pub warnings: Vec<String>,
+}
+
 impl AppConfig {
-    /// 从文件加载配置
+    /// 从文件加载配置，见 [`AppConfig::load_with_report`]
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self, ToolError> {
-        let content = fs::read_to_string(&path).map_err(|e| ToolError::IoError(e))?;
+        Self::load_with_report(path).map(|(config, _)| config)
+    }
+
+    /// 从文件加载配置并返回处理情况
+    ///
+    /// 配置文件版本较旧时先将原文件备份为 `<文件名>.v<版本>.bak`，再把迁移后的配置写回原文件。
+    pub fn load_with_report<P: AsRef<Path>>(path: P) -> Result<(Self, ConfigReport), ToolError> {
+        let (fields, mut report) = read_config_file(path.as_ref())?;
+        let config = Self::from_fields(fields, &mut report)?;
+        Ok((config, report))
+    }
 
-        serde_json::from_str(&content).map_err(|e| ToolError::ParseError(e.to_string()))
+    /// 从 JSON 文本解析配置，必要时先迁移到当前版本
+    ///
+    /// 未知字段和取值无效的字段不会导致失败，而是记录在返回的处理情况中，
+    /// 取值无效的字段使用默认值。
+    pub fn from_json(content: &str) -> Result<(Self, ConfigReport), ToolError> {
+        let mut fields = ConfigFormat::Json.parse(content)?;
+        let mut report = ConfigReport::default();
+        migrate(&mut fields, &mut report)?;
+        let config = Self::from_fields(fields, &mut report)?;
+        Ok((config, report))
     }
 
-    /// 保存配置到文件
+    /// 由当前版本的配置字段生成配置，未知字段和无效取值记录在 `report` 中
+    pub(crate) fn from_fields(
+        fields: Map<String, Value>,
+        report: &mut ConfigReport,
+    ) -> Result<Self, ToolError> {
+        // 记录当前版本不认识的字段
+        let known = serde_json::to_value(Self::default()).unwrap_or_default();
+        unknown_fields(&fields, &known, "", &mut report.warnings);
+
+        // 整体解析失败时逐个排除取值无效的字段
+        match serde_json::from_value(Value::Object(fields.clone())) {
+            Ok(config) => Ok(config),
+            Err(_) => {
+                let mut valid = Map::new();
+                for (name, value) in fields {
+                    let single = Map::from_iter([(name.clone(), value.clone())]);
+                    match serde_json::from_value::<Self>(Value::Object(single)) {
+                        Ok(_) => {
+                            valid.insert(name, value);
+                        }
+                        Err(e) => report
+                            .warnings
+                            .push(format!("字段 '{}' 的值无效，已使用默认值: {}", name, e)),
+                    }
+                }
+                serde_json::from_value(Value::Object(valid))
+                    .map_err(|e| ToolError::ParseError(e.to_string()))
+            }
+        }
+    }
+
+    /// 保存配置到文件，见 [`save_config_file`]
     pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), ToolError> {
-        let content = serde_json::to_string_pretty(self)
-            .map_err(|e| ToolError::FormatError(e.to_string()))?;
+        let value =
+            serde_json::to_value(self).map_err(|e| ToolError::FormatError(e.to_string()))?;
+        let Value::Object(fields) = value else {
+            return Err(ToolError::FormatError("配置不是对象".to_string()));
+        };
+        save_config_file(path.as_ref(), &fields)
+    }
 
-        fs::write(&path, content).map_err(|e| ToolError::IoError(e))
+    /// 与 `other` 相比取值不同的顶层配置项
+    pub fn changed_fields(&self, other: &Self) -> Vec<String> {
+        let (Ok(Value::Object(fields)), Ok(Value::Object(other))) =
+            (serde_json::to_value(self), serde_json::to_value(other))
+        else {
+            return Vec::new();
+        };
+        fields
+            .into_iter()
+            .filter(|(name, value)| other.get(name) != Some(value))
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// 三方合并配置：`local` 中相对 `base` 修改过的设置保留，其余设置使用 `incoming`
+    ///
+    /// 用于在界面有未保存的修改时应用外部对配置文件的修改，对象类型的设置逐项合并。
+    pub fn merge(base: &Self, local: &Self, incoming: &Self) -> Self {
+        let to_value = |config: &Self| serde_json::to_value(config).unwrap_or_default();
+        let merged = merge_values(
+            Some(&to_value(base)),
+            Some(&to_value(local)),
+            Some(&to_value(incoming)),
+        );
+        merged
+            .and_then(|merged| serde_json::from_value(merged).ok())
+            .unwrap_or_else(|| incoming.clone())
     }
 
     /// 获取指定工具的配置
-    pub fn get_tool_config(&self, tool_name: &str) -> Option<&serde_json::Value> {
-        self.tool_configs.get(tool_name)
+    pub fn get_tool_config(&self, tool_id: &str) -> Option<&serde_json::Value> {
+        self.tool_configs.get(tool_id)
     }
 
     /// 设置工具配置
-    pub fn set_tool_config(&mut self, tool_name: String, config: serde_json::Value) {
-        self.tool_configs.insert(tool_name, config);
+    pub fn set_tool_config(&mut self, tool_id: String, config: serde_json::Value) {
+        self.tool_configs.insert(tool_id, config);
+    }
+
+    /// 按工具的配置规格解析该工具的配置
+    pub fn tool_config(&self, tool: &dyn Tool) -> ToolConfig {
+        ToolConfig::resolve(&tool.config_schema(), self.get_tool_config(tool.id()))
+    }
+
+    /// 保存工具配置，只保存与默认值不同的配置项
+    pub fn save_tool_config(&mut self, tool: &dyn Tool, config: &ToolConfig) {
+        let stored = config.to_json(&tool.config_schema());
+        if stored.as_object().is_some_and(|stored| stored.is_empty()) {
+            self.tool_configs.remove(tool.id());
+        } else {
+            self.tool_configs.insert(tool.id().to_string(), stored);
+        }
+    }
+
+    /// 检查已加载工具保存的配置，无效的配置项恢复为默认值并返回发现的问题
+    ///
+    /// 没有加载的工具（例如未启用的插件）的配置保持不变。
+    pub fn repair_tool_configs(&mut self, tools: &[Arc<dyn Tool>]) -> Vec<String> {
+        let mut problems = Vec::new();
+        for tool in tools {
+            if !self.tool_configs.contains_key(tool.id()) {
+                continue;
+            }
+            let config = self.tool_config(tool.as_ref());
+            if config.problems.is_empty() {
+                continue;
+            }
+            problems.extend(
+                config
+                    .problems
+                    .iter()
+                    .map(|problem| format!("{}: {}", tool.id(), problem)),
+            );
+            self.save_tool_config(tool.as_ref(), &config);
+        }
+        problems
+    }
+
+    /// 获取指定名称的流水线
+    pub fn get_pipeline(&self, name: &str) -> Option<&PipelineDefinition> {
+        self.pipelines.iter().find(|p| p.name == name)
+    }
+
+    /// 保存流水线，已存在同名流水线时覆盖
+    pub fn save_pipeline(&mut self, pipeline: PipelineDefinition) {
+        match self.pipelines.iter_mut().find(|p| p.name == pipeline.name) {
+            Some(existing) => *existing = pipeline,
+            None => self.pipelines.push(pipeline),
+        }
+    }
+
+    /// 删除指定名称的流水线
+    pub fn remove_pipeline(&mut self, name: &str) {
+        self.pipelines.retain(|p| p.name != name);
+    }
+}
+
+/// 将配置字段迁移到当前版本，迁移前的版本记录在 `report` 中
+pub(crate) fn migrate(
+    fields: &mut Map<String, Value>,
+    report: &mut ConfigReport,
+) -> Result<(), ToolError> {
+    let version = match fields.get("config_version") {
+        None => 0,
+        Some(version) => version
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| ToolError::ParseError(format!("无效的配置版本: {}", version)))?,
+    };
+    if version > CONFIG_VERSION {
+        report.warnings.push(format!(
+            "配置文件版本 {} 比当前程序支持的版本 {} 新，部分设置可能被忽略",
+            version, CONFIG_VERSION
+        ));
+    } else if version < CONFIG_VERSION {
+        for migration in &MIGRATIONS[version as usize..] {
+            migration(fields);
+        }
+        fields.insert("config_version".to_string(), Value::from(CONFIG_VERSION));
+        report.migrated_from = Some(version);
+    }
+    Ok(())
+}
+
+/// 读取配置文件并在内存中迁移到当前版本，不修改原文件
+///
+/// 格式由扩展名决定，见 [`ConfigFormat::from_path`]。用于读取系统配置文件等程序不应写入的文件。
+pub(crate) fn parse_and_migrate(
+    path: &Path,
+) -> Result<(Map<String, Value>, ConfigReport), ToolError> {
+    let mut fields = ConfigFormat::from_path(path).parse(&fs::read_to_string(path)?)?;
+    let mut report = ConfigReport::default();
+    migrate(&mut fields, &mut report)?;
+    Ok((fields, report))
+}
+
+/// 读取配置文件并迁移到当前版本，见 [`parse_and_migrate`]
+///
+/// 发生迁移时先将原文件备份为 `<文件名>.v<版本>.bak`，再把迁移后的内容写回原文件。
+/// 备份或写回失败时原文件保持不变，仍返回迁移后的配置，失败原因记录在警告中。
+pub(crate) fn read_config_file(
+    path: &Path,
+) -> Result<(Map<String, Value>, ConfigReport), ToolError> {
+    let (fields, mut report) = parse_and_migrate(path)?;
+    if let Some(version) = report.migrated_from {
+        let backup = backup_path(path, version);
+        match write_migrated(path, &fields, &backup) {
+            Ok(()) => report.backup = Some(backup),
+            Err(e) => report
+                .warnings
+                .push(format!("迁移后的配置未能写回配置文件: {}", e)),
+        }
+    }
+    Ok((fields, report))
+}
+
+/// 将原文件备份到 `backup` 后写回迁移后的配置，备份失败时不修改原文件
+fn write_migrated(
+    path: &Path,
+    fields: &Map<String, Value>,
+    backup: &Path,
+) -> Result<(), ToolError> {
+    fs::copy(path, backup)?;
+    save_config_file(path, fields)
+}
+
+/// 按扩展名对应的格式保存配置字段，尽量保留原文件中的注释，见 [`write_config_file`]
+pub fn save_config_file(path: &Path, fields: &Map<String, Value>) -> Result<(), ToolError> {
+    let previous = fs::read_to_string(path).ok();
+    let content = ConfigFormat::from_path(path).serialize(fields, previous.as_deref())?;
+    write_config_file(path, &content)
+}
+
+/// 将配置文件转换为另一种格式，格式由两个文件的扩展名决定
+///
+/// 旧版本的配置在转换时迁移到当前版本，原文件保持不变。
+pub fn convert_config_file(input: &Path, output: &Path) -> Result<ConfigReport, ToolError> {
+    let (fields, report) = parse_and_migrate(input)?;
+    save_config_file(output, &fields)?;
+    Ok(report)
+}
+
+/// 写入配置文件
+///
+/// 先写入同一目录下的临时文件，再重命名替换原文件，写入中途失败不会损坏原文件。
+/// 原文件依次轮换备份为 `<文件名>.bak.1`（最新）到 `<文件名>.bak.N`，最多保留
+/// [`CONFIG_BACKUPS`] 份。内容与原文件相同时不写入。
+pub fn write_config_file(path: &Path, content: &str) -> Result<(), ToolError> {
+    if fs::read_to_string(path).is_ok_and(|existing| existing == content) {
+        return Ok(());
+    }
+
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let temp = path.with_file_name(format!(".{}.tmp", name));
+    let result = (|| {
+        let mut file = fs::File::create(&temp)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        drop(file);
+
+        if path.exists() {
+            let backup = |index: usize| path.with_file_name(format!("{}.bak.{}", name, index));
+            for index in (1..CONFIG_BACKUPS).rev() {
+                if backup(index).exists() {
+                    fs::rename(backup(index), backup(index + 1))?;
+                }
+            }
+            fs::copy(path, backup(1))?;
+        }
+        fs::rename(&temp, path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result.map_err(ToolError::IoError)
+}
+
+/// 三方合并 JSON 值，见 [`AppConfig::merge`]，`None` 表示不存在的字段
+fn merge_values(
+    base: Option<&Value>,
+    local: Option<&Value>,
+    incoming: Option<&Value>,
+) -> Option<Value> {
+    match (base, local, incoming) {
+        (Some(Value::Object(base)), Some(Value::Object(local)), Some(Value::Object(incoming))) => {
+            let names: BTreeSet<&String> = base
+                .keys()
+                .chain(local.keys())
+                .chain(incoming.keys())
+                .collect();
+            let merged = names
+                .into_iter()
+                .filter_map(|name| {
+                    merge_values(base.get(name), local.get(name), incoming.get(name))
+                        .map(|value| (name.clone(), value))
+                })
+                .collect();
+            Some(Value::Object(merged))
+        }
+        _ if local != base => local.cloned(),
+        _ => incoming.cloned(),
+    }
+}
+
+/// 版本 0 是加入版本号之前的配置，结构与版本 1 相同，只需补上版本号
+///
+/// 不补全缺少的字段，缺少的字段由默认值或其他配置层提供。
+fn migrate_v0_to_v1(_fields: &mut Map<String, Value>) {}
+
+/// 在 `fields` 中查找 `known` 没有的字段，只比较对象类型的字段
+///
+/// 以映射表示的字段（例如 `tool_configs`）在默认配置中为空对象，不比较其内容。
+fn unknown_fields(
+    fields: &Map<String, Value>,
+    known: &Value,
+    prefix: &str,
+    warnings: &mut Vec<String>,
+) {
+    let Value::Object(known) = known else {
+        return;
+    };
+    for (name, value) in fields {
+        let path = format!("{}{}", prefix, name);
+        match known.get(name) {
+            None => warnings.push(format!("未知的配置项 '{}' 已被忽略", path)),
+            Some(known @ Value::Object(known_fields)) if !known_fields.is_empty() => {
+                if let Value::Object(fields) = value {
+                    unknown_fields(fields, known, &format!("{}.", path), warnings);
+                }
+            }
+            Some(_) => {}
+        }
+    }
+}
+
+/// 迁移前的备份文件路径，已存在时依次尝试 `.bak.1`、`.bak.2` 等
+fn backup_path(path: &Path, version: u32) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut backup = path.with_file_name(format!("{}.v{}.bak", name, version));
+    let mut index = 1;
+    while backup.exists() {
+        backup = path.with_file_name(format!("{}.v{}.bak.{}", name, version, index));
+        index += 1;
+    }
+    backup
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// 为测试创建空的临时目录
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("unitools-config-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn fields(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(fields) => fields,
+            _ => panic!("不是对象"),
+        }
+    }
+
+    #[test]
+    fn migrate_without_version() {
+        let mut config = fields(json!({ "theme": "dark" }));
+        let mut report = ConfigReport::default();
+        migrate(&mut config, &mut report).unwrap();
+
+        assert_eq!(report.migrated_from, Some(0));
+        assert_eq!(config["config_version"], json!(CONFIG_VERSION));
+        assert_eq!(config["theme"], json!("dark"));
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn migrate_current_version() {
+        let mut config = fields(json!({ "config_version": CONFIG_VERSION, "theme": "dark" }));
+        let original = config.clone();
+        let mut report = ConfigReport::default();
+        migrate(&mut config, &mut report).unwrap();
+
+        assert_eq!(report, ConfigReport::default());
+        assert_eq!(config, original);
+    }
+
+    #[test]
+    fn migrate_newer_version() {
+        let newer = CONFIG_VERSION + 1;
+        let mut config = fields(json!({ "config_version": newer, "theme": "dark" }));
+        let original = config.clone();
+        let mut report = ConfigReport::default();
+        migrate(&mut config, &mut report).unwrap();
+
+        assert_eq!(report.migrated_from, None);
+        assert_eq!(config, original);
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].contains(&newer.to_string()));
+    }
+
+    #[test]
+    fn migrate_invalid_version() {
+        for version in [json!("1"), json!(-1), json!(u64::from(u32::MAX) + 1)] {
+            let mut config = fields(json!({ "config_version": version }));
+            let error = migrate(&mut config, &mut ConfigReport::default()).unwrap_err();
+            assert!(matches!(error, ToolError::ParseError(_)), "{}", version);
+        }
+    }
+
+    #[test]
+    fn read_migrates_and_backs_up_file() {
+        let dir = temp_dir("read-migrates");
+        let path = dir.join("config.json");
+        let original = r#"{ "theme": "dark" }"#;
+        fs::write(&path, original).unwrap();
+
+        let (config, report) = read_config_file(&path).unwrap();
+        let backup = dir.join("config.json.v0.bak");
+        assert_eq!(report.migrated_from, Some(0));
+        assert_eq!(report.backup.as_deref(), Some(backup.as_path()));
+        assert_eq!(fs::read_to_string(&backup).unwrap(), original);
+        assert_eq!(config["config_version"], json!(CONFIG_VERSION));
+
+        // 写回的文件已是当前版本，再次读取时不再迁移
+        let (_, report) = read_config_file(&path).unwrap();
+        assert_eq!(report, ConfigReport::default());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn system_layer_migrates_in_memory() {
+        let dir = temp_dir("system-layer");
+        let path = dir.join("config.json");
+        let original = r#"{ "theme": "dark", "locked": ["theme"] }"#;
+        fs::write(&path, original).unwrap();
+
+        let (layer, report) =
+            ConfigLayer::from_file(ConfigSource::System(path.clone()), &path).unwrap();
+        assert_eq!(report.migrated_from, Some(0));
+        assert_eq!(report.backup, None);
+        assert_eq!(fs::read_to_string(&path).unwrap(), original);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        // 锁定的配置项不会被用户配置覆盖
+        let user = ConfigLayer::new(
+            ConfigSource::User(dir.join("user.json")),
+            fields(json!({ "theme": "light" })),
+        );
+        let config = LayeredConfig::resolve(vec![layer, user]);
+        assert_eq!(config.config().theme, Theme::Dark);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn invalid_environment_variable_is_reported() {
+        let layers = ConfigLayer::from_env([
+            ("UNITOOLS_THEME".to_string(), "dark".to_string()),
+            ("UNITOOLS_TOOL_CONFIGS".to_string(), "{oops".to_string()),
+            ("UNITOOLS_TOKEN".to_string(), "secret".to_string()),
+        ]);
+        let config = LayeredConfig::resolve(layers);
+
+        assert_eq!(config.config().theme, Theme::Dark);
+        assert!(config.config().tool_configs.is_empty());
+        assert_eq!(config.warnings().len(), 1, "{:?}", config.warnings());
+        assert!(config.warnings()[0].contains("UNITOOLS_TOOL_CONFIGS"));
+        assert!(!config.is_locked("tool_configs"));
+    }
+
+    #[test]
+    fn failed_backup_keeps_original_file() {
+        let dir = temp_dir("failed-backup");
+        let path = dir.join("config.json");
+        let original = r#"{ "theme": "dark" }"#;
+        fs::write(&path, original).unwrap();
+
+        let migrated = fields(json!({ "config_version": CONFIG_VERSION, "theme": "dark" }));
+        let backup = dir.join("missing").join("config.json.v0.bak");
+        assert!(write_migrated(&path, &migrated, &backup).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), original);
+        assert!(!backup.exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    /// 合并测试使用的基础配置，包含两个工具配置
+    fn base_config() -> AppConfig {
+        let mut config = AppConfig::default();
+        config.set_tool_config("text.case".to_string(), json!({ "mode": "upper" }));
+        config.set_tool_config("text.analyze".to_string(), json!({ "words": true }));
+        config
+    }
+
+    #[test]
+    fn merge_takes_disk_changes() {
+        let base = base_config();
+        let mut incoming = base.clone();
+        incoming.theme = Theme::Dark;
+        incoming.user.username = "disk".to_string();
+
+        let merged = AppConfig::merge(&base, &base, &incoming);
+        assert_eq!(merged.theme, Theme::Dark);
+        assert_eq!(merged.user.username, "disk");
+    }
+
+    #[test]
+    fn merge_keeps_local_changes() {
+        let base = base_config();
+        let mut local = base.clone();
+        local.language = "en-US".to_string();
+        local.enabled_plugins.push("local".to_string());
+
+        let merged = AppConfig::merge(&base, &local, &base);
+        assert_eq!(merged.language, "en-US");
+        assert_eq!(merged.enabled_plugins, ["local"]);
+    }
+
+    #[test]
+    fn merge_combines_different_keys() {
+        let base = base_config();
+        let mut local = base.clone();
+        local.theme = Theme::Light;
+        local.user.username = "local".to_string();
+        let mut incoming = base.clone();
+        incoming.language = "en-US".to_string();
+        incoming.user.working_directory = Some(PathBuf::from("/disk"));
+
+        // 同一对象中的不同设置逐项合并
+        let merged = AppConfig::merge(&base, &local, &incoming);
+        assert_eq!(merged.theme, Theme::Light);
+        assert_eq!(merged.language, "en-US");
+        assert_eq!(merged.user.username, "local");
+        assert_eq!(merged.user.working_directory, Some(PathBuf::from("/disk")));
+    }
+
+    #[test]
+    fn merge_prefers_local_when_both_change_key() {
+        let base = base_config();
+        let mut local = base.clone();
+        local.theme = Theme::Light;
+        local.set_tool_config("text.case".to_string(), json!({ "mode": "lower" }));
+        let mut incoming = base.clone();
+        incoming.theme = Theme::Dark;
+        incoming.set_tool_config("text.case".to_string(), json!({ "mode": "title" }));
+
+        let merged = AppConfig::merge(&base, &local, &incoming);
+        assert_eq!(merged.theme, Theme::Light);
+        assert_eq!(
+            merged.get_tool_config("text.case"),
+            Some(&json!({ "mode": "lower" }))
+        );
+    }
+
+    #[test]
+    fn merge_applies_deletions() {
+        let base = base_config();
+
+        // 本地删除、文件未修改
+        let mut local = base.clone();
+        local.tool_configs.remove("text.case");
+        let merged = AppConfig::merge(&base, &local, &base);
+        assert_eq!(merged.get_tool_config("text.case"), None);
+        assert!(merged.get_tool_config("text.analyze").is_some());
+
+        // 文件中删除、本地未修改
+        let mut incoming = base.clone();
+        incoming.tool_configs.remove("text.analyze");
+        let merged = AppConfig::merge(&base, &base, &incoming);
+        assert_eq!(merged.get_tool_config("text.analyze"), None);
+        assert!(merged.get_tool_config("text.case").is_some());
+
+        // 文件中删除、本地修改过时保留本地的修改
+        let mut local = base.clone();
+        local.set_tool_config("text.analyze".to_string(), json!({ "words": false }));
+        let merged = AppConfig::merge(&base, &local, &incoming);
+        assert_eq!(
+            merged.get_tool_config("text.analyze"),
+            Some(&json!({ "words": false }))
+        );
+    }
+
+    #[test]
+    fn backup_path_naming() {
+        let dir = temp_dir("backup-path");
+        let path = dir.join("config.toml");
+
+        let first = backup_path(&path, 0);
+        assert_eq!(first, dir.join("config.toml.v0.bak"));
+        fs::write(&first, "").unwrap();
+
+        let second = backup_path(&path, 0);
+        assert_eq!(second, dir.join("config.toml.v0.bak.1"));
+        fs::write(&second, "").unwrap();
+
+        assert_eq!(backup_path(&path, 0), dir.join("config.toml.v0.bak.2"));
+        // 不同版本的备份互不影响
+        assert_eq!(backup_path(&path, 1), dir.join("config.toml.v1.bak"));
+        let _ = fs::remove_dir_all(&dir);
     }
 }
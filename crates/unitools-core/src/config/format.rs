@@ -0,0 +1,395 @@
+//! 配置文件格式
+//!
+//! 配置文件的格式由扩展名决定：`.toml` 为 TOML，`.yaml` 和 `.yml` 为 YAML（需要启用
+//! `yaml` 特性），其余为 JSON。
+//!
+//! 写回手工编辑过的配置文件时尽量保留其中的注释：TOML 文件在原文档上只修改变化的值，
+//! 注释和排版都会保留；YAML 文件保留开头的注释和顶层配置项前的注释，嵌套配置项中的注释
+//! 会丢失；JSON 不支持注释。
+
+use crate::error::{InputPosition, ToolError};
+use serde_json::{Map, Value};
+use std::fmt::{self, Display};
+use std::path::Path;
+
+/// 配置文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl ConfigFormat {
+    /// 根据扩展名判断配置文件格式，无法识别的扩展名视为 JSON
+    pub fn from_path(path: &Path) -> Self {
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase());
+        match extension.as_deref() {
+            Some("toml") => ConfigFormat::Toml,
+            Some("yaml" | "yml") => ConfigFormat::Yaml,
+            _ => ConfigFormat::Json,
+        }
+    }
+
+    /// 解析配置文件内容，顶层必须是对象（表）
+    pub fn parse(self, content: &str) -> Result<Map<String, Value>, ToolError> {
+        let value: Value = match self {
+            ConfigFormat::Json => serde_json::from_str(content).map_err(|e| {
+                ToolError::ParseError(e.to_string()).with_position(InputPosition {
+                    line: e.line(),
+                    column: e.column(),
+                })
+            })?,
+            ConfigFormat::Toml => toml::from_str(content).map_err(|e| {
+                let error = ToolError::ParseError(e.message().to_string());
+                match e.span() {
+                    Some(span) => error
+                        .with_position(InputPosition::from_offset(content.as_bytes(), span.start)),
+                    None => error,
+                }
+            })?,
+            ConfigFormat::Yaml => parse_yaml(content)?,
+        };
+        match value {
+            Value::Object(fields) => Ok(fields),
+            // 空的 YAML 文件解析为 null
+            Value::Null if self == ConfigFormat::Yaml => Ok(Map::new()),
+            _ => Err(ToolError::ParseError(format!(
+                "配置文件的内容不是 {} 对象",
+                self
+            ))),
+        }
+    }
+
+    /// 将配置字段转为文本
+    ///
+    /// `previous` 是文件原来的内容，用于保留其中的注释，无法解析时忽略。TOML 没有空值，
+    /// 取值为 `null` 的字段不会写入，加载时使用默认值。
+    pub fn serialize(
+        self,
+        fields: &Map<String, Value>,
+        previous: Option<&str>,
+    ) -> Result<String, ToolError> {
+        match self {
+            ConfigFormat::Json => serde_json::to_string_pretty(fields)
+                .map_err(|e| ToolError::FormatError(e.to_string())),
+            ConfigFormat::Toml => serialize_toml(fields, previous),
+            ConfigFormat::Yaml => serialize_yaml(fields, previous),
+        }
+    }
+}
+
+impl Display for ConfigFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigFormat::Json => write!(f, "JSON"),
+            ConfigFormat::Toml => write!(f, "TOML"),
+            ConfigFormat::Yaml => write!(f, "YAML"),
+        }
+    }
+}
+
+/// 去掉取值为 `null` 的字段和数组元素
+fn without_nulls(value: &Value) -> Option<Value> {
+    match value {
+        Value::Null => None,
+        Value::Object(fields) => Some(Value::Object(
+            fields
+                .iter()
+                .filter_map(|(name, value)| without_nulls(value).map(|value| (name.clone(), value)))
+                .collect(),
+        )),
+        Value::Array(items) => Some(Value::Array(
+            items.iter().filter_map(without_nulls).collect(),
+        )),
+        value => Some(value.clone()),
+    }
+}
+
+fn serialize_toml(
+    fields: &Map<String, Value>,
+    previous: Option<&str>,
+) -> Result<String, ToolError> {
+    let value = without_nulls(&Value::Object(fields.clone())).unwrap_or_default();
+    let content = toml::to_string(&value).map_err(|e| ToolError::FormatError(e.to_string()))?;
+
+    let Some(previous) = previous else {
+        return Ok(content);
+    };
+    let (Ok(mut document), Ok(old_fields)) = (
+        previous.parse::<toml_edit::DocumentMut>(),
+        ConfigFormat::Toml.parse(previous),
+    ) else {
+        return Ok(content);
+    };
+    let updated = content
+        .parse::<toml_edit::DocumentMut>()
+        .map_err(|e| ToolError::FormatError(e.to_string()))?;
+    let Value::Object(new_fields) = value else {
+        return Ok(content);
+    };
+    update_table(
+        document.as_table_mut(),
+        updated.as_table(),
+        &old_fields,
+        &new_fields,
+    );
+    Ok(document.to_string())
+}
+
+/// 把 `table` 更新为 `updated` 的内容，只替换取值变化的项，保留其余项的注释和排版
+///
+/// `old` 和 `new` 是两个表对应的 JSON 值，用于判断取值是否变化。
+fn update_table(
+    table: &mut toml_edit::Table,
+    updated: &toml_edit::Table,
+    old: &Map<String, Value>,
+    new: &Map<String, Value>,
+) {
+    let removed: Vec<String> = table
+        .iter()
+        .map(|(name, _)| name.to_string())
+        .filter(|name| !updated.contains_key(name))
+        .collect();
+    for name in removed {
+        table.remove(&name);
+    }
+
+    for (name, item) in updated.iter() {
+        if table.contains_key(name) && old.get(name) == new.get(name) {
+            continue;
+        }
+        if let (Some(toml_edit::Item::Table(existing)), toml_edit::Item::Table(item)) =
+            (table.get_mut(name), item)
+            && let (Some(Value::Object(old)), Some(Value::Object(new))) =
+                (old.get(name), new.get(name))
+        {
+            update_table(existing, item, old, new);
+            continue;
+        }
+
+        let mut item = item.clone();
+        match table.get_mut(name) {
+            Some(existing) => {
+                // 保留行尾注释
+                if let (Some(old), Some(value)) = (existing.as_value(), item.as_value_mut()) {
+                    *value.decor_mut() = old.decor().clone();
+                }
+                // 只替换值，已有的键保持原来的位置和键前的注释
+                *existing = item;
+            }
+            None => {
+                table.insert(name, item);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "yaml")]
+fn parse_yaml(content: &str) -> Result<Value, ToolError> {
+    serde_yaml::from_str(content).map_err(|e| {
+        let error = ToolError::ParseError(e.to_string());
+        match e.location() {
+            Some(location) => error.with_position(InputPosition {
+                line: location.line(),
+                column: location.column(),
+            }),
+            None => error,
+        }
+    })
+}
+
+#[cfg(not(feature = "yaml"))]
+fn parse_yaml(_content: &str) -> Result<Value, ToolError> {
+    Err(yaml_unsupported())
+}
+
+#[cfg(feature = "yaml")]
+fn serialize_yaml(
+    fields: &Map<String, Value>,
+    previous: Option<&str>,
+) -> Result<String, ToolError> {
+    let content =
+        serde_yaml::to_string(fields).map_err(|e| ToolError::FormatError(e.to_string()))?;
+    let Some(previous) = previous else {
+        return Ok(content);
+    };
+
+    // 收集开头的注释和每个顶层配置项前的注释
+    let mut header = Vec::new();
+    let mut comments = std::collections::HashMap::new();
+    let mut pending = Vec::new();
+    let mut seen_key = false;
+    for line in previous.lines() {
+        if line.trim_start().starts_with('#') {
+            pending.push(line);
+        } else if line.trim().is_empty() {
+            // 与第一个配置项之间有空行的注释属于文件开头
+            if !seen_key && !pending.is_empty() {
+                header.append(&mut pending);
+            }
+        } else if let Some(name) = top_level_key(line) {
+            comments.insert(name, std::mem::take(&mut pending));
+            seen_key = true;
+        } else {
+            pending.clear();
+        }
+    }
+
+    let mut output = String::new();
+    if !header.is_empty() {
+        for line in header {
+            output.push_str(line);
+            output.push('\n');
+        }
+        output.push('\n');
+    }
+    for line in content.lines() {
+        if let Some(name) = top_level_key(line)
+            && let Some(lines) = comments.get(&name)
+        {
+            for comment in lines {
+                output.push_str(comment);
+                output.push('\n');
+            }
+        }
+        output.push_str(line);
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+#[cfg(not(feature = "yaml"))]
+fn serialize_yaml(
+    _fields: &Map<String, Value>,
+    _previous: Option<&str>,
+) -> Result<String, ToolError> {
+    Err(yaml_unsupported())
+}
+
+#[cfg(not(feature = "yaml"))]
+fn yaml_unsupported() -> ToolError {
+    ToolError::NotImplementedError("未启用 YAML 配置文件支持".to_string())
+}
+
+/// 没有缩进的 `key:` 行对应的顶层配置项名称
+#[cfg(feature = "yaml")]
+fn top_level_key(line: &str) -> Option<String> {
+    if line.starts_with([' ', '\t', '-', '#']) {
+        return None;
+    }
+    let (name, _) = line.split_once(':')?;
+    Some(name.trim().trim_matches(['"', '\'']).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// 解析 `previous`，用 `change` 修改后按原格式保存，返回保存的文本
+    fn save(
+        format: ConfigFormat,
+        previous: &str,
+        change: impl FnOnce(&mut Map<String, Value>),
+    ) -> String {
+        let mut fields = format.parse(previous).unwrap();
+        change(&mut fields);
+        let saved = format.serialize(&fields, Some(previous)).unwrap();
+        assert_eq!(format.parse(&saved).unwrap(), fields);
+        saved
+    }
+
+    #[test]
+    fn toml_keeps_comments_and_unrelated_keys() {
+        let previous = r#"# UniTools 配置
+config_version = 1
+
+# 界面主题
+theme = "dark" # 跟随系统时改为 system
+language = "zh-CN"
+
+# 个人设置
+[user]
+# 显示的名称
+username = "张三"
+working_directory = "/home/zhang"
+
+[tool_configs."text.case"]
+mode = "upper" # 默认转为大写
+"#;
+        let saved = save(ConfigFormat::Toml, previous, |fields| {
+            fields["theme"] = json!("light");
+            fields["user"]["username"] = json!("李四");
+            fields.remove("language");
+        });
+
+        for comment in [
+            "# UniTools 配置",
+            "# 界面主题",
+            "# 跟随系统时改为 system",
+            "# 个人设置",
+            "# 显示的名称",
+            "# 默认转为大写",
+        ] {
+            assert!(saved.contains(comment), "缺少 {}:\n{}", comment, saved);
+        }
+        // 修改的值保留原来的空行、键前的注释和行尾注释
+        assert!(
+            saved.contains(
+                "config_version = 1\n\n# 界面主题\ntheme = \"light\" # 跟随系统时改为 system\n"
+            ),
+            "{}",
+            saved
+        );
+        assert!(
+            saved.contains("# 显示的名称\nusername = \"李四\"\n"),
+            "{}",
+            saved
+        );
+        assert!(saved.contains(r#"working_directory = "/home/zhang""#));
+        assert!(saved.contains(r#"mode = "upper""#));
+        assert!(!saved.contains("language"));
+    }
+
+    #[test]
+    fn toml_without_changes_is_unchanged() {
+        let previous = "# 注释\ntheme = \"dark\" # 行尾注释\n\n[user]\nusername = \"张三\"\n";
+        assert_eq!(save(ConfigFormat::Toml, previous, |_| {}), previous);
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn yaml_keeps_comments_of_remaining_keys() {
+        let previous = r#"# UniTools 配置
+
+# 界面主题
+theme: dark
+# 界面语言
+language: zh-CN
+# 已启用的插件
+enabled_plugins:
+- slow
+user:
+  username: 张三
+"#;
+        let saved = save(ConfigFormat::Yaml, previous, |fields| {
+            fields["theme"] = json!("light");
+            fields.remove("language");
+        });
+
+        assert!(saved.starts_with("# UniTools 配置\n\n"), "{}", saved);
+        assert!(saved.contains("# 界面主题\ntheme: light\n"), "{}", saved);
+        assert!(
+            saved.contains("# 已启用的插件\nenabled_plugins:\n- slow\n"),
+            "{}",
+            saved
+        );
+        assert!(saved.contains("username: 张三"));
+        // 删除的配置项连同其前面的注释一起去掉
+        assert!(!saved.contains("language"));
+        assert!(!saved.contains("# 界面语言"));
+    }
+}
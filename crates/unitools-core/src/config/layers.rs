@@ -0,0 +1,504 @@
+//! 分层配置
+//!
+//! 最终生效的配置由多个配置层依次叠加而成，优先级从低到高为：
+//!
+//! 1. 内置默认值
+//! 2. 系统配置文件，由管理员维护，可以锁定配置项
+//! 3. 用户配置文件 `config.json`，也可以使用 TOML 或 YAML 格式，见 [`ConfigFormat`](super::ConfigFormat)
+//! 4. `UNITOOLS_*` 环境变量
+//! 5. 命令行参数 `--set key=value`
+//!
+//! 配置项用以 `.` 分隔的路径表示，例如 `theme`、`user.username`。环境变量名去掉前缀
+//! `UNITOOLS_` 后转为小写，`__` 表示下一级，例如 `UNITOOLS_USER__USERNAME` 对应
+//! `user.username`。
+//!
+//! 被锁定的配置项不会被之后的配置层覆盖，设置页面也不允许修改。系统配置文件通过
+//! 顶层的 `"locked": ["theme", ...]` 锁定配置项；环境变量和命令行参数只在本次运行中生效，
+//! 它们设置的配置项同样视为锁定，避免在设置页面的修改被覆盖或写入用户配置文件。
+
+use super::{AppConfig, ConfigReport, parse_and_migrate, read_config_file};
+use crate::error::ToolError;
+use crate::tr;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::path::{Path, PathBuf};
+
+/// 环境变量前缀
+pub const ENV_PREFIX: &str = "UNITOOLS_";
+
+/// 系统配置文件中列出锁定配置项的字段
+const LOCKED_FIELD: &str = "locked";
+
+/// 配置项路径
+type KeyPath = Vec<String>;
+
+/// 配置值的来源
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// 内置默认值
+    Default,
+    /// 系统配置文件
+    System(PathBuf),
+    /// 用户配置文件
+    User(PathBuf),
+    /// 环境变量
+    Environment(String),
+    /// 命令行参数
+    CommandLine,
+}
+
+impl Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            ConfigSource::Default => tr!("config-source-default"),
+            ConfigSource::System(path) => tr!("config-source-system", path = path.display()),
+            ConfigSource::User(path) => tr!("config-source-user", path = path.display()),
+            ConfigSource::Environment(name) => tr!("config-source-environment", name = name),
+            ConfigSource::CommandLine => tr!("config-source-command-line"),
+        };
+        f.write_str(&text)
+    }
+}
+
+/// 一个配置层
+#[derive(Debug, Clone)]
+pub struct ConfigLayer {
+    source: ConfigSource,
+    values: Map<String, Value>,
+    locked: Vec<KeyPath>,
+    /// 创建配置层时发现的问题，例如取值无效而被忽略的环境变量
+    warnings: Vec<String>,
+}
+
+impl ConfigLayer {
+    /// 创建配置层
+    pub fn new(source: ConfigSource, values: Map<String, Value>) -> Self {
+        Self {
+            source,
+            values,
+            locked: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// 从配置文件创建配置层，旧版本的文件会先迁移
+    ///
+    /// 只有用户配置文件会备份并写回迁移后的内容，其他配置文件（例如通常没有写入权限的
+    /// 系统配置文件）只在内存中迁移。系统配置文件顶层的 `locked` 字段列出本层锁定的配置项。
+    pub fn from_file(source: ConfigSource, path: &Path) -> Result<(Self, ConfigReport), ToolError> {
+        let (mut values, report) = match &source {
+            ConfigSource::User(_) => read_config_file(path)?,
+            _ => parse_and_migrate(path)?,
+        };
+        let locked = match &source {
+            ConfigSource::System(_) => values.remove(LOCKED_FIELD),
+            _ => None,
+        };
+        let locked = match locked {
+            Some(Value::Array(keys)) => keys
+                .iter()
+                .filter_map(Value::as_str)
+                .map(split_key)
+                .collect(),
+            _ => Vec::new(),
+        };
+        Ok((
+            Self {
+                source,
+                values,
+                locked,
+                warnings: Vec::new(),
+            },
+            report,
+        ))
+    }
+
+    /// 由 `UNITOOLS_*` 环境变量创建配置层，每个变量一层，设置的配置项均被锁定
+    ///
+    /// 不对应任何配置项的变量（例如 `UNITOOLS_TOKEN`）被忽略；取值无效的变量不设置配置项，
+    /// 问题记录在配置层的警告中，见 [`LayeredConfig::warnings`]。
+    pub fn from_env(vars: impl IntoIterator<Item = (String, String)>) -> Vec<Self> {
+        let defaults = default_values();
+        let mut vars: Vec<_> = vars
+            .into_iter()
+            .filter(|(name, _)| name.starts_with(ENV_PREFIX))
+            .collect();
+        vars.sort();
+
+        vars.into_iter()
+            .filter_map(|(name, raw)| {
+                let path: KeyPath = name[ENV_PREFIX.len()..]
+                    .to_lowercase()
+                    .split("__")
+                    .map(str::to_string)
+                    .collect();
+                defaults.contains_key(&path[0]).then_some(())?;
+                let mut layer = Self::new(ConfigSource::Environment(name.clone()), Map::new());
+                if let Err(e) = layer.set_path(&path, &raw, &defaults) {
+                    layer
+                        .warnings
+                        .push(format!("忽略环境变量 {}: {}", name, e.message()));
+                }
+                Some(layer.locked())
+            })
+            .collect()
+    }
+
+    /// 由命令行参数 `--set key=value` 创建配置层，设置的配置项均被锁定
+    pub fn from_args(overrides: &[(String, String)]) -> Result<Self, ToolError> {
+        let defaults = default_values();
+        let mut layer = Self::new(ConfigSource::CommandLine, Map::new());
+        for (key, raw) in overrides {
+            let path = split_key(key);
+            if !defaults.contains_key(&path[0]) {
+                return Err(ToolError::ParameterError(format!("未知的配置项: {}", key)));
+            }
+            layer.set_path(&path, raw, &defaults)?;
+        }
+        Ok(layer.locked())
+    }
+
+    /// 锁定本层设置的所有配置项
+    pub fn locked(mut self) -> Self {
+        let mut paths = Vec::new();
+        leaf_paths(&self.values, &mut Vec::new(), &mut paths);
+        self.locked.extend(paths);
+        self
+    }
+
+    /// 配置层的来源
+    pub fn source(&self) -> &ConfigSource {
+        &self.source
+    }
+
+    /// 设置配置项，按默认值的类型解析文本
+    ///
+    /// 字符串类型的配置项直接使用文本；列表类型的配置项可以用逗号分隔；
+    /// 其他类型按 JSON 解析。
+    fn set_path(
+        &mut self,
+        path: &[String],
+        raw: &str,
+        defaults: &Map<String, Value>,
+    ) -> Result<(), ToolError> {
+        let value = match value_at(defaults, path) {
+            Some(Value::String(_)) => Value::String(raw.to_string()),
+            Some(Value::Array(_)) if !raw.trim_start().starts_with('[') => Value::Array(
+                raw.split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(|item| Value::String(item.to_string()))
+                    .collect(),
+            ),
+            // 可选的配置项默认为 null，不是 JSON 时按字符串处理，例如路径
+            Some(Value::Null) | None => {
+                serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))
+            }
+            Some(_) => serde_json::from_str(raw).map_err(|e| {
+                ToolError::ParameterError(format!(
+                    "配置项 '{}' 的值无效: {}: {}",
+                    path.join("."),
+                    raw,
+                    e
+                ))
+            })?,
+        };
+
+        let (last, parents) = path.split_last().expect("配置项路径不能为空");
+        let mut target = &mut self.values;
+        for segment in parents {
+            let entry = target
+                .entry(segment.clone())
+                .or_insert_with(|| Value::Object(Map::new()));
+            if !entry.is_object() {
+                *entry = Value::Object(Map::new());
+            }
+            target = entry.as_object_mut().expect("刚刚确保为对象");
+        }
+        target.insert(last.clone(), value);
+        Ok(())
+    }
+}
+
+/// 叠加所有配置层后生效的配置
+#[derive(Debug, Clone)]
+pub struct LayeredConfig {
+    layers: Vec<ConfigLayer>,
+    config: AppConfig,
+    sources: BTreeMap<KeyPath, ConfigSource>,
+    locked: BTreeMap<KeyPath, ConfigSource>,
+    warnings: Vec<String>,
+}
+
+impl LayeredConfig {
+    /// 按优先级从低到高叠加配置层，内置默认值总是作为最底层
+    pub fn resolve(layers: Vec<ConfigLayer>) -> Self {
+        let warnings = layers
+            .iter()
+            .flat_map(|layer| layer.warnings.iter().cloned())
+            .collect();
+        let mut resolved = Self {
+            layers,
+            config: AppConfig::default(),
+            sources: BTreeMap::new(),
+            locked: BTreeMap::new(),
+            warnings,
+        };
+        let values = resolved.merge(|_| true);
+
+        let mut report = ConfigReport::default();
+        match AppConfig::from_fields(values, &mut report) {
+            Ok(config) => resolved.config = config,
+            Err(e) => report.warnings.push(e.to_string()),
+        }
+        resolved.warnings.extend(report.warnings);
+        resolved
+    }
+
+    /// 生效的配置
+    pub fn config(&self) -> &AppConfig {
+        &self.config
+    }
+
+    /// 创建配置层和叠加过程中发现的问题，例如取值无效的环境变量和被锁定而忽略的值
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// 配置项的来源，`key` 为以 `.` 分隔的路径
+    ///
+    /// 没有任何配置层设置的配置项来源为默认值。
+    pub fn source(&self, key: &str) -> &ConfigSource {
+        let path = split_key(key);
+        (0..=path.len())
+            .rev()
+            .find_map(|len| self.sources.get(&path[..len]))
+            .unwrap_or(&ConfigSource::Default)
+    }
+
+    /// 锁定配置项的配置层，未锁定时为 `None`
+    pub fn locked_by(&self, key: &str) -> Option<&ConfigSource> {
+        let path = split_key(key);
+        (0..=path.len())
+            .rev()
+            .find_map(|len| self.locked.get(&path[..len]))
+    }
+
+    /// 配置项是否被锁定
+    pub fn is_locked(&self, key: &str) -> bool {
+        self.locked_by(key).is_some()
+    }
+
+    /// 重新读取用户配置文件，其余配置层保持不变
+    ///
+    /// 配置文件无法解析时返回错误，当前的配置不受影响。
+    pub fn reload_user(&self, path: &Path) -> Result<(Self, ConfigReport), ToolError> {
+        let (layer, report) = ConfigLayer::from_file(ConfigSource::User(path.to_path_buf()), path)?;
+        let mut layers = self.layers.clone();
+        match layers.iter().position(is_user) {
+            Some(index) => layers[index] = layer,
+            None => {
+                // 用户配置位于系统配置之后、环境变量和命令行参数之前
+                let index = layers
+                    .iter()
+                    .position(|layer| {
+                        !matches!(
+                            layer.source,
+                            ConfigSource::Default | ConfigSource::System(_)
+                        )
+                    })
+                    .unwrap_or(layers.len());
+                layers.insert(index, layer);
+            }
+        }
+        Ok((Self::resolve(layers), report))
+    }
+
+    /// 不含用户配置文件时的配置，用于恢复默认设置
+    pub fn without_user(&self) -> AppConfig {
+        let values = self.clone().merge(|layer| !is_user(layer));
+        AppConfig::from_fields(values, &mut ConfigReport::default()).unwrap_or_default()
+    }
+
+    /// 计算要写入用户配置文件的内容
+    ///
+    /// 只保留与低于用户配置的层不同的值，使系统配置的修改能继续生效；
+    /// 被锁定的配置项保留用户配置文件中原有的值，不写入来自环境变量或命令行的值。
+    pub fn user_values(&self, config: &AppConfig) -> Map<String, Value> {
+        let Ok(Value::Object(current)) = serde_json::to_value(config) else {
+            return Map::new();
+        };
+        let base = self.clone().merge(|layer| {
+            matches!(
+                layer.source,
+                ConfigSource::Default | ConfigSource::System(_)
+            )
+        });
+
+        let mut values = Map::new();
+        diff(&current, &base, &mut values);
+
+        let original = self
+            .layers
+            .iter()
+            .find(|layer| is_user(layer))
+            .map(|layer| layer.values.clone())
+            .unwrap_or_default();
+        for path in self.locked.keys() {
+            remove_path(&mut values, path);
+            if let Some(value) = value_at(&original, path) {
+                insert_path(&mut values, path, value.clone());
+            }
+        }
+
+        values.insert(
+            "config_version".to_string(),
+            Value::from(config.config_version),
+        );
+        values
+    }
+
+    /// 依次叠加满足条件的配置层，记录每个配置项的来源和锁定情况
+    fn merge(&mut self, include: impl Fn(&ConfigLayer) -> bool) -> Map<String, Value> {
+        let mut values = default_values();
+        self.sources.clear();
+        self.locked.clear();
+
+        let layers = std::mem::take(&mut self.layers);
+        for layer in layers.iter().filter(|layer| include(layer)) {
+            self.merge_object(&mut values, &layer.values, &mut Vec::new(), &layer.source);
+            for path in &layer.locked {
+                self.locked
+                    .entry(path.clone())
+                    .or_insert_with(|| layer.source.clone());
+            }
+        }
+        self.layers = layers;
+        values
+    }
+
+    fn merge_object(
+        &mut self,
+        target: &mut Map<String, Value>,
+        values: &Map<String, Value>,
+        path: &mut KeyPath,
+        source: &ConfigSource,
+    ) {
+        for (name, value) in values {
+            path.push(name.clone());
+            // 只有系统配置的锁定会阻止之后的层，环境变量和命令行参数的锁定只影响设置页面和保存
+            let locked = (1..=path.len())
+                .find_map(|len| self.locked.get(&path[..len]))
+                .filter(|source| matches!(source, ConfigSource::System(_)));
+
+            if let Some(locked_by) = locked {
+                if target.get(name) != Some(value) {
+                    self.warnings.push(format!(
+                        "配置项 '{}' 已被 {} 锁定，忽略 {} 中的值",
+                        path.join("."),
+                        locked_by,
+                        source
+                    ));
+                }
+            } else if let (Some(Value::Object(target)), Value::Object(values)) =
+                (target.get_mut(name), value)
+            {
+                self.merge_object(target, values, path, source);
+            } else if target.get(name) != Some(value) {
+                // 与之前的值相同时保留原来的来源，使完整保存的配置文件不会遮盖系统配置的来源
+                self.sources.retain(|key, _| !key.starts_with(path));
+                self.sources.insert(path.clone(), source.clone());
+                target.insert(name.clone(), value.clone());
+            }
+            path.pop();
+        }
+    }
+}
+
+fn is_user(layer: &ConfigLayer) -> bool {
+    matches!(layer.source, ConfigSource::User(_))
+}
+
+/// 默认配置的字段
+fn default_values() -> Map<String, Value> {
+    match serde_json::to_value(AppConfig::default()) {
+        Ok(Value::Object(values)) => values,
+        _ => Map::new(),
+    }
+}
+
+/// 将以 `.` 分隔的配置项拆分为路径
+fn split_key(key: &str) -> KeyPath {
+    key.split('.').map(str::to_string).collect()
+}
+
+/// 列出所有叶子配置项，对象按下一级展开
+fn leaf_paths(values: &Map<String, Value>, path: &mut KeyPath, paths: &mut Vec<KeyPath>) {
+    for (name, value) in values {
+        path.push(name.clone());
+        match value {
+            Value::Object(children) if !children.is_empty() => leaf_paths(children, path, paths),
+            _ => paths.push(path.clone()),
+        }
+        path.pop();
+    }
+}
+
+fn value_at<'a>(values: &'a Map<String, Value>, path: &[String]) -> Option<&'a Value> {
+    let (first, rest) = path.split_first()?;
+    let value = values.get(first)?;
+    match rest {
+        [] => Some(value),
+        _ => value_at(value.as_object()?, rest),
+    }
+}
+
+fn insert_path(values: &mut Map<String, Value>, path: &[String], value: Value) {
+    let Some((first, rest)) = path.split_first() else {
+        return;
+    };
+    if rest.is_empty() {
+        values.insert(first.clone(), value);
+        return;
+    }
+    let entry = values
+        .entry(first.clone())
+        .or_insert_with(|| Value::Object(Map::new()));
+    if let Value::Object(children) = entry {
+        insert_path(children, rest, value);
+    }
+}
+
+fn remove_path(values: &mut Map<String, Value>, path: &[String]) {
+    let Some((first, rest)) = path.split_first() else {
+        return;
+    };
+    if rest.is_empty() {
+        values.remove(first);
+    } else if let Some(Value::Object(children)) = values.get_mut(first) {
+        remove_path(children, rest);
+        if children.is_empty() {
+            values.remove(first);
+        }
+    }
+}
+
+/// 记录 `current` 中与 `base` 不同的值，对象逐项比较
+fn diff(current: &Map<String, Value>, base: &Map<String, Value>, output: &mut Map<String, Value>) {
+    for (name, value) in current {
+        match (value, base.get(name)) {
+            (Value::Object(children), Some(Value::Object(base_children))) => {
+                let mut changed = Map::new();
+                diff(children, base_children, &mut changed);
+                if !changed.is_empty() {
+                    output.insert(name.clone(), Value::Object(changed));
+                }
+            }
+            (value, Some(base_value)) if value == base_value => {}
+            (value, _) => {
+                output.insert(name.clone(), value.clone());
+            }
+        }
+    }
+}
@@ -1,33 +1,368 @@
+use crate::tr;
+use serde::{Deserialize, Serialize};
 use std::io;
 use thiserror::Error;
 
-/// 工具箱错误类型
+/// 工具箱错误类型，显示的信息使用当前界面语言
+///
+/// 错误类别代码 [`ToolError::code`] 是稳定的，用于插件协议、HTTP 接口和命令行的 JSON 输出。
+/// 出错的参数、输入中的位置、修改建议和引起错误的原因通过 [`ToolError::with_parameter`]
+/// 等方法附加，附加后错误的类别和信息不变。
 #[derive(Error, Debug)]
 pub enum ToolError {
-    #[error("IO错误: {0}")]
+    #[error("{}", tr!("error-io", message = .0))]
     IoError(#[from] io::Error),
 
-    #[error("参数错误: {0}")]
+    #[error("{}", tr!("error-parameter", message = .0))]
     ParameterError(String),
 
-    #[error("格式错误: {0}")]
+    #[error("{}", tr!("error-format", message = .0))]
     FormatError(String),
 
-    #[error("网络错误: {0}")]
+    #[error("{}", tr!("error-network", message = .0))]
     NetworkError(String),
 
-    #[error("解析错误: {0}")]
+    #[error("{}", tr!("error-parse", message = .0))]
     ParseError(String),
 
-    #[error("未找到: {0}")]
+    #[error("{}", tr!("error-not-found", message = .0))]
     NotFoundError(String),
 
-    #[error("插件错误: {0}")]
+    #[error("{}", tr!("error-plugin", message = .0))]
     PluginError(String),
 
-    #[error("未实现: {0}")]
+    /// 流水线步骤失败，`step` 从 1 开始
+    ///
+    /// 只由错误代码和信息还原（见 [`ToolError::from_code`]）时步骤未知，`step` 为 0，
+    /// `source` 为包含原信息的 `Other`，显示的信息与原错误相同。
+    #[error("{}", pipeline_message(*.step, .tool, .source))]
+    PipelineError {
+        step: usize,
+        tool: String,
+        source: Box<ToolError>,
+    },
+
+    #[error("{}", tr!("error-cancelled"))]
+    Cancelled,
+
+    #[error("{}", tr!("error-permission-denied", message = .0))]
+    PermissionDenied(String),
+
+    #[error("{}", tr!("error-not-implemented", message = .0))]
     NotImplementedError(String),
 
-    #[error("其他错误: {0}")]
+    #[error("{}", tr!("error-other", message = .0))]
     Other(String),
+
+    /// 附带诊断信息的错误，显示的信息与 `error` 相同
+    #[error("{error}")]
+    Diagnostic {
+        error: Box<ToolError>,
+        diagnostic: Box<Diagnostic>,
+        #[source]
+        cause: Option<Box<ToolError>>,
+    },
+}
+
+/// 错误的诊断信息
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// 出错的参数名称
+    pub parameter: Option<String>,
+    /// 出错的输入位置
+    pub position: Option<InputPosition>,
+    /// 修改建议
+    pub hint: Option<String>,
+}
+
+/// 输入中的位置，行号和列号从 1 开始，列号按字符计数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputPosition {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl InputPosition {
+    /// 根据字节偏移量计算位置
+    pub fn from_offset(input: &[u8], offset: usize) -> Self {
+        let before = String::from_utf8_lossy(&input[..offset.min(input.len())]);
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+impl ToolError {
+    /// 返回错误类别代码，用于在插件协议中传递错误
+    pub fn code(&self) -> &'static str {
+        match self {
+            ToolError::IoError(_) => "io",
+            ToolError::ParameterError(_) => "parameter",
+            ToolError::FormatError(_) => "format",
+            ToolError::NetworkError(_) => "network",
+            ToolError::ParseError(_) => "parse",
+            ToolError::NotFoundError(_) => "not_found",
+            ToolError::PluginError(_) => "plugin",
+            ToolError::PipelineError { .. } => "pipeline",
+            ToolError::Cancelled => "cancelled",
+            ToolError::PermissionDenied(_) => "permission_denied",
+            ToolError::NotImplementedError(_) => "not_implemented",
+            ToolError::Other(_) => "other",
+            ToolError::Diagnostic { error, .. } => error.code(),
+        }
+    }
+
+    /// 返回不带类别前缀的错误信息
+    pub fn message(&self) -> String {
+        match self {
+            ToolError::IoError(e) => e.to_string(),
+            ToolError::ParameterError(message)
+            | ToolError::FormatError(message)
+            | ToolError::NetworkError(message)
+            | ToolError::ParseError(message)
+            | ToolError::NotFoundError(message)
+            | ToolError::PluginError(message)
+            | ToolError::PermissionDenied(message)
+            | ToolError::NotImplementedError(message)
+            | ToolError::Other(message) => message.clone(),
+            ToolError::PipelineError { .. } | ToolError::Cancelled => self.to_string(),
+            ToolError::Diagnostic { error, .. } => error.message(),
+        }
+    }
+
+    /// 根据错误类别代码和信息还原错误，未知代码归为 `Other`
+    pub fn from_code(code: &str, message: String) -> Self {
+        match code {
+            "io" => ToolError::IoError(io::Error::other(message)),
+            "parameter" => ToolError::ParameterError(message),
+            "format" => ToolError::FormatError(message),
+            "network" => ToolError::NetworkError(message),
+            "parse" => ToolError::ParseError(message),
+            "not_found" => ToolError::NotFoundError(message),
+            "plugin" => ToolError::PluginError(message),
+            "pipeline" => ToolError::PipelineError {
+                step: 0,
+                tool: String::new(),
+                source: Box::new(ToolError::Other(message)),
+            },
+            "cancelled" => ToolError::Cancelled,
+            "permission_denied" => ToolError::PermissionDenied(message),
+            "not_implemented" => ToolError::NotImplementedError(message),
+            _ => ToolError::Other(message),
+        }
+    }
+
+    /// 记录出错的参数名称
+    pub fn with_parameter(self, name: impl Into<String>) -> Self {
+        let name = name.into();
+        self.update(|diagnostic, _| diagnostic.parameter = Some(name))
+    }
+
+    /// 记录出错的输入位置
+    pub fn with_position(self, position: InputPosition) -> Self {
+        self.update(|diagnostic, _| diagnostic.position = Some(position))
+    }
+
+    /// 附加修改建议
+    pub fn with_hint(self, hint: impl Into<String>) -> Self {
+        let hint = hint.into();
+        self.update(|diagnostic, _| diagnostic.hint = Some(hint))
+    }
+
+    /// 记录引起此错误的原因，可以通过 [`std::error::Error::source`] 获取
+    pub fn with_source(self, source: ToolError) -> Self {
+        self.update(|_, cause| *cause = Some(Box::new(source)))
+    }
+
+    /// 出错的参数名称
+    pub fn parameter(&self) -> Option<&str> {
+        self.diagnostic()?.parameter.as_deref()
+    }
+
+    /// 出错的输入位置
+    pub fn position(&self) -> Option<InputPosition> {
+        self.diagnostic()?.position
+    }
+
+    /// 修改建议
+    pub fn hint(&self) -> Option<&str> {
+        self.diagnostic()?.hint.as_deref()
+    }
+
+    /// 引起此错误的工具错误：流水线中失败步骤的错误，或通过 [`ToolError::with_source`] 记录的原因
+    pub fn cause(&self) -> Option<&ToolError> {
+        match self {
+            ToolError::PipelineError { source, .. } => Some(source),
+            ToolError::Diagnostic { cause, .. } => cause.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// 完整的错误说明：错误信息之后依次列出输入位置、修改建议和各层原因，每项一行
+    pub fn describe(&self) -> String {
+        let mut lines = vec![self.to_string()];
+        let mut error = self;
+        loop {
+            if let Some(position) = error.position() {
+                lines.push(tr!(
+                    "error-position",
+                    line = position.line,
+                    column = position.column
+                ));
+            }
+            if let Some(hint) = error.hint() {
+                lines.push(tr!("error-hint", hint = hint));
+            }
+            let Some(cause) = error.cause() else {
+                break;
+            };
+            // 已经包含在上一层信息中的原因（例如流水线步骤的错误）不重复列出
+            let message = cause.to_string();
+            if !error.to_string().contains(&message) {
+                lines.push(tr!("error-caused-by", error = message));
+            }
+            error = cause;
+        }
+        lines.join("\n")
+    }
+
+    fn diagnostic(&self) -> Option<&Diagnostic> {
+        match self {
+            ToolError::Diagnostic { diagnostic, .. } => Some(diagnostic),
+            _ => None,
+        }
+    }
+
+    /// 修改诊断信息，不带诊断信息的错误先包装为 `Diagnostic`
+    fn update(self, f: impl FnOnce(&mut Diagnostic, &mut Option<Box<ToolError>>)) -> Self {
+        let (error, mut diagnostic, mut cause) = match self {
+            ToolError::Diagnostic {
+                error,
+                diagnostic,
+                cause,
+            } => (error, diagnostic, cause),
+            error => (Box::new(error), Box::default(), None),
+        };
+        f(&mut diagnostic, &mut cause);
+        ToolError::Diagnostic {
+            error,
+            diagnostic,
+            cause,
+        }
+    }
+}
+
+/// 流水线步骤失败时显示的信息，步骤未知时直接使用 `source` 的信息
+fn pipeline_message(step: usize, tool: &str, source: &ToolError) -> String {
+    if step == 0 {
+        source.message()
+    } else {
+        tr!("error-pipeline", step = step, tool = tool, source = source)
+    }
+}
+
+/// 在 `candidates` 中查找与 `name` 最接近的名称，用于提示拼写错误
+///
+/// 只返回编辑距离不超过名称长度三分之一（至少为 1）的候选项，比较时忽略大小写。
+pub fn closest_match<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+    let limit = (name.len() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| {
+            let chars: Vec<char> = candidate.to_lowercase().chars().collect();
+            (edit_distance(&name, &chars), candidate)
+        })
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// 两个字符序列之间的编辑距离
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::ErrorPayload;
+
+    /// 每种错误类别各一个错误
+    fn samples() -> Vec<ToolError> {
+        vec![
+            ToolError::IoError(io::Error::other("磁盘已满")),
+            ToolError::ParameterError("缺少参数".to_string()),
+            ToolError::FormatError("格式无效".to_string()),
+            ToolError::NetworkError("连接超时".to_string()),
+            ToolError::ParseError("意外的字符".to_string()),
+            ToolError::NotFoundError("工具不存在".to_string()),
+            ToolError::PluginError("插件崩溃".to_string()),
+            ToolError::PipelineError {
+                step: 2,
+                tool: "text.case".to_string(),
+                source: Box::new(ToolError::ParameterError("缺少参数".to_string())),
+            },
+            ToolError::Cancelled,
+            ToolError::PermissionDenied("不能读取文件".to_string()),
+            ToolError::NotImplementedError("尚不支持".to_string()),
+            ToolError::Other("未知错误".to_string()),
+        ]
+    }
+
+    #[test]
+    fn every_code_round_trips_through_from_code() {
+        for error in samples() {
+            let restored = ToolError::from_code(error.code(), error.message());
+            assert_eq!(restored.code(), error.code());
+            assert_eq!(restored.to_string(), error.to_string());
+        }
+    }
+
+    #[test]
+    fn every_code_round_trips_through_payload() {
+        for error in samples() {
+            let error = error.with_hint("换个参数");
+            let restored = ToolError::from(ErrorPayload::from(&error));
+            assert_eq!(restored.code(), error.code());
+            assert_eq!(restored.to_string(), error.to_string());
+            assert_eq!(restored.hint(), Some("换个参数"));
+        }
+    }
+
+    #[test]
+    fn pipeline_payload_keeps_failed_step() {
+        let error = ToolError::PipelineError {
+            step: 2,
+            tool: "text.case".to_string(),
+            source: Box::new(
+                ToolError::ParameterError("缺少参数".to_string()).with_parameter("mode"),
+            ),
+        };
+        let json = serde_json::to_string(&ErrorPayload::from(&error)).unwrap();
+        let restored = ToolError::from(serde_json::from_str::<ErrorPayload>(&json).unwrap());
+
+        let ToolError::PipelineError { step, tool, source } = &restored else {
+            panic!("unexpected error: {:?}", restored);
+        };
+        assert_eq!(*step, 2);
+        assert_eq!(tool, "text.case");
+        assert_eq!(source.code(), "parameter");
+        assert_eq!(source.parameter(), Some("mode"));
+        assert_eq!(restored.to_string(), error.to_string());
+    }
 }
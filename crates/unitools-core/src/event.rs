@@ -0,0 +1,166 @@
+//! 应用事件
+//!
+//! [`EventBus`] 把工具注册、工具执行、配置修改等事件分发给订阅者，审计、历史记录、
+//! 通知和统计等功能可以通过订阅事件实现，不需要修改界面代码。插件通过
+//! [`Plugin::subscribe`](crate::plugin::Plugin::subscribe) 订阅事件，卸载时自动取消订阅。
+//!
+//! 事件在发布者所在的线程上同步分发，工具执行事件可能来自后台线程。订阅者应尽快返回，
+//! 耗时的处理应交给其他线程。
+
+use crate::config::{AppConfig, Theme};
+use crate::protocol::ErrorPayload;
+use crate::tool::ToolResult;
+use serde::Serialize;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 应用事件
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Event {
+    /// 工具已注册，`plugin` 为提供工具的插件，内置工具为 `None`
+    ToolRegistered {
+        tool: String,
+        plugin: Option<String>,
+    },
+    /// 工具开始执行，`execution` 用于与结束事件对应
+    ToolExecutionStarted { tool: String, execution: u64 },
+    /// 工具执行结束，失败时 `error` 为错误
+    ToolExecutionFinished {
+        tool: String,
+        execution: u64,
+        duration: Duration,
+        error: Option<ErrorPayload>,
+    },
+    /// 配置已修改，`changed` 为取值发生变化的顶层配置项
+    ConfigChanged {
+        changed: Vec<String>,
+        config: Box<AppConfig>,
+    },
+    /// 界面主题已切换
+    ThemeChanged { theme: Theme },
+    /// 插件已卸载
+    PluginUnloaded { plugin: String },
+}
+
+/// 订阅标识，用于取消订阅
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+type Handler = Arc<dyn Fn(&Event) + Send + Sync>;
+
+#[derive(Default)]
+struct Subscribers {
+    next_id: u64,
+    handlers: Vec<(SubscriptionId, Handler)>,
+}
+
+/// 事件总线，克隆得到的总线共享同一组订阅者
+#[derive(Clone, Default)]
+pub struct EventBus {
+    subscribers: Arc<Mutex<Subscribers>>,
+}
+
+impl fmt::Debug for EventBus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventBus")
+            .field(
+                "subscribers",
+                &self.subscribers.lock().unwrap().handlers.len(),
+            )
+            .finish()
+    }
+}
+
+/// 工具执行序号
+static NEXT_EXECUTION: AtomicU64 = AtomicU64::new(1);
+
+impl EventBus {
+    /// 创建没有订阅者的事件总线
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 订阅所有事件，返回用于取消订阅的标识
+    pub fn subscribe(&self, handler: impl Fn(&Event) + Send + Sync + 'static) -> SubscriptionId {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        let id = SubscriptionId(subscribers.next_id);
+        subscribers.next_id += 1;
+        subscribers.handlers.push((id, Arc::new(handler)));
+        id
+    }
+
+    /// 取消订阅
+    pub fn unsubscribe(&self, id: SubscriptionId) {
+        self.subscribers
+            .lock()
+            .unwrap()
+            .handlers
+            .retain(|(subscription, _)| *subscription != id);
+    }
+
+    /// 发布事件
+    ///
+    /// 分发时不持有锁，订阅者可以在处理事件时发布新事件或修改订阅。
+    pub fn publish(&self, event: Event) {
+        let handlers: Vec<Handler> = self
+            .subscribers
+            .lock()
+            .unwrap()
+            .handlers
+            .iter()
+            .map(|(_, handler)| handler.clone())
+            .collect();
+        for handler in handlers {
+            handler(&event);
+        }
+    }
+
+    /// 执行工具并发布开始和结束事件
+    pub fn track_execution(&self, tool: &str, run: impl FnOnce() -> ToolResult) -> ToolResult {
+        let execution = self.execution_started(tool);
+        let started = Instant::now();
+        let result = run();
+        self.execution_finished(tool, execution, started, &result);
+        result
+    }
+
+    /// 异步执行工具并发布开始和结束事件
+    pub async fn track_execution_async(
+        &self,
+        tool: &str,
+        run: impl Future<Output = ToolResult>,
+    ) -> ToolResult {
+        let execution = self.execution_started(tool);
+        let started = Instant::now();
+        let result = run.await;
+        self.execution_finished(tool, execution, started, &result);
+        result
+    }
+
+    fn execution_started(&self, tool: &str) -> u64 {
+        let execution = NEXT_EXECUTION.fetch_add(1, Ordering::Relaxed);
+        self.publish(Event::ToolExecutionStarted {
+            tool: tool.to_string(),
+            execution,
+        });
+        execution
+    }
+
+    fn execution_finished(
+        &self,
+        tool: &str,
+        execution: u64,
+        started: Instant,
+        result: &ToolResult,
+    ) {
+        self.publish(Event::ToolExecutionFinished {
+            tool: tool.to_string(),
+            execution,
+            duration: started.elapsed(),
+            error: result.as_ref().err().map(ErrorPayload::from),
+        });
+    }
+}
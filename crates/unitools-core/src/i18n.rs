@@ -0,0 +1,154 @@
+//! 界面语言和本地化
+//!
+//! 文本使用 [Fluent](https://projectfluent.org/) 格式，每种语言一个资源集合。
+//! 核心库内置工具类别、错误信息等文本，应用可以通过 [`add_resource`] 追加自己的资源。
+//! 当前语言是进程级的设置，切换后新生成的文本立即使用新语言，无需重启。
+//!
+//! 查找文本时依次尝试当前语言和默认语言，都没有时返回文本标识本身。
+
+use crate::error::ToolError;
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::{LazyLock, RwLock};
+use unic_langid::LanguageIdentifier;
+
+/// 默认语言，其他语言缺少的文本使用默认语言
+pub const DEFAULT_LANGUAGE: &str = "zh-CN";
+
+/// 支持的语言，每项为语言标识和该语言下的语言名称
+pub const LANGUAGES: &[(&str, &str)] = &[("zh-CN", "简体中文"), ("en-US", "English")];
+
+/// 核心库内置的资源
+const CORE_RESOURCES: &[(&str, &str)] = &[
+    ("zh-CN", include_str!("../locales/zh-CN/core.ftl")),
+    ("en-US", include_str!("../locales/en-US/core.ftl")),
+];
+
+/// 本地化状态
+struct Localizer {
+    language: String,
+    bundles: HashMap<String, FluentBundle<FluentResource>>,
+}
+
+static LOCALIZER: LazyLock<RwLock<Localizer>> = LazyLock::new(|| {
+    let mut localizer = Localizer {
+        language: DEFAULT_LANGUAGE.to_string(),
+        bundles: HashMap::new(),
+    };
+    for (language, source) in CORE_RESOURCES {
+        localizer
+            .add_resource(language, source)
+            .expect("内置的本地化资源无效");
+    }
+    RwLock::new(localizer)
+});
+
+impl Localizer {
+    fn add_resource(&mut self, language: &str, source: &str) -> Result<(), ToolError> {
+        let langid: LanguageIdentifier = language.parse().map_err(|e| {
+            ToolError::ParameterError(format!("无效的语言标识 '{}': {}", language, e))
+        })?;
+        let resource = FluentResource::try_new(source.to_string()).map_err(|(_, errors)| {
+            let errors: Vec<String> = errors.iter().map(ToString::to_string).collect();
+            ToolError::ParseError(format!(
+                "{} 的本地化资源无效: {}",
+                language,
+                errors.join("; ")
+            ))
+        })?;
+
+        let bundle = self.bundles.entry(language.to_string()).or_insert_with(|| {
+            let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+            // 界面不支持 Unicode 方向隔离符，关闭以免显示为方框
+            bundle.set_use_isolating(false);
+            bundle
+        });
+        // 后加入的资源覆盖同名文本，便于应用替换内置文本
+        bundle.add_resource_overriding(resource);
+        Ok(())
+    }
+
+    fn format(&self, language: &str, id: &str, args: Option<&FluentArgs>) -> Option<String> {
+        let bundle = self.bundles.get(language)?;
+        let pattern = bundle.get_message(id)?.value()?;
+        let mut errors = Vec::new();
+        Some(
+            bundle
+                .format_pattern(pattern, args, &mut errors)
+                .into_owned(),
+        )
+    }
+
+    fn translate(&self, id: &str, args: Option<&FluentArgs>) -> String {
+        self.format(&self.language, id, args)
+            .or_else(|| self.format(DEFAULT_LANGUAGE, id, args))
+            .unwrap_or_else(|| id.to_string())
+    }
+}
+
+/// 返回当前语言
+pub fn language() -> String {
+    LOCALIZER.read().unwrap().language.clone()
+}
+
+/// 切换当前语言，不支持的语言返回错误
+pub fn set_language(language: &str) -> Result<(), ToolError> {
+    if !LANGUAGES.iter().any(|(id, _)| *id == language) {
+        return Err(ToolError::NotFoundError(format!(
+            "不支持的语言: {}",
+            language
+        )));
+    }
+    LOCALIZER.write().unwrap().language = language.to_string();
+    Ok(())
+}
+
+/// 追加指定语言的 Fluent 资源，同名文本覆盖已有的文本
+pub fn add_resource(language: &str, source: &str) -> Result<(), ToolError> {
+    LOCALIZER.write().unwrap().add_resource(language, source)
+}
+
+/// 查找当前语言下的文本
+pub fn tr(id: &str) -> String {
+    LOCALIZER.read().unwrap().translate(id, None)
+}
+
+/// 查找当前语言下的文本并填入参数，整数形式的参数按数字处理以便选择复数形式
+pub fn tr_args(id: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        let value = value.to_string();
+        let value = match value.parse::<i64>() {
+            Ok(number) if number.to_string() == value => FluentValue::from(number),
+            _ => FluentValue::from(value),
+        };
+        fluent_args.set(*name, value);
+    }
+    LOCALIZER.read().unwrap().translate(id, Some(&fluent_args))
+}
+
+/// 查找指定语言下的文本，不回退到默认语言
+pub fn lookup(language: &str, id: &str) -> Option<String> {
+    LOCALIZER.read().unwrap().format(language, id, None)
+}
+
+/// 查找当前语言下的文本
+///
+/// ```ignore
+/// tr!("error-cancelled");
+/// tr!("param-missing", name = spec.name);
+/// ```
+#[macro_export]
+macro_rules! tr {
+    ($id:expr) => {
+        $crate::i18n::tr($id)
+    };
+    ($id:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::tr_args(
+            $id,
+            &[$((stringify!($name), &$value as &dyn ::std::fmt::Display)),+],
+        )
+    };
+}
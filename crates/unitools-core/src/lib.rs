@@ -1,12 +1,35 @@
+pub mod batch;
+pub mod capability;
 pub mod config;
 pub mod error;
+pub mod event;
+pub mod i18n;
+pub mod native;
+pub mod pipeline;
 pub mod plugin;
+pub mod process;
+pub mod protocol;
+#[cfg(feature = "script")]
+pub mod script;
+#[cfg(feature = "async")]
+pub mod task;
 pub mod tool;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
+pub use batch::{Batch, BatchDestination, BatchSummary};
+pub use capability::{Capability, Permissions};
 pub use config::AppConfig;
 pub use error::ToolError;
+pub use event::{Event, EventBus};
+pub use pipeline::{Pipeline, PipelineDefinition};
 pub use plugin::{Plugin, PluginManager};
-pub use tool::{Tool, ToolCategory, ToolContext, ToolResult};
+#[cfg(feature = "async")]
+pub use task::ToolTask;
+pub use tool::{
+    CancellationToken, ParameterSpec, ParameterType, Progress, ProgressReporter, Tool,
+    ToolCategory, ToolConfig, ToolContext, ToolDescriptor, ToolOutput, ToolResult, ToolTranslation,
+};
 
 /// 版本信息
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,330 @@
+//! 原生动态库插件
+//!
+//! 插件以动态库（`.dll`/`.so`/`.dylib`）形式发布，并通过 [`declare_plugin!`] 宏导出
+//! 一个 `#[repr(C)]` 的 [`PluginDeclaration`]。宿主加载时先检查 ABI 版本，再检查插件
+//! 编译时使用的 `unitools-core` 版本是否与宿主一致，全部通过后才调用插件的构造函数。
+//!
+//! 由于插件与宿主之间通过 Rust 特质对象交互，插件必须使用与宿主相同版本的
+//! `unitools-core` 和相同的编译器构建。
+//!
+//! ```ignore
+//! use unitools_core::declare_plugin;
+//!
+//! declare_plugin!(MyPlugin::default);
+//! ```
+
+use crate::capability::Capability;
+use crate::error::ToolError;
+use crate::event::{EventBus, SubscriptionId};
+use crate::plugin::Plugin;
+use crate::tool::{
+    BoxFuture, ParameterSpec, Tool, ToolCategory, ToolContext, ToolResult, ToolTranslation,
+};
+use libloading::Library;
+use std::collections::HashMap;
+use std::ffi::{CStr, c_char, c_void};
+use std::path::Path;
+use std::sync::Arc;
+
+/// 插件 ABI 版本，`PluginDeclaration` 的布局或语义变化时递增
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// 插件导出的声明符号名称
+pub const PLUGIN_DECLARATION_SYMBOL: &[u8] = b"UNITOOLS_PLUGIN_DECLARATION\0";
+
+/// 以 C 字符串形式保存的核心库版本
+pub const CORE_VERSION: &CStr =
+    match CStr::from_bytes_with_nul(concat!(env!("CARGO_PKG_VERSION"), "\0").as_bytes()) {
+        Ok(version) => version,
+        Err(_) => panic!("无效的版本字符串"),
+    };
+
+/// 插件声明，由插件动态库导出
+#[repr(C)]
+pub struct PluginDeclaration {
+    /// 插件使用的 ABI 版本，必须位于第一个字段
+    pub abi_version: u32,
+    /// 插件编译时使用的 `unitools-core` 版本（以 NUL 结尾）
+    pub core_version: *const c_char,
+    /// 创建插件实例，返回 `Box<Box<dyn Plugin>>` 的裸指针
+    pub create: unsafe extern "C" fn() -> *mut c_void,
+}
+
+// 声明中只包含指向静态数据和函数的指针
+unsafe impl Sync for PluginDeclaration {}
+
+/// 在插件动态库中导出插件声明
+///
+/// 参数为返回插件实例的构造函数路径，例如 `MyPlugin::default`。
+#[macro_export]
+macro_rules! declare_plugin {
+    ($constructor:path) => {
+        #[doc(hidden)]
+        pub unsafe extern "C" fn __unitools_create_plugin() -> *mut ::std::ffi::c_void {
+            let plugin: ::std::boxed::Box<dyn $crate::plugin::Plugin> =
+                ::std::boxed::Box::new($constructor());
+            ::std::boxed::Box::into_raw(::std::boxed::Box::new(plugin)) as *mut ::std::ffi::c_void
+        }
+
+        #[unsafe(no_mangle)]
+        pub static UNITOOLS_PLUGIN_DECLARATION: $crate::native::PluginDeclaration =
+            $crate::native::PluginDeclaration {
+                abi_version: $crate::native::PLUGIN_ABI_VERSION,
+                core_version: $crate::native::CORE_VERSION.as_ptr(),
+                create: __unitools_create_plugin,
+            };
+    };
+}
+
+/// 从动态库加载的插件
+///
+/// 持有动态库的引用，保证插件及其工具在使用期间库不会被卸载。
+pub struct NativePlugin {
+    // 字段按声明顺序析构：插件必须先于动态库释放
+    plugin: Box<dyn Plugin>,
+    library: Arc<Library>,
+}
+
+impl NativePlugin {
+    /// 加载动态库插件
+    ///
+    /// # Safety
+    ///
+    /// 加载动态库会执行其中的初始化代码，调用方必须信任该插件文件。
+    pub unsafe fn load<P: AsRef<Path>>(path: P) -> Result<Self, ToolError> {
+        let path = path.as_ref();
+        let library = unsafe { Library::new(path) }.map_err(|e| {
+            ToolError::PluginError(format!("无法加载插件 '{}': {}", path.display(), e))
+        })?;
+
+        let declaration = unsafe {
+            let symbol = library
+                .get::<*const PluginDeclaration>(PLUGIN_DECLARATION_SYMBOL)
+                .map_err(|e| {
+                    ToolError::PluginError(format!(
+                        "'{}' 不是有效的插件，缺少插件声明: {}",
+                        path.display(),
+                        e
+                    ))
+                })?;
+            &**symbol
+        };
+
+        if declaration.abi_version != PLUGIN_ABI_VERSION {
+            return Err(ToolError::PluginError(format!(
+                "插件 '{}' 的 ABI 版本不兼容: 插件为 {}，宿主为 {}",
+                path.display(),
+                declaration.abi_version,
+                PLUGIN_ABI_VERSION
+            )));
+        }
+
+        let core_version = unsafe { CStr::from_ptr(declaration.core_version) };
+        if core_version != CORE_VERSION {
+            return Err(ToolError::PluginError(format!(
+                "插件 '{}' 的核心库版本不兼容: 插件为 {}，宿主为 {}",
+                path.display(),
+                core_version.to_string_lossy(),
+                crate::VERSION
+            )));
+        }
+
+        let raw = unsafe { (declaration.create)() };
+        if raw.is_null() {
+            return Err(ToolError::PluginError(format!(
+                "插件 '{}' 创建实例失败",
+                path.display()
+            )));
+        }
+        let plugin = unsafe { *Box::from_raw(raw as *mut Box<dyn Plugin>) };
+
+        Ok(Self {
+            plugin,
+            library: Arc::new(library),
+        })
+    }
+}
+
+impl Plugin for NativePlugin {
+    fn name(&self) -> &str {
+        self.plugin.name()
+    }
+
+    fn description(&self) -> &str {
+        self.plugin.description()
+    }
+
+    fn version(&self) -> &str {
+        self.plugin.version()
+    }
+
+    fn initialize(&mut self) -> Result<(), ToolError> {
+        self.plugin.initialize()
+    }
+
+    fn get_tools(&self) -> Vec<Arc<dyn Tool>> {
+        self.plugin
+            .get_tools()
+            .into_iter()
+            .map(|tool| {
+                Arc::new(NativeTool {
+                    tool,
+                    _library: self.library.clone(),
+                }) as Arc<dyn Tool>
+            })
+            .collect()
+    }
+
+    fn cleanup(&mut self) -> Result<(), ToolError> {
+        self.plugin.cleanup()
+    }
+
+    fn subscribe(&self, events: &EventBus) -> Vec<SubscriptionId> {
+        self.plugin.subscribe(events)
+    }
+
+    // 原生插件运行在应用进程中，不能由插件自己声明为沙箱插件
+    fn is_sandboxed(&self) -> bool {
+        false
+    }
+}
+
+/// 动态库插件提供的工具，持有动态库的引用
+#[derive(Clone)]
+struct NativeTool {
+    tool: Arc<dyn Tool>,
+    _library: Arc<Library>,
+}
+
+impl std::fmt::Debug for NativeTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.tool.fmt(f)
+    }
+}
+
+impl Tool for NativeTool {
+    fn id(&self) -> &str {
+        self.tool.id()
+    }
+
+    fn name(&self) -> &str {
+        self.tool.name()
+    }
+
+    fn description(&self) -> &str {
+        self.tool.description()
+    }
+
+    fn category(&self) -> ToolCategory {
+        self.tool.category()
+    }
+
+    fn execute(&self, ctx: &ToolContext) -> ToolResult {
+        self.tool.execute(ctx)
+    }
+
+    fn parameters(&self) -> Vec<ParameterSpec> {
+        self.tool.parameters()
+    }
+
+    fn parameter_descriptions(&self) -> Vec<(String, String)> {
+        self.tool.parameter_descriptions()
+    }
+
+    fn capabilities(&self) -> Vec<Capability> {
+        self.tool.capabilities()
+    }
+
+    fn config_schema(&self) -> Vec<ParameterSpec> {
+        self.tool.config_schema()
+    }
+
+    fn translation(&self, language: &str) -> ToolTranslation {
+        self.tool.translation(language)
+    }
+
+    fn validate_parameters(
+        &self,
+        parameters: &HashMap<String, String>,
+    ) -> Result<HashMap<String, String>, ToolError> {
+        self.tool.validate_parameters(parameters)
+    }
+
+    fn execute_async<'a>(&'a self, ctx: &'a ToolContext) -> BoxFuture<'a, ToolResult> {
+        self.tool.execute_async(ctx)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    /// 自定义参数校验和参数说明的工具
+    #[derive(Debug, Clone)]
+    struct CheckedTool;
+
+    impl Tool for CheckedTool {
+        fn id(&self) -> &str {
+            "test.checked"
+        }
+
+        fn name(&self) -> &str {
+            "checked"
+        }
+
+        fn description(&self) -> &str {
+            ""
+        }
+
+        fn category(&self) -> ToolCategory {
+            ToolCategory::Other
+        }
+
+        fn parameters(&self) -> Vec<ParameterSpec> {
+            vec![ParameterSpec::int("count", "次数")]
+        }
+
+        fn parameter_descriptions(&self) -> Vec<(String, String)> {
+            vec![("count".to_string(), "重复的次数".to_string())]
+        }
+
+        fn validate_parameters(
+            &self,
+            parameters: &HashMap<String, String>,
+        ) -> Result<HashMap<String, String>, ToolError> {
+            match parameters.get("count").map(String::as_str) {
+                Some("0") => Err(ToolError::ParameterError("次数不能为 0".to_string())),
+                _ => Ok(parameters.clone()),
+            }
+        }
+
+        fn execute(&self, ctx: &ToolContext) -> ToolResult {
+            Ok(Some(ctx.parameter("count").unwrap_or_default().into()))
+        }
+    }
+
+    fn native_tool() -> NativeTool {
+        NativeTool {
+            tool: Arc::new(CheckedTool),
+            _library: Arc::new(libloading::os::unix::Library::this().into()),
+        }
+    }
+
+    #[test]
+    fn forwards_parameter_descriptions() {
+        assert_eq!(
+            native_tool().parameter_descriptions(),
+            [("count".to_string(), "重复的次数".to_string())]
+        );
+    }
+
+    #[test]
+    fn forwards_parameter_validation() {
+        let ctx = ToolContext {
+            parameters: HashMap::from([("count".to_string(), "0".to_string())]),
+            ..Default::default()
+        };
+        let error = native_tool().validate_and_execute(ctx).unwrap_err();
+        assert_eq!(error.message(), "次数不能为 0");
+    }
+}
@@ -0,0 +1,336 @@
+use crate::error::ToolError;
+use crate::tool::{BoxFuture, Tool, ToolCategory, ToolContext, ToolResult};
+use crate::tr;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+/// 流水线步骤定义，可序列化保存到配置中
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PipelineStepDefinition {
+    /// 工具标识
+    pub tool: String,
+    /// 该步骤使用的参数
+    #[serde(default)]
+    pub parameters: HashMap<String, String>,
+}
+
+/// 流水线定义，可序列化保存到配置中
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PipelineDefinition {
+    /// 流水线名称
+    pub name: String,
+    /// 按执行顺序排列的步骤
+    #[serde(default)]
+    pub steps: Vec<PipelineStepDefinition>,
+}
+
+impl PipelineDefinition {
+    /// 从JSON字符串解析流水线定义
+    pub fn from_json(json: &str) -> Result<Self, ToolError> {
+        serde_json::from_str(json).map_err(|e| ToolError::ParseError(e.to_string()))
+    }
+
+    /// 序列化为JSON字符串
+    pub fn to_json(&self) -> Result<String, ToolError> {
+        serde_json::to_string_pretty(self).map_err(|e| ToolError::FormatError(e.to_string()))
+    }
+}
+
+/// 流水线步骤
+#[derive(Debug, Clone)]
+pub struct PipelineStep {
+    /// 执行该步骤的工具
+    pub tool: Arc<dyn Tool>,
+    /// 该步骤使用的参数
+    pub parameters: HashMap<String, String>,
+}
+
+/// 工具流水线，按顺序执行多个工具，前一步的输出作为下一步的输入
+///
+/// 流水线本身也实现了 `Tool`，因此可以像普通工具一样在后台执行。
+#[derive(Debug, Clone)]
+pub struct Pipeline {
+    id: String,
+    name: String,
+    description: String,
+    steps: Vec<PipelineStep>,
+}
+
+impl Pipeline {
+    /// 创建空的流水线
+    pub fn new(name: &str) -> Self {
+        let mut pipeline = Self {
+            id: format!("pipeline.{}", name),
+            name: name.to_string(),
+            description: String::new(),
+            steps: Vec::new(),
+        };
+        pipeline.update_description();
+        pipeline
+    }
+
+    /// 根据定义创建流水线，`resolve` 用于按标识查找工具
+    pub fn from_definition<F>(
+        definition: &PipelineDefinition,
+        resolve: F,
+    ) -> Result<Self, ToolError>
+    where
+        F: Fn(&str) -> Option<Arc<dyn Tool>>,
+    {
+        let mut pipeline = Self::new(&definition.name);
+        for (index, step) in definition.steps.iter().enumerate() {
+            let tool = resolve(&step.tool).ok_or_else(|| {
+                ToolError::NotFoundError(tr!(
+                    "pipeline-tool-missing",
+                    step = index + 1,
+                    tool = step.tool
+                ))
+            })?;
+            pipeline.add_step(tool, step.parameters.clone());
+        }
+        Ok(pipeline)
+    }
+
+    /// 转换为可保存的定义
+    pub fn to_definition(&self) -> PipelineDefinition {
+        PipelineDefinition {
+            name: self.name.clone(),
+            steps: self
+                .steps
+                .iter()
+                .map(|step| PipelineStepDefinition {
+                    tool: step.tool.id().to_string(),
+                    parameters: step.parameters.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// 添加步骤
+    pub fn add_step(&mut self, tool: Arc<dyn Tool>, parameters: HashMap<String, String>) {
+        self.steps.push(PipelineStep { tool, parameters });
+        self.update_description();
+    }
+
+    /// 获取所有步骤
+    pub fn steps(&self) -> &[PipelineStep] {
+        &self.steps
+    }
+
+    /// 按顺序执行所有步骤
+    ///
+    /// 每一步都经过参数校验和配置解析后异步执行，其进度按步骤数折算后汇报到整体进度；
+    /// 每一步的输出会转换为字节作为下一步的输入。任一步骤失败时返回
+    /// `ToolError::PipelineError`，其中包含失败的步骤序号和工具名称。
+    pub async fn run(&self, ctx: &ToolContext) -> ToolResult {
+        let total = self.steps.len();
+        let mut input_data = ctx.input_data.clone();
+        let mut output = None;
+
+        for (index, step) in self.steps.iter().enumerate() {
+            ctx.cancellation.check()?;
+            let label = tr!(
+                "pipeline-step-progress",
+                step = index + 1,
+                total = total,
+                tool = step.tool.name()
+            );
+            ctx.progress.report_count(index, total, &label);
+
+            let step_ctx = ToolContext {
+                input_data: input_data.take(),
+                parameters: step.parameters.clone(),
+                progress: ctx.progress.scoped(
+                    index as f32 / total as f32,
+                    (index + 1) as f32 / total as f32,
+                    &label,
+                ),
+                cancellation: ctx.cancellation.clone(),
+                permissions: ctx.permissions.clone(),
+                tool_configs: ctx.tool_configs.clone(),
+                events: ctx.events.clone(),
+                ..Default::default()
+            };
+
+            output = match step.tool.validate_and_execute_async(step_ctx).await {
+                Ok(output) => output,
+                Err(ToolError::Cancelled) => return Err(ToolError::Cancelled),
+                Err(error) => {
+                    return Err(ToolError::PipelineError {
+                        step: index + 1,
+                        tool: step.tool.name().to_string(),
+                        source: Box::new(error),
+                    });
+                }
+            };
+            input_data = output.as_ref().map(|o| o.to_bytes());
+        }
+
+        ctx.progress
+            .report_count(total, total, &tr!("pipeline-finished"));
+        Ok(output)
+    }
+
+    /// 根据步骤生成描述
+    fn update_description(&mut self) {
+        self.description = if self.steps.is_empty() {
+            tr!("pipeline-empty")
+        } else {
+            let names: Vec<&str> = self.steps.iter().map(|s| s.tool.name()).collect();
+            tr!("pipeline-description", tools = names.join(" → "))
+        };
+    }
+}
+
+impl Tool for Pipeline {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Other
+    }
+
+    fn execute(&self, ctx: &ToolContext) -> ToolResult {
+        block_on(self.run(ctx))
+    }
+
+    fn execute_async<'a>(&'a self, ctx: &'a ToolContext) -> BoxFuture<'a, ToolResult> {
+        Box::pin(self.run(ctx))
+    }
+}
+
+/// 在当前线程上执行 future 直到完成，用于同步执行流水线
+fn block_on<F: Future>(future: F) -> F::Output {
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tool::{ParameterSpec, Progress, ProgressReporter, ToolOutput};
+    use std::sync::Mutex;
+
+    /// 执行时汇报一半进度并记录整体进度的工具
+    #[derive(Debug, Clone)]
+    struct HalfwayTool {
+        observed: Arc<Mutex<Vec<Progress>>>,
+        overall: ProgressReporter,
+    }
+
+    impl Tool for HalfwayTool {
+        fn id(&self) -> &str {
+            "test.halfway"
+        }
+
+        fn name(&self) -> &str {
+            "halfway"
+        }
+
+        fn description(&self) -> &str {
+            ""
+        }
+
+        fn category(&self) -> ToolCategory {
+            ToolCategory::Other
+        }
+
+        fn parameters(&self) -> Vec<ParameterSpec> {
+            vec![ParameterSpec::int("count", "").with_range(Some(1.0), Some(3.0))]
+        }
+
+        fn execute(&self, ctx: &ToolContext) -> ToolResult {
+            ctx.progress.report(Some(0.5), "一半");
+            self.observed.lock().unwrap().push(self.overall.snapshot());
+            Ok(Some(ToolOutput::Text(
+                ctx.parameter("count").unwrap_or_default().to_string(),
+            )))
+        }
+    }
+
+    fn pipeline(counts: &[&str]) -> (Pipeline, ProgressReporter, Arc<Mutex<Vec<Progress>>>) {
+        let overall = ProgressReporter::new();
+        let observed = Arc::new(Mutex::new(Vec::new()));
+        let tool = Arc::new(HalfwayTool {
+            observed: observed.clone(),
+            overall: overall.clone(),
+        });
+        let mut pipeline = Pipeline::new("test");
+        for count in counts {
+            pipeline.add_step(
+                tool.clone(),
+                HashMap::from([("count".to_string(), count.to_string())]),
+            );
+        }
+        (pipeline, overall, observed)
+    }
+
+    #[test]
+    fn step_progress_is_scaled_into_overall_progress() {
+        let (pipeline, overall, observed) = pipeline(&["1", "2"]);
+        let ctx = ToolContext {
+            progress: overall.clone(),
+            ..Default::default()
+        };
+
+        let output = pipeline.execute(&ctx).unwrap();
+        assert_eq!(output, Some(ToolOutput::Text("2".to_string())));
+
+        let observed = observed.lock().unwrap();
+        assert_eq!(observed[0].fraction, Some(0.25));
+        assert_eq!(observed[0].message, "第 1/2 步: halfway: 一半");
+        assert_eq!(observed[1].fraction, Some(0.75));
+        assert_eq!(overall.snapshot().fraction, Some(1.0));
+    }
+
+    #[test]
+    fn step_parameters_are_validated() {
+        let (pipeline, _, observed) = pipeline(&["1", "9"]);
+
+        let error = pipeline.execute(&ToolContext::default()).unwrap_err();
+        let ToolError::PipelineError { step, source, .. } = &error else {
+            panic!("unexpected error: {:?}", error);
+        };
+        assert_eq!(*step, 2);
+        assert_eq!(source.parameter(), Some("count"));
+        assert_eq!(observed.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn cancelled_pipeline_stops_before_next_step() {
+        let (pipeline, _, observed) = pipeline(&["1", "2"]);
+        let ctx = ToolContext::default();
+        ctx.cancellation.cancel();
+
+        assert!(matches!(pipeline.execute(&ctx), Err(ToolError::Cancelled)));
+        assert!(observed.lock().unwrap().is_empty());
+    }
+}
@@ -1,8 +1,174 @@
+use crate::capability::{Capability, Permissions};
 use crate::error::ToolError;
-use crate::tool::Tool;
-use std::collections::HashMap;
+use crate::event::{Event, EventBus, SubscriptionId};
+use crate::tool::{
+    BoxFuture, ParameterSpec, Tool, ToolCategory, ToolContext, ToolResult, ToolTranslation,
+};
+use crate::tr;
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
+/// 插件清单文件名
+pub const MANIFEST_FILE_NAME: &str = "plugin.toml";
+
+/// 不在沙箱中运行的插件隐含需要的能力
+///
+/// 原生插件和外部进程插件拥有应用进程的全部权限，无论声明了哪些能力，都视为需要启动子进程，
+/// 必须经用户批准后才能加载。
+pub const UNSANDBOXED_CAPABILITY: Capability = Capability::ProcessSpawn;
+
+/// 插件清单，位于插件目录下的 `plugin.toml`
+///
+/// ```toml
+/// id = "text-extras"
+/// name = "文本扩展"
+/// version = "1.2.0"
+/// min_app_version = "0.1.0"
+/// authors = ["Alice"]
+/// entry = "text_extras.wasm"
+/// tools = ["text.reverse"]
+/// permissions = ["clipboard", "fs-read:/home/alice/Documents"]
+///
+/// [dependencies]
+/// text-core = "^1.0"
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginManifest {
+    /// 插件标识，其他插件通过它声明依赖
+    pub id: String,
+    /// 显示名称
+    #[serde(default)]
+    pub name: String,
+    /// 插件描述
+    #[serde(default)]
+    pub description: String,
+    /// 插件版本
+    pub version: Version,
+    /// 运行插件所需的最低应用版本
+    #[serde(default)]
+    pub min_app_version: Option<Version>,
+    /// 作者列表
+    #[serde(default)]
+    pub authors: Vec<String>,
+    /// 插件入口文件，相对于清单所在目录
+    pub entry: PathBuf,
+    /// 插件声明提供的工具标识
+    #[serde(default)]
+    pub tools: Vec<String>,
+    /// 依赖的其他插件及版本要求
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, VersionReq>,
+    /// 插件需要的权限
+    #[serde(default)]
+    pub permissions: Vec<Capability>,
+}
+
+impl PluginManifest {
+    /// 从 TOML 文本解析清单
+    pub fn from_toml(content: &str) -> Result<Self, ToolError> {
+        let manifest: Self = toml::from_str(content)
+            .map_err(|e| ToolError::PluginError(tr!("plugin-manifest-invalid", error = e)))?;
+
+        if manifest.id.trim().is_empty() {
+            return Err(ToolError::PluginError(tr!("plugin-manifest-missing-id")));
+        }
+        if manifest.dependencies.contains_key(&manifest.id) {
+            return Err(ToolError::PluginError(tr!(
+                "plugin-self-dependency",
+                id = manifest.id
+            )));
+        }
+
+        Ok(manifest)
+    }
+
+    /// 从文件加载清单
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ToolError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+        Self::from_toml(&content)
+            .map_err(|e| ToolError::PluginError(format!("{}: {}", path.display(), e.message())))
+    }
+
+    /// 返回显示名称，未设置时使用插件标识
+    pub fn display_name(&self) -> &str {
+        if self.name.is_empty() {
+            &self.id
+        } else {
+            &self.name
+        }
+    }
+
+    /// 检查插件是否支持当前应用版本
+    pub fn check_app_version(&self, app_version: &Version) -> Result<(), ToolError> {
+        match &self.min_app_version {
+            Some(min) if app_version < min => Err(ToolError::PluginError(tr!(
+                "plugin-app-version",
+                id = self.id,
+                required = min,
+                current = app_version
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// 加载插件前需要批准的能力：清单声明的权限，不在沙箱中运行的插件再加上
+    /// [`UNSANDBOXED_CAPABILITY`]
+    pub fn required_capabilities(&self, sandboxed: bool) -> Vec<Capability> {
+        let mut capabilities = self.permissions.clone();
+        if !sandboxed {
+            capabilities.push(UNSANDBOXED_CAPABILITY);
+        }
+        capabilities.sort();
+        capabilities.dedup();
+        capabilities
+    }
+
+    /// 检查插件实际提供的工具是否与清单声明一致，未声明工具时不做检查
+    fn check_tools(&self, tools: &[Arc<dyn Tool>]) -> Result<(), ToolError> {
+        if self.tools.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(tool) = tools
+            .iter()
+            .find(|t| !self.tools.iter().any(|id| id == t.id()))
+        {
+            return Err(ToolError::PluginError(tr!(
+                "plugin-undeclared-tool",
+                id = self.id,
+                tool = tool.id()
+            )));
+        }
+        if let Some(id) = self
+            .tools
+            .iter()
+            .find(|id| !tools.iter().any(|t| t.id() == *id))
+        {
+            return Err(ToolError::PluginError(tr!(
+                "plugin-missing-tool",
+                id = self.id,
+                tool = id
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// 插件加载计划
+#[derive(Debug, Default)]
+pub struct LoadPlan<'a> {
+    /// 按依赖关系排序后的清单，被依赖的插件在前
+    pub order: Vec<&'a PluginManifest>,
+    /// 无法加载的插件及原因
+    pub rejected: Vec<(String, ToolError)>,
+}
+
 /// 插件接口
 pub trait Plugin: Send + Sync {
     /// 返回插件名称
@@ -22,20 +188,65 @@ pub trait Plugin: Send + Sync {
 
     /// 清理插件资源
     fn cleanup(&mut self) -> Result<(), ToolError>;
+
+    /// 返回插件需要的能力，默认为其所有工具声明的能力
+    fn capabilities(&self) -> Vec<Capability> {
+        let mut capabilities: Vec<Capability> = self
+            .get_tools()
+            .iter()
+            .flat_map(|tool| tool.capabilities())
+            .collect();
+        capabilities.sort();
+        capabilities.dedup();
+        capabilities
+    }
+
+    /// 插件是否运行在沙箱中
+    ///
+    /// 不在沙箱中的插件无论声明了哪些能力，都需要批准 [`UNSANDBOXED_CAPABILITY`]。
+    fn is_sandboxed(&self) -> bool {
+        false
+    }
+
+    /// 检查插件是否仍能正常响应
+    fn health_check(&self) -> Result<(), ToolError> {
+        Ok(())
+    }
+
+    /// 重启插件，用于恢复未通过健康检查的插件
+    fn restart(&self) -> Result<(), ToolError> {
+        Ok(())
+    }
+
+    /// 订阅应用事件，在插件注册成功后调用，返回的订阅在卸载插件时自动取消
+    fn subscribe(&self, _events: &EventBus) -> Vec<SubscriptionId> {
+        Vec::new()
+    }
 }
 
 /// 插件管理器
 pub struct PluginManager {
+    app_version: Version,
     plugins: Mutex<HashMap<String, Box<dyn Plugin>>>,
+    manifests: Mutex<HashMap<String, PluginManifest>>,
+    /// 按标识索引的工具
     tools: Mutex<HashMap<String, Arc<dyn Tool>>>,
+    /// 每个插件注册的工具标识
+    plugin_tools: Mutex<HashMap<String, Vec<String>>>,
+    /// 应用内置工具的标识，插件工具不能使用
+    builtin_tools: Mutex<HashSet<String>>,
+    /// 用户已批准的插件能力
+    approved: Mutex<HashMap<String, Vec<Capability>>>,
+    /// 因权限未批准而未能注册的插件及其需要的能力
+    pending: Mutex<BTreeMap<String, Vec<Capability>>>,
+    /// 每个插件的事件订阅
+    subscriptions: Mutex<HashMap<String, Vec<SubscriptionId>>>,
+    events: EventBus,
 }
 
 impl Default for PluginManager {
     fn default() -> Self {
-        Self {
-            plugins: Mutex::new(HashMap::new()),
-            tools: Mutex::new(HashMap::new()),
-        }
+        Self::with_app_version(Version::parse(crate::VERSION).expect("核心库版本号无效"))
     }
 }
 
@@ -45,37 +256,377 @@ impl PluginManager {
         Self::default()
     }
 
+    /// 创建插件管理器，并指定用于检查插件兼容性的应用版本
+    pub fn with_app_version(app_version: Version) -> Self {
+        Self {
+            app_version,
+            plugins: Mutex::new(HashMap::new()),
+            manifests: Mutex::new(HashMap::new()),
+            tools: Mutex::new(HashMap::new()),
+            plugin_tools: Mutex::new(HashMap::new()),
+            builtin_tools: Mutex::new(HashSet::new()),
+            approved: Mutex::new(HashMap::new()),
+            pending: Mutex::new(BTreeMap::new()),
+            subscriptions: Mutex::new(HashMap::new()),
+            events: EventBus::new(),
+        }
+    }
+
+    /// 获取事件总线，插件工具的注册和插件的卸载在其上发布
+    pub fn events(&self) -> &EventBus {
+        &self.events
+    }
+
+    /// 登记应用内置工具的标识，之后注册的插件不能提供标识相同的工具
+    pub fn reserve_tool_ids<I>(&self, ids: I)
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.builtin_tools
+            .lock()
+            .unwrap()
+            .extend(ids.into_iter().map(Into::into));
+    }
+
     /// 注册插件
     pub fn register_plugin(&self, plugin: Box<dyn Plugin>) -> Result<(), ToolError> {
         let plugin_name = plugin.name().to_string();
+        self.register(plugin_name, plugin, None)
+    }
+
+    /// 以指定名称注册插件，权限批准和卸载都使用该名称
+    pub fn register_plugin_as(&self, name: &str, plugin: Box<dyn Plugin>) -> Result<(), ToolError> {
+        self.register(name.to_string(), plugin, None)
+    }
+
+    /// 注册带有清单的插件，注册前检查应用版本、依赖和声明的工具
+    pub fn register_plugin_with_manifest(
+        &self,
+        plugin: Box<dyn Plugin>,
+        manifest: PluginManifest,
+    ) -> Result<(), ToolError> {
+        manifest.check_app_version(&self.app_version)?;
+
+        {
+            let manifests = self.manifests.lock().unwrap();
+            for (dependency, requirement) in &manifest.dependencies {
+                match manifests.get(dependency) {
+                    Some(loaded) if requirement.matches(&loaded.version) => {}
+                    Some(loaded) => {
+                        return Err(ToolError::PluginError(tr!(
+                            "plugin-dependency-loaded-version",
+                            id = manifest.id,
+                            dependency = dependency,
+                            requirement = requirement,
+                            version = loaded.version
+                        )));
+                    }
+                    None => {
+                        return Err(ToolError::PluginError(tr!(
+                            "plugin-dependency-not-loaded",
+                            id = manifest.id,
+                            dependency = dependency
+                        )));
+                    }
+                }
+            }
+        }
+
+        manifest.check_tools(&plugin.get_tools())?;
+        self.register(manifest.id.clone(), plugin, Some(manifest))
+    }
+
+    /// 按依赖关系确定插件的加载顺序
+    ///
+    /// 不支持当前应用版本、依赖缺失或版本不符、存在循环依赖的插件会被拒绝，
+    /// 依赖被拒绝插件的插件也会一并被拒绝。已注册的插件可以作为依赖。
+    pub fn resolve_load_order<'a>(&self, manifests: &'a [PluginManifest]) -> LoadPlan<'a> {
+        let loaded = self.manifests.lock().unwrap();
+        let mut plan = LoadPlan::default();
+        let mut candidates: BTreeMap<&str, &PluginManifest> = BTreeMap::new();
+
+        for manifest in manifests {
+            let result = if loaded.contains_key(&manifest.id)
+                || candidates.contains_key(manifest.id.as_str())
+            {
+                Err(ToolError::PluginError(tr!(
+                    "plugin-duplicate-id",
+                    id = manifest.id
+                )))
+            } else {
+                manifest.check_app_version(&self.app_version)
+            };
+
+            match result {
+                Ok(()) => {
+                    candidates.insert(&manifest.id, manifest);
+                }
+                Err(error) => plan.rejected.push((manifest.id.clone(), error)),
+            }
+        }
+
+        // 反复剔除依赖无法满足的插件，直到没有变化
+        loop {
+            let mut rejected = Vec::new();
+            for manifest in candidates.values() {
+                for (dependency, requirement) in &manifest.dependencies {
+                    let version = candidates
+                        .get(dependency.as_str())
+                        .map(|m| &m.version)
+                        .or_else(|| loaded.get(dependency).map(|m| &m.version));
+
+                    let error = match version {
+                        Some(version) if requirement.matches(version) => continue,
+                        Some(version) => tr!(
+                            "plugin-dependency-version",
+                            id = manifest.id,
+                            dependency = dependency,
+                            requirement = requirement,
+                            version = version
+                        ),
+                        None => tr!(
+                            "plugin-dependency-missing",
+                            id = manifest.id,
+                            dependency = dependency
+                        ),
+                    };
+                    rejected.push((manifest.id.clone(), ToolError::PluginError(error)));
+                    break;
+                }
+            }
+
+            if rejected.is_empty() {
+                break;
+            }
+            for (id, error) in rejected {
+                candidates.remove(id.as_str());
+                plan.rejected.push((id, error));
+            }
+        }
+
+        // 拓扑排序，被依赖的插件先加载
+        let mut remaining = candidates;
+        let mut done: HashSet<&str> = HashSet::new();
+        loop {
+            let ready: Vec<&str> = remaining
+                .values()
+                .filter(|m| {
+                    m.dependencies
+                        .keys()
+                        .all(|d| done.contains(d.as_str()) || !remaining.contains_key(d.as_str()))
+                })
+                .map(|m| m.id.as_str())
+                .collect();
+
+            if ready.is_empty() {
+                break;
+            }
+            for id in ready {
+                if let Some(manifest) = remaining.remove(id) {
+                    plan.order.push(manifest);
+                    done.insert(id);
+                }
+            }
+        }
+
+        // 剩下的插件都处于循环依赖中，或依赖了循环中的插件
+        for manifest in remaining.values() {
+            let cycle = find_cycle(manifest, &remaining);
+            let message = if cycle.first() == Some(&manifest.id) {
+                tr!(
+                    "plugin-dependency-cycle",
+                    id = manifest.id,
+                    cycle = cycle.join(" -> ")
+                )
+            } else {
+                tr!(
+                    "plugin-dependency-in-cycle",
+                    id = manifest.id,
+                    cycle = cycle.join(" -> ")
+                )
+            };
+            plan.rejected
+                .push((manifest.id.clone(), ToolError::PluginError(message)));
+        }
+
+        plan
+    }
+
+    /// 设置用户已批准的插件能力，插件需要的能力全部获批后才能注册
+    ///
+    /// 同时清空待批准列表，之后注册失败的插件会重新加入。
+    pub fn set_approved_capabilities(&self, approved: HashMap<String, Vec<Capability>>) {
+        *self.approved.lock().unwrap() = approved;
+        self.pending.lock().unwrap().clear();
+    }
+
+    /// 检查插件需要的能力是否已全部获得用户批准
+    ///
+    /// 未全部批准时把插件加入待批准列表并返回 `ToolError::PermissionDenied`。加载时就会执行
+    /// 插件代码的插件（原生插件、外部进程插件）应在加载前按清单调用此方法。
+    pub fn check_approval(&self, name: &str, capabilities: &[Capability]) -> Result<(), ToolError> {
+        let approved = Permissions::Granted(
+            self.approved
+                .lock()
+                .unwrap()
+                .get(name)
+                .cloned()
+                .unwrap_or_default(),
+        );
+        let unapproved: Vec<String> = capabilities
+            .iter()
+            .filter(|c| !approved.allows(c))
+            .map(|c| c.to_string())
+            .collect();
+
+        let mut pending = self.pending.lock().unwrap();
+        if unapproved.is_empty() {
+            pending.remove(name);
+            return Ok(());
+        }
+        pending.insert(name.to_string(), capabilities.to_vec());
+        Err(ToolError::PermissionDenied(tr!(
+            "plugin-permissions-pending",
+            id = name,
+            capabilities = unapproved.join(", ")
+        )))
+    }
+
+    /// 插件需要批准的能力：工具声明的能力、清单声明的权限，以及不在沙箱中运行时隐含的能力
+    fn required_capabilities(
+        plugin: &dyn Plugin,
+        manifest: Option<&PluginManifest>,
+    ) -> Vec<Capability> {
+        let mut capabilities = plugin.capabilities();
+        if let Some(manifest) = manifest {
+            capabilities.extend(manifest.permissions.iter().cloned());
+        }
+        if !plugin.is_sandboxed() {
+            capabilities.push(UNSANDBOXED_CAPABILITY);
+        }
+        capabilities.sort();
+        capabilities.dedup();
+        capabilities
+    }
+
+    /// 获取因权限未批准而未能注册的插件及其需要的能力
+    pub fn pending_approvals(&self) -> Vec<(String, Vec<Capability>)> {
+        self.pending
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, capabilities)| (name.clone(), capabilities.clone()))
+            .collect()
+    }
+
+    /// 获取已注册插件的清单
+    pub fn manifest(&self, id: &str) -> Option<PluginManifest> {
+        self.manifests.lock().unwrap().get(id).cloned()
+    }
+
+    /// 注册插件及其工具
+    fn register(
+        &self,
+        plugin_name: String,
+        plugin: Box<dyn Plugin>,
+        manifest: Option<PluginManifest>,
+    ) -> Result<(), ToolError> {
         let mut plugins = self.plugins.lock().unwrap();
 
         if plugins.contains_key(&plugin_name) {
-            return Err(ToolError::PluginError(format!(
-                "插件 '{}' 已经注册",
-                plugin_name
+            return Err(ToolError::PluginError(tr!(
+                "plugin-already-registered",
+                id = plugin_name
             )));
         }
 
+        // 初始化前检查权限，未获批准的插件不会执行初始化代码
+        self.check_approval(
+            &plugin_name,
+            &Self::required_capabilities(&*plugin, manifest.as_ref()),
+        )?;
         let mut plugin = plugin;
         plugin.initialize()?;
 
-        // 注册工具
+        // 初始化后插件提供的工具可能变化，需要的能力必须仍然全部获得批准
+        let capabilities = Self::required_capabilities(&*plugin, manifest.as_ref());
+        if let Err(error) = self.check_approval(&plugin_name, &capabilities) {
+            let _ = plugin.cleanup();
+            return Err(error);
+        }
+
+        // 先检查所有工具，确认没有冲突后再一次性注册，失败时回滚初始化
         let tools = plugin.get_tools();
+        let builtin_tools = self.builtin_tools.lock().unwrap();
         let mut tools_map = self.tools.lock().unwrap();
+        let mut tool_ids: Vec<String> = Vec::with_capacity(tools.len());
 
-        for tool in tools {
-            let tool_name = tool.name().to_string();
-            if tools_map.contains_key(&tool_name) {
-                return Err(ToolError::PluginError(format!(
-                    "工具 '{}' 已经被其他插件注册",
-                    tool_name
-                )));
+        for tool in &tools {
+            let tool_id = tool.id().to_string();
+            let error = if builtin_tools.contains(&tool_id) {
+                Some(tr!("plugin-tool-builtin", tool = tool_id))
+            } else if tools_map.contains_key(&tool_id) {
+                Some(tr!("plugin-tool-registered", tool = tool_id))
+            } else if tool_ids.contains(&tool_id) {
+                Some(tr!(
+                    "plugin-tool-duplicate",
+                    id = plugin_name,
+                    tool = tool_id
+                ))
+            } else {
+                None
+            };
+
+            if let Some(error) = error {
+                if let Err(e) = plugin.cleanup() {
+                    return Err(ToolError::PluginError(tr!(
+                        "plugin-cleanup-failed",
+                        error = error,
+                        cleanup = e
+                    )));
+                }
+                return Err(ToolError::PluginError(error));
             }
-            tools_map.insert(tool_name, tool);
+            tool_ids.push(tool_id);
         }
 
-        plugins.insert(plugin_name, plugin);
+        // 插件工具只能使用已批准的能力
+        let permissions = Permissions::Granted(capabilities);
+        for (tool_id, tool) in tool_ids.iter().zip(tools) {
+            let tool = Arc::new(SandboxedTool {
+                tool,
+                permissions: permissions.clone(),
+            });
+            tools_map.insert(tool_id.clone(), tool);
+        }
+        self.plugin_tools
+            .lock()
+            .unwrap()
+            .insert(plugin_name.clone(), tool_ids.clone());
+        if let Some(manifest) = manifest {
+            self.manifests
+                .lock()
+                .unwrap()
+                .insert(plugin_name.clone(), manifest);
+        }
+        let subscriptions = plugin.subscribe(&self.events);
+        self.subscriptions
+            .lock()
+            .unwrap()
+            .insert(plugin_name.clone(), subscriptions);
+        plugins.insert(plugin_name.clone(), plugin);
+
+        // 释放锁后再发布事件，订阅者可以在处理事件时访问插件管理器
+        drop(builtin_tools);
+        drop(tools_map);
+        drop(plugins);
+        for tool in tool_ids {
+            self.events.publish(Event::ToolRegistered {
+                tool,
+                plugin: Some(plugin_name.clone()),
+            });
+        }
         Ok(())
     }
 
@@ -84,30 +635,643 @@ impl PluginManager {
         self.tools.lock().unwrap().values().cloned().collect()
     }
 
-    /// 通过名称获取工具
-    pub fn get_tool(&self, name: &str) -> Option<Arc<dyn Tool>> {
-        self.tools.lock().unwrap().get(name).cloned()
+    /// 通过标识获取工具
+    pub fn get_tool(&self, id: &str) -> Option<Arc<dyn Tool>> {
+        self.tools.lock().unwrap().get(id).cloned()
+    }
+
+    /// 对所有插件进行健康检查并重启无响应的插件，返回检查发现的问题
+    pub fn check_health(&self) -> Vec<String> {
+        let plugins = self.plugins.lock().unwrap();
+        let mut problems = Vec::new();
+
+        for (name, plugin) in plugins.iter() {
+            if let Err(error) = plugin.health_check() {
+                match plugin.restart() {
+                    Ok(()) => {
+                        problems.push(tr!("plugin-health-restarted", id = name, error = error))
+                    }
+                    Err(e) => problems.push(tr!(
+                        "plugin-health-restart-failed",
+                        id = name,
+                        error = error,
+                        restart = e
+                    )),
+                }
+            }
+        }
+
+        problems
     }
 
     /// 卸载插件
     pub fn unload_plugin(&self, name: &str) -> Result<(), ToolError> {
         let mut plugins = self.plugins.lock().unwrap();
 
-        if let Some(mut plugin) = plugins.remove(name) {
-            // 清理插件资源
-            plugin.cleanup()?;
+        // 仍被其他插件依赖时不能卸载
+        {
+            let mut manifests = self.manifests.lock().unwrap();
+            if let Some(dependent) = manifests
+                .values()
+                .find(|m| m.dependencies.contains_key(name))
+            {
+                return Err(ToolError::PluginError(tr!(
+                    "plugin-has-dependent",
+                    id = name,
+                    dependent = dependent.id
+                )));
+            }
+            manifests.remove(name);
+        }
+
+        let Some(mut plugin) = plugins.remove(name) else {
+            return Err(ToolError::NotFoundError(tr!("plugin-not-found", id = name)));
+        };
+
+        // 移除插件注册的工具
+        let tool_ids = self
+            .plugin_tools
+            .lock()
+            .unwrap()
+            .remove(name)
+            .unwrap_or_default();
+        let mut tools_map = self.tools.lock().unwrap();
+        for tool_id in tool_ids {
+            tools_map.remove(&tool_id);
+        }
+        drop(tools_map);
+        drop(plugins);
+
+        for subscription in self
+            .subscriptions
+            .lock()
+            .unwrap()
+            .remove(name)
+            .unwrap_or_default()
+        {
+            self.events.unsubscribe(subscription);
+        }
+
+        // 清理插件资源
+        let result = plugin.cleanup();
+        self.events.publish(Event::PluginUnloaded {
+            plugin: name.to_string(),
+        });
+        result
+    }
+}
+
+/// 限制插件工具权限的包装
+#[derive(Clone)]
+struct SandboxedTool {
+    tool: Arc<dyn Tool>,
+    permissions: Permissions,
+}
+
+impl std::fmt::Debug for SandboxedTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.tool.fmt(f)
+    }
+}
+
+impl Tool for SandboxedTool {
+    fn id(&self) -> &str {
+        self.tool.id()
+    }
+
+    fn name(&self) -> &str {
+        self.tool.name()
+    }
+
+    fn description(&self) -> &str {
+        self.tool.description()
+    }
+
+    fn category(&self) -> ToolCategory {
+        self.tool.category()
+    }
+
+    fn parameters(&self) -> Vec<ParameterSpec> {
+        self.tool.parameters()
+    }
 
-            // 移除相关工具
-            let tools = plugin.get_tools();
-            let mut tools_map = self.tools.lock().unwrap();
+    fn parameter_descriptions(&self) -> Vec<(String, String)> {
+        self.tool.parameter_descriptions()
+    }
+
+    fn capabilities(&self) -> Vec<Capability> {
+        self.tool.capabilities()
+    }
+
+    fn config_schema(&self) -> Vec<ParameterSpec> {
+        self.tool.config_schema()
+    }
+
+    fn translation(&self, language: &str) -> ToolTranslation {
+        self.tool.translation(language)
+    }
+
+    fn validate_parameters(
+        &self,
+        parameters: &HashMap<String, String>,
+    ) -> Result<HashMap<String, String>, ToolError> {
+        self.tool.validate_parameters(parameters)
+    }
+
+    fn execute(&self, ctx: &ToolContext) -> ToolResult {
+        self.tool
+            .execute(&ctx.with_permissions(self.permissions.clone()))
+    }
+
+    fn execute_async<'a>(&'a self, ctx: &'a ToolContext) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            let ctx = ctx.with_permissions(self.permissions.clone());
+            self.tool.execute_async(&ctx).await
+        })
+    }
+}
+
+/// 从指定插件出发沿依赖查找循环，返回循环路径
+fn find_cycle(start: &PluginManifest, remaining: &BTreeMap<&str, &PluginManifest>) -> Vec<String> {
+    let mut path: Vec<&str> = Vec::new();
+    let mut current = start;
+
+    loop {
+        if let Some(position) = path.iter().position(|id| *id == current.id) {
+            let mut cycle: Vec<String> = path[position..].iter().map(|id| id.to_string()).collect();
+            cycle.push(current.id.clone());
+            return cycle;
+        }
+        path.push(&current.id);
+
+        // 剩余插件至少依赖一个同样剩余的插件
+        match current
+            .dependencies
+            .keys()
+            .find_map(|d| remaining.get(d.as_str()))
+        {
+            Some(next) => current = next,
+            None => return path.iter().map(|id| id.to_string()).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    fn manifest(id: &str, version: &str, dependencies: &[(&str, &str)]) -> PluginManifest {
+        PluginManifest {
+            id: id.to_string(),
+            name: String::new(),
+            description: String::new(),
+            version: Version::parse(version).unwrap(),
+            min_app_version: None,
+            authors: Vec::new(),
+            entry: PathBuf::from(format!("{}.wasm", id)),
+            tools: Vec::new(),
+            dependencies: dependencies
+                .iter()
+                .map(|(id, requirement)| (id.to_string(), VersionReq::parse(requirement).unwrap()))
+                .collect(),
+            permissions: Vec::new(),
+        }
+    }
+
+    fn manager() -> PluginManager {
+        PluginManager::with_app_version(Version::new(1, 0, 0))
+    }
+
+    fn order(plan: &LoadPlan) -> Vec<String> {
+        plan.order.iter().map(|m| m.id.clone()).collect()
+    }
+
+    fn rejection(plan: &LoadPlan, id: &str) -> String {
+        plan.rejected
+            .iter()
+            .find(|(rejected, _)| rejected == id)
+            .map(|(_, error)| error.message())
+            .unwrap_or_else(|| panic!("'{}' 未被拒绝", id))
+    }
+
+    #[test]
+    fn dependencies_load_first() {
+        let manifests = [
+            manifest("app", "1.0.0", &[("ui", "^2"), ("core", "^1.2")]),
+            manifest("ui", "2.1.0", &[("core", ">=1.0, <2.0")]),
+            manifest("core", "1.3.5", &[]),
+        ];
+
+        let plan = manager().resolve_load_order(&manifests);
+        assert!(plan.rejected.is_empty());
+        assert_eq!(order(&plan), ["core", "ui", "app"]);
+    }
+
+    #[test]
+    fn semver_requirements_are_respected() {
+        let manifests = [
+            manifest("caret", "1.0.0", &[("core", "^1.2")]),
+            manifest("tilde", "1.0.0", &[("core", "~1.3.0")]),
+            manifest("exact", "1.0.0", &[("core", "=1.3.4")]),
+            manifest("core", "1.3.5", &[]),
+        ];
+
+        let plan = manager().resolve_load_order(&manifests);
+        assert_eq!(order(&plan), ["core", "caret", "tilde"]);
+        assert!(rejection(&plan, "exact").contains("1.3.5"));
+    }
+
+    #[test]
+    fn prerelease_does_not_match_plain_requirement() {
+        let manifests = [
+            manifest("app", "1.0.0", &[("core", "^1.0")]),
+            manifest("core", "1.1.0-beta.1", &[]),
+        ];
+
+        let plan = manager().resolve_load_order(&manifests);
+        assert_eq!(order(&plan), ["core"]);
+        assert!(rejection(&plan, "app").contains("'core'"));
+    }
+
+    #[test]
+    fn missing_dependency_is_rejected() {
+        let manifests = [manifest("app", "1.0.0", &[("core", "^1")])];
+
+        let plan = manager().resolve_load_order(&manifests);
+        assert!(plan.order.is_empty());
+        let error = rejection(&plan, "app");
+        assert!(error.contains("'core'"), "{}", error);
+    }
+
+    #[test]
+    fn version_mismatch_is_rejected() {
+        let manifests = [
+            manifest("app", "1.0.0", &[("core", "^2")]),
+            manifest("core", "1.9.0", &[]),
+        ];
+
+        let plan = manager().resolve_load_order(&manifests);
+        assert_eq!(order(&plan), ["core"]);
+        let error = rejection(&plan, "app");
+        assert!(error.contains("^2") && error.contains("1.9.0"), "{}", error);
+    }
+
+    #[test]
+    fn rejection_cascades_to_dependents() {
+        let manifests = [
+            manifest("top", "1.0.0", &[("middle", "*")]),
+            manifest("middle", "1.0.0", &[("bottom", "^2")]),
+            manifest("bottom", "1.0.0", &[]),
+        ];
+
+        let plan = manager().resolve_load_order(&manifests);
+        assert_eq!(order(&plan), ["bottom"]);
+        rejection(&plan, "middle");
+        rejection(&plan, "top");
+    }
+
+    #[test]
+    fn registered_plugins_satisfy_dependencies() {
+        let manager = manager();
+        manager
+            .manifests
+            .lock()
+            .unwrap()
+            .insert("core".to_string(), manifest("core", "1.4.0", &[]));
+        let manifests = [
+            manifest("app", "1.0.0", &[("core", "^1.4")]),
+            manifest("old", "1.0.0", &[("core", "<1.4")]),
+            manifest("core", "1.5.0", &[]),
+        ];
+
+        let plan = manager.resolve_load_order(&manifests);
+        assert_eq!(order(&plan), ["app"]);
+        assert!(rejection(&plan, "core").contains("重复"));
+        assert!(rejection(&plan, "old").contains("1.4.0"));
+    }
+
+    #[test]
+    fn duplicate_ids_are_rejected() {
+        let manifests = [
+            manifest("core", "1.0.0", &[]),
+            manifest("core", "2.0.0", &[]),
+        ];
+
+        let plan = manager().resolve_load_order(&manifests);
+        assert_eq!(plan.order.len(), 1);
+        assert_eq!(plan.order[0].version, Version::new(1, 0, 0));
+        assert!(rejection(&plan, "core").contains("重复"));
+    }
+
+    #[test]
+    fn app_version_is_checked() {
+        let mut too_new = manifest("too-new", "1.0.0", &[]);
+        too_new.min_app_version = Some(Version::new(1, 1, 0));
+        let mut current = manifest("current", "1.0.0", &[]);
+        current.min_app_version = Some(Version::new(1, 0, 0));
+
+        let manifests = [too_new, current];
+        let plan = manager().resolve_load_order(&manifests);
+        assert_eq!(order(&plan), ["current"]);
+        assert!(rejection(&plan, "too-new").contains("1.1.0"));
+    }
 
-            for tool in tools {
-                tools_map.remove(tool.name());
+    #[test]
+    fn cycles_are_reported_with_their_path() {
+        let manifests = [
+            manifest("a", "1.0.0", &[("b", "*")]),
+            manifest("b", "1.0.0", &[("c", "*")]),
+            manifest("c", "1.0.0", &[("a", "*")]),
+            manifest("outside", "1.0.0", &[("a", "*")]),
+            manifest("free", "1.0.0", &[]),
+        ];
+
+        let plan = manager().resolve_load_order(&manifests);
+        assert_eq!(order(&plan), ["free"]);
+        assert!(rejection(&plan, "a").contains("a -> b -> c -> a"));
+        assert!(rejection(&plan, "b").contains("b -> c -> a -> b"));
+        // 依赖循环中插件的插件不在循环中，报告它所依赖的循环
+        let error = rejection(&plan, "outside");
+        assert!(error.contains("依赖的插件存在循环依赖"), "{}", error);
+        assert!(error.contains("a -> b -> c -> a"), "{}", error);
+    }
+
+    #[test]
+    fn find_cycle_returns_the_cycle_only() {
+        let a = manifest("a", "1.0.0", &[("b", "*")]);
+        let b = manifest("b", "1.0.0", &[("a", "*")]);
+        let start = manifest("start", "1.0.0", &[("a", "*")]);
+        let remaining = BTreeMap::from([("a", &a), ("b", &b), ("start", &start)]);
+
+        assert_eq!(find_cycle(&a, &remaining), ["a", "b", "a"]);
+        // 起点不在循环中时，路径从起点开始，到重复出现的插件为止
+        assert_eq!(find_cycle(&start, &remaining), ["a", "b", "a"]);
+    }
+
+    #[test]
+    fn self_dependency_is_rejected_by_manifest() {
+        let error = PluginManifest::from_toml(
+            r#"
+            id = "loop"
+            version = "1.0.0"
+            entry = "loop.wasm"
+
+            [dependencies]
+            loop = "*"
+            "#,
+        )
+        .unwrap_err();
+        assert!(error.message().contains("自身"));
+    }
+
+    #[derive(Debug, Clone)]
+    struct TestTool(String);
+
+    impl Tool for TestTool {
+        fn id(&self) -> &str {
+            &self.0
+        }
+
+        fn name(&self) -> &str {
+            &self.0
+        }
+
+        fn description(&self) -> &str {
+            ""
+        }
+
+        fn category(&self) -> ToolCategory {
+            ToolCategory::Other
+        }
+
+        fn execute(&self, _ctx: &ToolContext) -> ToolResult {
+            Ok(None)
+        }
+    }
+
+    /// 记录是否被初始化的插件
+    struct TestPlugin {
+        name: String,
+        sandboxed: bool,
+        capabilities: Vec<Capability>,
+        tools: Vec<String>,
+        initialized: Arc<AtomicBool>,
+    }
+
+    impl TestPlugin {
+        fn new(name: &str, sandboxed: bool, capabilities: &[Capability]) -> Self {
+            Self {
+                name: name.to_string(),
+                sandboxed,
+                capabilities: capabilities.to_vec(),
+                tools: Vec::new(),
+                initialized: Arc::new(AtomicBool::new(false)),
             }
+        }
 
+        fn with_tools(mut self, tools: &[&str]) -> Self {
+            self.tools = tools.iter().map(|id| id.to_string()).collect();
+            self
+        }
+    }
+
+    impl Plugin for TestPlugin {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn description(&self) -> &str {
+            ""
+        }
+
+        fn version(&self) -> &str {
+            "1.0.0"
+        }
+
+        fn initialize(&mut self) -> Result<(), ToolError> {
+            self.initialized.store(true, Ordering::SeqCst);
             Ok(())
-        } else {
-            Err(ToolError::NotFoundError(format!("未找到插件: '{}'", name)))
         }
+
+        fn get_tools(&self) -> Vec<Arc<dyn Tool>> {
+            self.tools
+                .iter()
+                .map(|id| Arc::new(TestTool(id.clone())) as Arc<dyn Tool>)
+                .collect()
+        }
+
+        fn cleanup(&mut self) -> Result<(), ToolError> {
+            Ok(())
+        }
+
+        fn capabilities(&self) -> Vec<Capability> {
+            self.capabilities.clone()
+        }
+
+        fn is_sandboxed(&self) -> bool {
+            self.sandboxed
+        }
+    }
+
+    #[test]
+    fn unapproved_plugin_is_never_initialized() {
+        let manager = manager();
+        let plugin = TestPlugin::new("native", false, &[]);
+        let initialized = plugin.initialized.clone();
+
+        let error = manager.register_plugin(Box::new(plugin)).unwrap_err();
+        assert_eq!(error.code(), "permission_denied");
+        assert!(!initialized.load(Ordering::SeqCst));
+        assert_eq!(
+            manager.pending_approvals(),
+            [("native".to_string(), vec![UNSANDBOXED_CAPABILITY])]
+        );
+    }
+
+    #[test]
+    fn unsandboxed_plugin_without_capabilities_requires_approval() {
+        let manager = manager();
+        manager.set_approved_capabilities(HashMap::from([(
+            "native".to_string(),
+            vec![Capability::Network],
+        )]));
+        let plugin = TestPlugin::new("native", false, &[Capability::Network]);
+        let initialized = plugin.initialized.clone();
+        assert!(manager.register_plugin(Box::new(plugin)).is_err());
+        assert!(!initialized.load(Ordering::SeqCst));
+
+        manager.set_approved_capabilities(HashMap::from([(
+            "native".to_string(),
+            vec![Capability::Network, UNSANDBOXED_CAPABILITY],
+        )]));
+        let plugin = TestPlugin::new("native", false, &[Capability::Network]);
+        let initialized = plugin.initialized.clone();
+        manager.register_plugin(Box::new(plugin)).unwrap();
+        assert!(initialized.load(Ordering::SeqCst));
+        assert!(manager.pending_approvals().is_empty());
+    }
+
+    #[test]
+    fn sandboxed_plugin_needs_approval_only_for_declared_capabilities() {
+        let manager = manager();
+        manager
+            .register_plugin(Box::new(TestPlugin::new("plain", true, &[])))
+            .unwrap();
+
+        let plugin = TestPlugin::new("clipboard", true, &[Capability::Clipboard]);
+        let initialized = plugin.initialized.clone();
+        assert!(manager.register_plugin(Box::new(plugin)).is_err());
+        assert!(!initialized.load(Ordering::SeqCst));
+        assert_eq!(
+            manager.pending_approvals(),
+            [("clipboard".to_string(), vec![Capability::Clipboard])]
+        );
+    }
+
+    #[test]
+    fn manifest_permissions_are_checked_before_initialization() {
+        let manager = manager();
+        manager.set_approved_capabilities(HashMap::from([(
+            "extras".to_string(),
+            vec![UNSANDBOXED_CAPABILITY],
+        )]));
+        let mut extras = manifest("extras", "1.0.0", &[]);
+        extras.permissions = vec![Capability::FsRead(None)];
+        assert_eq!(
+            extras.required_capabilities(false),
+            [Capability::FsRead(None), UNSANDBOXED_CAPABILITY]
+        );
+        assert_eq!(
+            extras.required_capabilities(true),
+            [Capability::FsRead(None)]
+        );
+
+        let plugin = TestPlugin::new("ignored", false, &[]);
+        let initialized = plugin.initialized.clone();
+        let error = manager
+            .register_plugin_with_manifest(Box::new(plugin), extras)
+            .unwrap_err();
+        assert!(error.message().contains("fs-read"));
+        assert!(!initialized.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn check_approval_records_and_clears_pending_plugins() {
+        let manager = manager();
+        let required = [Capability::Network];
+
+        assert!(manager.check_approval("net", &required).is_err());
+        assert_eq!(manager.pending_approvals().len(), 1);
+
+        manager.set_approved_capabilities(HashMap::from([("net".to_string(), required.to_vec())]));
+        manager.check_approval("net", &required).unwrap();
+        assert!(manager.pending_approvals().is_empty());
+    }
+
+    #[test]
+    fn dependencies_are_checked_on_registration() {
+        let manager = manager();
+        let sandboxed = |name: &str| Box::new(TestPlugin::new(name, true, &[]));
+
+        let error = manager
+            .register_plugin_with_manifest(
+                sandboxed("app"),
+                manifest("app", "1.0.0", &[("core", "^1")]),
+            )
+            .unwrap_err();
+        assert!(error.message().contains("未加载"));
+
+        manager
+            .register_plugin_with_manifest(sandboxed("core"), manifest("core", "2.0.0", &[]))
+            .unwrap();
+        let error = manager
+            .register_plugin_with_manifest(
+                sandboxed("app"),
+                manifest("app", "1.0.0", &[("core", "^1")]),
+            )
+            .unwrap_err();
+        assert!(error.message().contains("2.0.0"));
+
+        // 被依赖的插件不能卸载
+        manager
+            .register_plugin_with_manifest(
+                sandboxed("app"),
+                manifest("app", "1.0.0", &[("core", "^2")]),
+            )
+            .unwrap();
+        assert!(manager.unload_plugin("core").is_err());
+        manager.unload_plugin("app").unwrap();
+        manager.unload_plugin("core").unwrap();
+    }
+
+    #[test]
+    fn tool_ids_must_not_collide_with_builtins_or_other_plugins() {
+        let manager = manager();
+        manager.reserve_tool_ids(["text.case"]);
+        let plugin = |name: &str, tools: &[&str]| {
+            Box::new(TestPlugin::new(name, true, &[]).with_tools(tools))
+        };
+
+        let error = manager
+            .register_plugin(plugin("shadow", &["extra.one", "text.case"]))
+            .unwrap_err();
+        assert!(error.message().contains("text.case"));
+        assert!(manager.get_tool("extra.one").is_none());
+
+        manager
+            .register_plugin(plugin("first", &["extra.one"]))
+            .unwrap();
+        assert!(
+            manager
+                .register_plugin(plugin("second", &["extra.one"]))
+                .is_err()
+        );
+        assert!(
+            manager
+                .register_plugin(plugin("twice", &["a", "a"]))
+                .is_err()
+        );
+        assert_eq!(manager.get_tools().len(), 1);
     }
 }
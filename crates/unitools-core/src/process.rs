@@ -0,0 +1,529 @@
+//! 外部进程插件
+//!
+//! 插件可以是任意可执行文件（Python、Go、Shell 脚本等），宿主启动进程后通过标准输入输出
+//! 交换 JSON-RPC 2.0 消息，每条消息占一行。标准输出只能用于协议消息，日志请写到标准错误。
+//!
+//! 宿主发送的请求：
+//!
+//! - `describe`：无参数，返回 [`PluginDescriptor`]
+//! - `execute`：参数为 [`ExecuteRequest`]，返回 [`ToolOutput`] 或 `null`
+//! - `ping`：无参数，返回任意值，用于健康检查
+//!
+//! 宿主可能在上一个请求尚未响应时发送新的请求，响应按请求编号对应，顺序不限。
+//! 插件执行期间可以发送 `progress` 通知汇报进度，参数格式与 [`Progress`] 一致，
+//! 例如 `{"id": 2, "fraction": 0.5, "message": "处理中"}`，其中 `id` 为所属请求的编号，
+//! 省略时进度转发给所有正在执行的请求。
+//!
+//! 请求被取消或超时时，如果没有其他请求在执行，宿主直接终止进程；否则发送 `cancel`
+//! 通知，参数为 `{"id": 请求编号}`，插件可以据此停止该请求。宿主在关闭进程前发送
+//! `shutdown` 通知。
+//!
+//! 工具执行失败时返回 JSON-RPC 错误，并在 `data.code` 中给出 `ToolError::code` 的取值，
+//! `data` 中还可以给出出错的参数 `parameter`、输入位置 `position` 和修改建议 `hint`：
+//!
+//! ```json
+//! {"jsonrpc": "2.0", "id": 2, "error": {"code": -32000, "message": "缺少参数", "data": {"code": "parameter", "parameter": "pattern"}}}
+//! ```
+//!
+//! 进程意外退出后，下一次调用会自动重新启动它。
+
+use crate::capability::Capability;
+use crate::error::ToolError;
+use crate::plugin::Plugin;
+use crate::protocol::{ExecuteRequest, PluginDescriptor, RpcMessage};
+use crate::tool::{
+    ParameterSpec, Progress, Tool, ToolCategory, ToolContext, ToolDescriptor, ToolOutput,
+    ToolResult, ToolTranslation,
+};
+use crate::tr;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 等待响应时检查取消令牌的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// `describe` 请求的超时时间
+const DESCRIBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 健康检查的超时时间
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 发送 `shutdown` 通知后等待进程退出的时间
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// 等待响应的请求，进程退出后不再接受新的请求
+#[derive(Default)]
+struct PendingRequests {
+    closed: bool,
+    /// 按请求编号索引，读取线程把响应和进度通知转发给对应的请求
+    requests: HashMap<u64, Sender<RpcMessage>>,
+}
+
+/// 正在运行的插件进程
+///
+/// 多个请求可以同时等待响应，读取线程按请求编号把响应分发给各自的请求。
+struct RunningProcess {
+    child: Mutex<Child>,
+    stdin: Mutex<ChildStdin>,
+    pending: Arc<Mutex<PendingRequests>>,
+}
+
+impl RunningProcess {
+    /// 进程是否已经退出
+    fn has_exited(&self) -> bool {
+        !matches!(self.child.lock().unwrap().try_wait(), Ok(None))
+    }
+
+    /// 登记请求，返回接收响应的通道；进程已退出时返回 `None`
+    fn register(&self, id: u64) -> Option<Receiver<RpcMessage>> {
+        let mut pending = self.pending.lock().unwrap();
+        if pending.closed {
+            return None;
+        }
+        let (sender, receiver) = mpsc::channel();
+        pending.requests.insert(id, sender);
+        Some(receiver)
+    }
+
+    /// 取消登记，返回是否还有其他请求在等待响应
+    fn unregister(&self, id: u64) -> bool {
+        let mut pending = self.pending.lock().unwrap();
+        pending.requests.remove(&id);
+        !pending.requests.is_empty()
+    }
+
+    /// 是否有请求正在等待响应
+    fn is_busy(&self) -> bool {
+        !self.pending.lock().unwrap().requests.is_empty()
+    }
+
+    /// 向进程写入一条消息
+    fn send(&self, message: &RpcMessage) -> std::io::Result<()> {
+        send(&mut self.stdin.lock().unwrap(), message)
+    }
+
+    /// 终止进程，等待中的请求会收到进程退出的错误
+    fn kill(&self) {
+        let mut child = self.child.lock().unwrap();
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+impl Drop for RunningProcess {
+    fn drop(&mut self) {
+        self.kill();
+    }
+}
+
+/// 把进程输出的一行分发给等待中的请求
+///
+/// 响应交给编号相同的请求；进度通知交给参数中 `id` 指明的请求，没有指明时交给所有请求。
+fn dispatch(pending: &Mutex<PendingRequests>, line: &str) {
+    // 忽略无法解析的输出行
+    let Ok(message) = serde_json::from_str::<RpcMessage>(line) else {
+        return;
+    };
+
+    let mut pending = pending.lock().unwrap();
+    match (message.id, message.method.as_deref()) {
+        (Some(id), None) => {
+            if let Some(sender) = pending.requests.remove(&id) {
+                let _ = sender.send(message);
+            }
+        }
+        (None, Some("progress")) => {
+            let target = message
+                .params
+                .as_ref()
+                .and_then(|params| params.get("id"))
+                .and_then(Value::as_u64);
+            for (id, sender) in &pending.requests {
+                if target.is_none_or(|target| target == *id) {
+                    let _ = sender.send(message.clone());
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 与插件进程的连接
+struct ProcessConnection {
+    name: String,
+    path: PathBuf,
+    process: Mutex<Option<Arc<RunningProcess>>>,
+    next_id: AtomicU64,
+}
+
+impl ProcessConnection {
+    fn new(path: &Path) -> Self {
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        // 进程在插件所在目录中运行，因此使用绝对路径启动
+        let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+
+        Self {
+            name,
+            path,
+            process: Mutex::new(None),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// 启动插件进程
+    fn spawn(&self) -> Result<RunningProcess, ToolError> {
+        let mut command = Command::new(&self.path);
+        if let Some(dir) = self.path.parent() {
+            command.current_dir(dir);
+        }
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| self.error(tr!("process-spawn-failed", error = e)))?;
+
+        let stdin = child.stdin.take().expect("stdin 已设置为管道");
+        let stdout = child.stdout.take().expect("stdout 已设置为管道");
+
+        let pending = Arc::new(Mutex::new(PendingRequests::default()));
+        let reader_pending = pending.clone();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                dispatch(&reader_pending, &line);
+            }
+            // 进程已退出，丢弃发送端使等待中的请求结束
+            let mut pending = reader_pending.lock().unwrap();
+            pending.closed = true;
+            pending.requests.clear();
+        });
+
+        Ok(RunningProcess {
+            child: Mutex::new(child),
+            stdin: Mutex::new(stdin),
+            pending,
+        })
+    }
+
+    /// 获取正在运行的进程，进程尚未启动或已经退出时重新启动
+    fn current(&self) -> Result<Arc<RunningProcess>, ToolError> {
+        let mut guard = self.process.lock().unwrap();
+        match guard.as_ref() {
+            Some(process) if !process.has_exited() => Ok(process.clone()),
+            _ => {
+                let process = Arc::new(self.spawn()?);
+                *guard = Some(process.clone());
+                Ok(process)
+            }
+        }
+    }
+
+    /// 终止进程，下一次调用会重新启动
+    fn discard(&self, process: &Arc<RunningProcess>) {
+        process.kill();
+        let mut guard = self.process.lock().unwrap();
+        if guard
+            .as_ref()
+            .is_some_and(|current| Arc::ptr_eq(current, process))
+        {
+            *guard = None;
+        }
+    }
+
+    /// 放弃等待请求的响应
+    ///
+    /// 没有其他请求在等待时终止进程，使插件停止工作；否则只发送 `cancel` 通知，
+    /// 不影响其他请求。
+    fn abandon(&self, process: &Arc<RunningProcess>, id: u64) {
+        if !process.unregister(id) {
+            self.discard(process);
+            return;
+        }
+        // 写入阻塞时不等待，避免取消被其他请求的写入拖住
+        if let Ok(mut stdin) = process.stdin.try_lock() {
+            let _ = send(
+                &mut stdin,
+                &RpcMessage::notification("cancel", serde_json::json!({ "id": id })),
+            );
+        }
+    }
+
+    /// 发送请求并等待响应
+    ///
+    /// 等待期间不持有连接的锁，同一插件的多个请求可以同时进行。提供上下文时会转发
+    /// 进度通知，并在取消令牌触发后放弃等待。
+    fn call(
+        &self,
+        method: &str,
+        params: Value,
+        ctx: Option<&ToolContext>,
+        timeout: Option<Duration>,
+    ) -> Result<Value, ToolError> {
+        let process = self.current()?;
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let Some(responses) = process.register(id) else {
+            self.discard(&process);
+            return Err(self.error(tr!("process-exited")));
+        };
+
+        if let Err(e) = process.send(&RpcMessage::request(id, method, params)) {
+            process.unregister(id);
+            self.discard(&process);
+            return Err(self.error(tr!("process-send-failed", error = e)));
+        }
+
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            if ctx.is_some_and(|ctx| ctx.cancellation.is_cancelled()) {
+                self.abandon(&process, id);
+                return Err(ToolError::Cancelled);
+            }
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                self.abandon(&process, id);
+                return Err(self.error(tr!("process-timeout", method = method)));
+            }
+
+            let message = match responses.recv_timeout(POLL_INTERVAL) {
+                Ok(message) => message,
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => {
+                    self.discard(&process);
+                    return Err(self.error(tr!("process-exited")));
+                }
+            };
+
+            if message.id == Some(id) {
+                return message.into_result();
+            }
+
+            if let Some(ctx) = ctx
+                && let Some(progress) = message
+                    .params
+                    .and_then(|params| serde_json::from_value::<Progress>(params).ok())
+            {
+                ctx.progress.report(progress.fraction, &progress.message);
+            }
+        }
+    }
+
+    /// 发送 `ping`，进程正在执行其他请求时视为正常
+    fn ping(&self) -> Result<(), ToolError> {
+        let busy = self
+            .process
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|process| process.is_busy());
+        if busy {
+            return Ok(());
+        }
+        self.call("ping", Value::Null, None, Some(PING_TIMEOUT))
+            .map(|_| ())
+    }
+
+    /// 终止当前进程并重新启动
+    fn restart(&self) -> Result<(), ToolError> {
+        let mut guard = self.process.lock().unwrap();
+        if let Some(process) = guard.take() {
+            process.kill();
+        }
+        *guard = Some(Arc::new(self.spawn()?));
+        Ok(())
+    }
+
+    /// 通知进程退出，超时后强制终止
+    fn shutdown(&self) {
+        let Some(process) = self.process.lock().unwrap().take() else {
+            return;
+        };
+
+        if process
+            .send(&RpcMessage::notification("shutdown", Value::Null))
+            .is_ok()
+        {
+            let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+            while Instant::now() < deadline && !process.has_exited() {
+                thread::sleep(POLL_INTERVAL);
+            }
+        }
+        process.kill();
+    }
+
+    fn error(&self, message: String) -> ToolError {
+        ToolError::PluginError(tr!("process-error", name = self.name, message = message))
+    }
+}
+
+/// 向进程写入一条消息
+fn send(stdin: &mut ChildStdin, message: &RpcMessage) -> std::io::Result<()> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    stdin.write_all(&line)?;
+    stdin.flush()
+}
+
+/// 以外部进程运行的插件
+pub struct ProcessPlugin {
+    descriptor: PluginDescriptor,
+    connection: Arc<ProcessConnection>,
+}
+
+impl ProcessPlugin {
+    /// 启动插件进程并获取其描述
+    ///
+    /// 启动进程就会执行插件代码，调用前应按清单通过
+    /// [`PluginManager::check_approval`](crate::plugin::PluginManager::check_approval) 检查权限。
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ToolError> {
+        let connection = Arc::new(ProcessConnection::new(path.as_ref()));
+        let descriptor = connection
+            .call("describe", Value::Null, None, Some(DESCRIBE_TIMEOUT))
+            .and_then(|value| {
+                serde_json::from_value(value)
+                    .map_err(|e| connection.error(tr!("plugin-describe-invalid", error = e)))
+            });
+
+        match descriptor {
+            Ok(descriptor) => Ok(Self {
+                descriptor,
+                connection,
+            }),
+            Err(error) => {
+                connection.shutdown();
+                Err(error)
+            }
+        }
+    }
+}
+
+impl Plugin for ProcessPlugin {
+    fn name(&self) -> &str {
+        &self.descriptor.name
+    }
+
+    fn description(&self) -> &str {
+        &self.descriptor.description
+    }
+
+    fn version(&self) -> &str {
+        &self.descriptor.version
+    }
+
+    fn initialize(&mut self) -> Result<(), ToolError> {
+        Ok(())
+    }
+
+    fn get_tools(&self) -> Vec<Arc<dyn Tool>> {
+        self.descriptor
+            .tools
+            .iter()
+            .map(|descriptor| {
+                Arc::new(ProcessTool {
+                    descriptor: descriptor.clone(),
+                    connection: self.connection.clone(),
+                }) as Arc<dyn Tool>
+            })
+            .collect()
+    }
+
+    fn cleanup(&mut self) -> Result<(), ToolError> {
+        self.connection.shutdown();
+        Ok(())
+    }
+
+    fn health_check(&self) -> Result<(), ToolError> {
+        self.connection.ping()
+    }
+
+    fn restart(&self) -> Result<(), ToolError> {
+        self.connection.restart()
+    }
+}
+
+/// 外部进程插件提供的工具
+#[derive(Clone)]
+struct ProcessTool {
+    descriptor: ToolDescriptor,
+    connection: Arc<ProcessConnection>,
+}
+
+impl std::fmt::Debug for ProcessTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProcessTool")
+            .field("plugin", &self.connection.name)
+            .field("tool", &self.descriptor.name)
+            .finish()
+    }
+}
+
+impl Tool for ProcessTool {
+    fn id(&self) -> &str {
+        self.descriptor.tool_id()
+    }
+
+    fn name(&self) -> &str {
+        &self.descriptor.name
+    }
+
+    fn description(&self) -> &str {
+        &self.descriptor.description
+    }
+
+    fn category(&self) -> ToolCategory {
+        self.descriptor.category
+    }
+
+    fn parameters(&self) -> Vec<ParameterSpec> {
+        self.descriptor.parameters.clone()
+    }
+
+    fn capabilities(&self) -> Vec<Capability> {
+        self.descriptor.capabilities.clone()
+    }
+
+    fn config_schema(&self) -> Vec<ParameterSpec> {
+        self.descriptor.config.clone()
+    }
+
+    fn translation(&self, language: &str) -> ToolTranslation {
+        self.descriptor.translation(language)
+    }
+
+    fn execute(&self, ctx: &ToolContext) -> ToolResult {
+        ctx.cancellation.check()?;
+
+        let request = serde_json::to_value(ExecuteRequest {
+            tool: self.descriptor.tool_id().to_string(),
+            input: ctx.input_data.clone(),
+            parameters: ctx.parameters.clone(),
+            config: ctx.config.clone(),
+        })
+        .map_err(|e| ToolError::FormatError(e.to_string()))?;
+
+        let value = self.connection.call("execute", request, Some(ctx), None)?;
+        if value.is_null() {
+            return Ok(None);
+        }
+
+        serde_json::from_value::<ToolOutput>(value)
+            .map(Some)
+            .map_err(|e| {
+                self.connection
+                    .error(tr!("plugin-result-invalid", error = e))
+            })
+    }
+}
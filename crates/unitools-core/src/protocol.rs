@@ -0,0 +1,249 @@
+//! 沙箱插件（WASM 模块、外部进程）与宿主之间交换的消息
+//!
+//! 所有消息均以 JSON 编码，二进制数据使用 base64 字符串表示。
+
+use crate::error::{InputPosition, ToolError};
+use crate::tool::{ToolDescriptor, ToolOutput, ToolResult};
+use crate::tr;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// 插件描述，由插件在加载时返回
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginDescriptor {
+    /// 插件名称
+    pub name: String,
+    /// 插件描述
+    #[serde(default)]
+    pub description: String,
+    /// 插件版本
+    #[serde(default)]
+    pub version: String,
+    /// 插件提供的工具
+    #[serde(default)]
+    pub tools: Vec<ToolDescriptor>,
+}
+
+/// 执行请求
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecuteRequest {
+    /// 要执行的工具标识，插件描述中没有给出标识的工具为其名称
+    pub tool: String,
+    /// 输入数据
+    #[serde(default, with = "crate::tool::base64_option")]
+    pub input: Option<Vec<u8>>,
+    /// 参数
+    #[serde(default)]
+    pub parameters: HashMap<String, String>,
+    /// 工具配置，按工具声明的配置规格补全了默认值
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub config: HashMap<String, String>,
+}
+
+/// 执行失败时返回的错误
+///
+/// 除类别代码和信息外的字段都是可选的，旧版本的插件可以只返回 `code` 和 `message`。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorPayload {
+    /// 错误类别代码，取值见 `ToolError::code`
+    pub code: String,
+    /// 错误信息
+    pub message: String,
+    /// 出错的参数名称
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameter: Option<String>,
+    /// 出错的输入位置
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<InputPosition>,
+    /// 修改建议
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+    /// 引起此错误的原因，流水线错误时为失败步骤的错误
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<Box<ErrorPayload>>,
+    /// 流水线错误中失败的步骤，从 1 开始
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step: Option<usize>,
+    /// 流水线错误中失败步骤的工具标识
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+}
+
+impl From<&ToolError> for ErrorPayload {
+    fn from(error: &ToolError) -> Self {
+        let source = error
+            .cause()
+            .map(|source| Box::new(ErrorPayload::from(source)));
+        let (step, tool) = match error {
+            ToolError::PipelineError { step, tool, .. } if *step > 0 => {
+                (Some(*step), Some(tool.clone()))
+            }
+            _ => (None, None),
+        };
+        Self {
+            code: error.code().to_string(),
+            message: error.message(),
+            parameter: error.parameter().map(str::to_string),
+            position: error.position(),
+            hint: error.hint().map(str::to_string),
+            source,
+            step,
+            tool,
+        }
+    }
+}
+
+impl From<ErrorPayload> for ToolError {
+    fn from(payload: ErrorPayload) -> Self {
+        let mut source = payload.source.map(|source| ToolError::from(*source));
+        let mut error = match (payload.code.as_str(), payload.step, payload.tool) {
+            ("pipeline", Some(step), Some(tool)) => ToolError::PipelineError {
+                step,
+                tool,
+                source: Box::new(source.take().unwrap_or(ToolError::Other(payload.message))),
+            },
+            (code, _, _) => ToolError::from_code(code, payload.message),
+        };
+        if let Some(parameter) = payload.parameter {
+            error = error.with_parameter(parameter);
+        }
+        if let Some(position) = payload.position {
+            error = error.with_position(position);
+        }
+        if let Some(hint) = payload.hint {
+            error = error.with_hint(hint);
+        }
+        if let Some(source) = source {
+            error = error.with_source(source);
+        }
+        error
+    }
+}
+
+/// 执行响应，`output` 与 `error` 至多出现一个
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExecuteResponse {
+    /// 工具输出
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<ToolOutput>,
+    /// 错误
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorPayload>,
+}
+
+impl ExecuteResponse {
+    /// 根据工具执行结果生成响应
+    pub fn from_result(result: &ToolResult) -> Self {
+        match result {
+            Ok(output) => Self {
+                output: output.clone(),
+                error: None,
+            },
+            Err(error) => Self {
+                output: None,
+                error: Some(error.into()),
+            },
+        }
+    }
+
+    /// 转换为工具执行结果
+    pub fn into_result(self) -> ToolResult {
+        match self.error {
+            Some(error) => Err(error.into()),
+            None => Ok(self.output),
+        }
+    }
+}
+
+/// JSON-RPC 协议版本
+pub const JSONRPC_VERSION: &str = "2.0";
+
+/// JSON-RPC 消息，请求、响应与通知共用同一结构
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RpcMessage {
+    /// 协议版本，固定为 "2.0"
+    pub jsonrpc: String,
+    /// 请求编号，通知没有编号
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    /// 方法名（请求与通知）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    /// 方法参数（请求与通知）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+    /// 调用结果（成功的响应）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    /// 错误（失败的响应）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+impl RpcMessage {
+    /// 创建请求
+    pub fn request(id: u64, method: &str, params: Value) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id: Some(id),
+            method: Some(method.to_string()),
+            params: Some(params),
+            ..Default::default()
+        }
+    }
+
+    /// 创建不需要响应的通知
+    pub fn notification(method: &str, params: Value) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            method: Some(method.to_string()),
+            params: Some(params),
+            ..Default::default()
+        }
+    }
+
+    /// 将响应转换为调用结果，`result` 缺省时视为 `null`
+    pub fn into_result(self) -> Result<Value, ToolError> {
+        match self.error {
+            Some(error) => Err(error.into()),
+            None => Ok(self.result.unwrap_or(Value::Null)),
+        }
+    }
+}
+
+/// JSON-RPC 错误对象
+///
+/// 工具执行失败时，`data.code` 为 `ToolError::code` 的取值，`data` 中还可以包含
+/// [`ErrorPayload`] 的 `parameter`、`position`、`hint` 等字段；
+/// 没有 `data.code` 的错误视为插件自身的错误。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RpcError {
+    /// JSON-RPC 错误码
+    pub code: i64,
+    /// 错误信息
+    pub message: String,
+    /// 附加数据
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl From<RpcError> for ToolError {
+    fn from(error: RpcError) -> Self {
+        // `data` 中除 `code` 外还可以包含 `ErrorPayload` 的其他字段
+        match error.data {
+            Some(Value::Object(mut data)) if data.get("code").is_some_and(Value::is_string) => {
+                let code = data["code"].as_str().unwrap_or_default().to_string();
+                data.insert("message".to_string(), Value::String(error.message.clone()));
+                serde_json::from_value::<ErrorPayload>(Value::Object(data))
+                    .map(ToolError::from)
+                    .unwrap_or_else(|_| ToolError::from_code(&code, error.message))
+            }
+            _ => ToolError::PluginError(tr!(
+                "protocol-error-code",
+                message = error.message,
+                code = error.code
+            )),
+        }
+    }
+}
@@ -0,0 +1,368 @@
+//! Rhai 脚本工具
+//!
+//! 每个 `.rhai` 脚本定义一个工具，脚本需要包含两个函数：
+//!
+//! ```rhai
+//! // 返回工具的元信息，格式与 `ToolDescriptor` 一致
+//! fn describe() {
+//!     #{
+//!         id: "text.reverse",
+//!         name: "反转文本",
+//!         description: "将文本按字符反转",
+//!         category: "text",
+//!         parameters: [
+//!             #{ name: "repeat", type: "int", description: "重复次数", "default": "1", min: 1.0 }
+//!         ],
+//!         config: [
+//!             #{ name: "separator", type: "string", description: "重复之间的分隔符", "default": "" }
+//!         ]
+//!     }
+//! }
+//!
+//! // input 为输入文本，params 为参数表，config 为工具配置表（值均为字符串）
+//! fn execute(input, params, config) {
+//!     let chars = input.to_chars();
+//!     chars.reverse();
+//!     let text = "";
+//!     for i in 0..parse_int(params.repeat) {
+//!         if i > 0 { text += config.separator; }
+//!         for c in chars { text += c; }
+//!     }
+//!     text
+//! }
+//! ```
+//!
+//! `default` 是 Rhai 的保留字，作为键名时需要加引号。没有配置项的脚本可以省略
+//! `execute` 的 `config` 参数。
+//!
+//! `execute` 返回字符串时作为文本输出，返回其他值时转换为 JSON 输出；
+//! 脚本中 `throw` 的值会作为错误信息返回。
+//!
+//! 脚本运行在沙箱中：不能通过 `import` 加载其他脚本文件，`print` 和 `debug` 的输出写入日志。
+
+use crate::capability::Capability;
+use crate::error::ToolError;
+use crate::plugin::Plugin;
+use crate::tool::{
+    CancellationToken, ParameterSpec, Tool, ToolCategory, ToolContext, ToolDescriptor, ToolOutput,
+    ToolResult, ToolTranslation,
+};
+use crate::tr;
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{AST, Dynamic, Engine, EvalAltResult, Map, Scope};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+/// 单次执行允许的最大操作数
+const MAX_OPERATIONS: u64 = 50_000_000;
+
+/// 已编译的脚本
+struct CompiledScript {
+    name: String,
+    ast: AST,
+}
+
+impl CompiledScript {
+    /// 创建编译和执行脚本使用的引擎，取消令牌被触发时中止脚本
+    fn engine(cancellation: Option<CancellationToken>) -> Engine {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.set_module_resolver(DummyModuleResolver::new());
+        engine.on_print(|text| log::info!("{}", text));
+        engine.on_debug(|text, _, position| log::debug!("{}: {}", position, text));
+        if let Some(cancellation) = cancellation {
+            engine.on_progress(move |_| {
+                cancellation
+                    .is_cancelled()
+                    .then(|| Dynamic::from("cancelled"))
+            });
+        }
+        engine
+    }
+
+    /// 调用 `describe` 获取工具描述
+    fn describe(&self) -> Result<ToolDescriptor, ToolError> {
+        let engine = Self::engine(None);
+        let value: Dynamic = engine
+            .call_fn(&mut Scope::new(), &self.ast, "describe", ())
+            .map_err(|e| self.error(tr!("script-describe-failed", error = e)))?;
+
+        rhai::serde::from_dynamic(&value)
+            .map_err(|e| self.error(tr!("script-describe-invalid", error = e)))
+    }
+
+    /// 调用 `execute`
+    fn execute(&self, ctx: &ToolContext) -> ToolResult {
+        let input = match &ctx.input_data {
+            Some(data) => String::from_utf8(data.clone())
+                .map_err(|_| ToolError::FormatError(tr!("input-not-utf8")))?,
+            None => String::new(),
+        };
+
+        let to_map = |values: &HashMap<String, String>| -> Map {
+            values
+                .iter()
+                .map(|(name, value)| (name.as_str().into(), value.clone().into()))
+                .collect()
+        };
+        let params = to_map(&ctx.parameters);
+
+        // 只有声明了 config 参数的 execute 才传入工具配置
+        let takes_config = self
+            .ast
+            .iter_functions()
+            .any(|f| f.name == "execute" && f.params.len() == 3);
+        let engine = Self::engine(Some(ctx.cancellation.clone()));
+        let mut scope = Scope::new();
+        let result = if takes_config {
+            let config = to_map(&ctx.config);
+            engine.call_fn::<Dynamic>(&mut scope, &self.ast, "execute", (input, params, config))
+        } else {
+            engine.call_fn::<Dynamic>(&mut scope, &self.ast, "execute", (input, params))
+        }
+        .map_err(|e| self.map_error(*e))?;
+
+        if result.is_unit() {
+            Ok(None)
+        } else if result.is_string() {
+            Ok(Some(ToolOutput::Text(result.to_string())))
+        } else {
+            let value: serde_json::Value = rhai::serde::from_dynamic(&result)
+                .map_err(|e| self.error(tr!("script-result-invalid", error = e)))?;
+            Ok(Some(ToolOutput::Json(value)))
+        }
+    }
+
+    /// 转换脚本执行错误
+    fn map_error(&self, error: EvalAltResult) -> ToolError {
+        match error {
+            EvalAltResult::ErrorTerminated(..) => ToolError::Cancelled,
+            EvalAltResult::ErrorRuntime(value, _) => ToolError::Other(value.to_string()),
+            EvalAltResult::ErrorTooManyOperations(_) => self.error(tr!("plugin-out-of-fuel")),
+            error => self.error(tr!("plugin-run-failed", error = error)),
+        }
+    }
+
+    fn error(&self, message: String) -> ToolError {
+        ToolError::PluginError(tr!("script-error", name = self.name, message = message))
+    }
+}
+
+/// 由单个脚本文件构成的插件
+pub struct ScriptPlugin {
+    name: String,
+    tool: Arc<ScriptTool>,
+}
+
+impl ScriptPlugin {
+    /// 加载并编译脚本
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ToolError> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)?;
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let ast = CompiledScript::engine(None).compile(&source).map_err(|e| {
+            ToolError::PluginError(tr!(
+                "script-compile-failed",
+                path = path.display(),
+                error = e
+            ))
+        })?;
+        let script = Arc::new(CompiledScript {
+            name: name.clone(),
+            ast,
+        });
+        let descriptor = script.describe()?;
+
+        Ok(Self {
+            name,
+            tool: Arc::new(ScriptTool { descriptor, script }),
+        })
+    }
+}
+
+impl Plugin for ScriptPlugin {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.tool.descriptor.description
+    }
+
+    fn version(&self) -> &str {
+        "script"
+    }
+
+    fn initialize(&mut self) -> Result<(), ToolError> {
+        Ok(())
+    }
+
+    fn get_tools(&self) -> Vec<Arc<dyn Tool>> {
+        vec![self.tool.clone()]
+    }
+
+    fn cleanup(&mut self) -> Result<(), ToolError> {
+        Ok(())
+    }
+
+    fn is_sandboxed(&self) -> bool {
+        true
+    }
+}
+
+/// 脚本提供的工具
+#[derive(Clone)]
+struct ScriptTool {
+    descriptor: ToolDescriptor,
+    script: Arc<CompiledScript>,
+}
+
+impl std::fmt::Debug for ScriptTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScriptTool")
+            .field("script", &self.script.name)
+            .field("tool", &self.descriptor.name)
+            .finish()
+    }
+}
+
+impl Tool for ScriptTool {
+    fn id(&self) -> &str {
+        self.descriptor.tool_id()
+    }
+
+    fn name(&self) -> &str {
+        &self.descriptor.name
+    }
+
+    fn description(&self) -> &str {
+        &self.descriptor.description
+    }
+
+    fn category(&self) -> ToolCategory {
+        self.descriptor.category
+    }
+
+    fn parameters(&self) -> Vec<ParameterSpec> {
+        self.descriptor.parameters.clone()
+    }
+
+    fn capabilities(&self) -> Vec<Capability> {
+        self.descriptor.capabilities.clone()
+    }
+
+    fn config_schema(&self) -> Vec<ParameterSpec> {
+        self.descriptor.config.clone()
+    }
+
+    fn translation(&self, language: &str) -> ToolTranslation {
+        self.descriptor.translation(language)
+    }
+
+    fn execute(&self, ctx: &ToolContext) -> ToolResult {
+        self.script.execute(ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 将脚本写入临时文件并加载其中的工具
+    fn load(name: &str, source: &str) -> Arc<dyn Tool> {
+        let path = std::env::temp_dir().join(format!(
+            "unitools-script-{}-{}.rhai",
+            std::process::id(),
+            name
+        ));
+        fs::write(&path, source).unwrap();
+        let plugin = ScriptPlugin::load(&path);
+        let _ = fs::remove_file(&path);
+        plugin.unwrap().get_tools().remove(0)
+    }
+
+    const DESCRIBE: &str = r#"
+        fn describe() {
+            #{
+                id: "test.suffix",
+                name: "suffix",
+                description: "",
+                category: "text",
+                config: [#{ name: "suffix", type: "string", description: "", "default": "?" }]
+            }
+        }
+    "#;
+
+    #[test]
+    fn execute_receives_config() {
+        let tool = load(
+            "config",
+            &format!(
+                "{}\nfn execute(input, params, config) {{ input + config.suffix }}",
+                DESCRIBE
+            ),
+        );
+        assert_eq!(tool.config_schema()[0].name, "suffix");
+
+        let ctx = ToolContext {
+            input_data: Some(b"abc".to_vec()),
+            config: HashMap::from([("suffix".to_string(), "!".to_string())]),
+            ..Default::default()
+        };
+        assert_eq!(
+            tool.execute(&ctx).unwrap(),
+            Some(ToolOutput::Text("abc!".to_string()))
+        );
+    }
+
+    #[test]
+    fn import_is_disabled() {
+        let module = std::env::temp_dir().join(format!(
+            "unitools-script-{}-module.rhai",
+            std::process::id()
+        ));
+        fs::write(&module, "fn value() { 1 }").unwrap();
+        let tool = load(
+            "import",
+            &format!(
+                "{}\nfn execute(input, params) {{ import {:?} as m; m::value() }}",
+                DESCRIBE,
+                module.with_extension("").display().to_string()
+            ),
+        );
+        let result = tool.execute(&ToolContext::default());
+        let _ = fs::remove_file(&module);
+        assert!(
+            matches!(result, Err(ToolError::PluginError(_))),
+            "{:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn execute_without_config_parameter() {
+        let tool = load(
+            "no-config",
+            &format!(
+                "{}\nfn execute(input, params) {{ input + params.x }}",
+                DESCRIBE
+            ),
+        );
+        let ctx = ToolContext {
+            input_data: Some(b"abc".to_vec()),
+            parameters: HashMap::from([("x".to_string(), "1".to_string())]),
+            config: HashMap::from([("suffix".to_string(), "!".to_string())]),
+            ..Default::default()
+        };
+        assert_eq!(
+            tool.execute(&ctx).unwrap(),
+            Some(ToolOutput::Text("abc1".to_string()))
+        );
+    }
+}
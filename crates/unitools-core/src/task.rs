@@ -0,0 +1,71 @@
+use crate::error::ToolError;
+use crate::tool::{CancellationToken, Progress, ProgressReporter, Tool, ToolContext, ToolResult};
+use crate::tr;
+use std::sync::Arc;
+use tokio::runtime::Handle;
+use tokio::sync::oneshot;
+
+/// 在后台运行的工具任务
+///
+/// 工具在运行时的阻塞线程池中执行，调用方可以随时查询进度、请求取消或获取结果，
+/// 适合在界面线程中逐帧轮询。
+pub struct ToolTask {
+    receiver: oneshot::Receiver<ToolResult>,
+    progress: ProgressReporter,
+    cancellation: CancellationToken,
+}
+
+impl ToolTask {
+    /// 在指定运行时中启动工具，执行前会先校验参数并解析工具配置，并在 `ctx.events` 上发布执行事件
+    pub fn spawn(runtime: &Handle, tool: Arc<dyn Tool>, ctx: ToolContext) -> Self {
+        let (sender, receiver) = oneshot::channel();
+        let progress = ctx.progress.clone();
+        let cancellation = ctx.cancellation.clone();
+        let handle = runtime.clone();
+
+        runtime.spawn_blocking(move || {
+            let result = handle.block_on(tool.validate_and_execute_async(ctx));
+            // 接收方可能已被丢弃（例如任务被取消），此时忽略结果
+            let _ = sender.send(result);
+        });
+
+        Self {
+            receiver,
+            progress,
+            cancellation,
+        }
+    }
+
+    /// 获取当前进度
+    pub fn progress(&self) -> Progress {
+        self.progress.snapshot()
+    }
+
+    /// 请求取消任务
+    pub fn cancel(&self) {
+        self.cancellation.cancel();
+    }
+
+    /// 是否已请求取消
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.is_cancelled()
+    }
+
+    /// 非阻塞地获取结果，任务尚未完成时返回 None
+    pub fn try_result(&mut self) -> Option<ToolResult> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(oneshot::error::TryRecvError::Empty) => None,
+            Err(oneshot::error::TryRecvError::Closed) => {
+                Some(Err(ToolError::Other(tr!("task-aborted"))))
+            }
+        }
+    }
+
+    /// 等待任务完成并返回结果
+    pub async fn join(self) -> ToolResult {
+        self.receiver
+            .await
+            .unwrap_or_else(|_| Err(ToolError::Other(tr!("task-aborted"))))
+    }
+}
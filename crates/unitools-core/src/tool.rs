@@ -1,9 +1,25 @@
-use crate::error::ToolError;
+use crate::capability::{Capability, Permissions};
+use crate::error::{ToolError, closest_match};
+use crate::event::EventBus;
+use crate::i18n;
+use crate::tr;
 use dyn_clone::DynClone;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt::{Debug, Display};
+use std::fs;
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use std::process::{Command, Output};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
 /// 工具类别枚举
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ToolCategory {
     File,      // 文件操作工具
     Text,      // 文本处理工具
@@ -16,39 +32,955 @@ pub enum ToolCategory {
 
 impl Display for ToolCategory {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            ToolCategory::File => write!(f, "文件工具"),
-            ToolCategory::Text => write!(f, "文本工具"),
-            ToolCategory::Network => write!(f, "网络工具"),
-            ToolCategory::Image => write!(f, "图像工具"),
-            ToolCategory::Converter => write!(f, "转换工具"),
-            ToolCategory::System => write!(f, "系统工具"),
-            ToolCategory::Other => write!(f, "其他工具"),
+        let text = match self {
+            ToolCategory::File => tr!("category-file"),
+            ToolCategory::Text => tr!("category-text"),
+            ToolCategory::Network => tr!("category-network"),
+            ToolCategory::Image => tr!("category-image"),
+            ToolCategory::Converter => tr!("category-converter"),
+            ToolCategory::System => tr!("category-system"),
+            ToolCategory::Other => tr!("category-other"),
+        };
+        f.write_str(&text)
+    }
+}
+
+/// 参数类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParameterType {
+    String,    // 单行文本
+    Int,       // 整数
+    Float,     // 浮点数
+    Bool,      // 布尔值
+    Enum,      // 枚举，从候选值中选择
+    Path,      // 文件或目录路径
+    Multiline, // 多行文本
+}
+
+/// 参数规格，描述工具参数的类型和约束
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParameterSpec {
+    /// 参数名称
+    pub name: String,
+    /// 参数说明
+    pub description: String,
+    /// 参数类型
+    #[serde(rename = "type")]
+    pub kind: ParameterType,
+    /// 是否必填
+    #[serde(default)]
+    pub required: bool,
+    /// 默认值
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    /// 允许的取值（枚举类型使用）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_values: Vec<String>,
+    /// 最小值（数值类型使用）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    /// 最大值（数值类型使用）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    /// 参数值必须匹配的正则表达式
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+}
+
+impl ParameterSpec {
+    /// 创建新的参数规格
+    pub fn new(name: &str, kind: ParameterType, description: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            kind,
+            required: false,
+            default: None,
+            allowed_values: Vec::new(),
+            min: None,
+            max: None,
+            pattern: None,
+        }
+    }
+
+    /// 创建单行文本参数
+    pub fn string(name: &str, description: &str) -> Self {
+        Self::new(name, ParameterType::String, description)
+    }
+
+    /// 创建整数参数
+    pub fn int(name: &str, description: &str) -> Self {
+        Self::new(name, ParameterType::Int, description)
+    }
+
+    /// 创建浮点数参数
+    pub fn float(name: &str, description: &str) -> Self {
+        Self::new(name, ParameterType::Float, description)
+    }
+
+    /// 创建布尔参数
+    pub fn bool(name: &str, description: &str) -> Self {
+        Self::new(name, ParameterType::Bool, description)
+    }
+
+    /// 创建枚举参数
+    pub fn enumeration(name: &str, description: &str, values: &[&str]) -> Self {
+        let mut spec = Self::new(name, ParameterType::Enum, description);
+        spec.allowed_values = values.iter().map(|v| v.to_string()).collect();
+        spec
+    }
+
+    /// 创建路径参数
+    pub fn path(name: &str, description: &str) -> Self {
+        Self::new(name, ParameterType::Path, description)
+    }
+
+    /// 创建多行文本参数
+    pub fn multiline(name: &str, description: &str) -> Self {
+        Self::new(name, ParameterType::Multiline, description)
+    }
+
+    /// 标记为必填参数
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// 设置默认值
+    pub fn with_default(mut self, value: &str) -> Self {
+        self.default = Some(value.to_string());
+        self
+    }
+
+    /// 设置数值范围
+    pub fn with_range(mut self, min: Option<f64>, max: Option<f64>) -> Self {
+        self.min = min;
+        self.max = max;
+        self
+    }
+
+    /// 设置校验用的正则表达式
+    pub fn with_pattern(mut self, pattern: &str) -> Self {
+        self.pattern = Some(pattern.to_string());
+        self
+    }
+
+    /// 校验参数值，返回规范化后的值，错误中记录参数名称
+    pub fn validate(&self, value: &str) -> Result<String, ToolError> {
+        self.normalize(value)
+            .map_err(|error| error.with_parameter(self.name.clone()))
+    }
+
+    /// 校验并规范化参数值
+    fn normalize(&self, value: &str) -> Result<String, ToolError> {
+        let normalized = match self.kind {
+            ParameterType::Int => {
+                let number = value.trim().parse::<i64>().map_err(|_| {
+                    ToolError::ParameterError(tr!("param-not-int", name = self.name, value = value))
+                })?;
+                self.check_range(number as f64)?;
+                number.to_string()
+            }
+            ParameterType::Float => {
+                let number = value
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|n| n.is_finite())
+                    .ok_or_else(|| {
+                        ToolError::ParameterError(tr!(
+                            "param-not-number",
+                            name = self.name,
+                            value = value
+                        ))
+                    })?;
+                self.check_range(number)?;
+                number.to_string()
+            }
+            ParameterType::Bool => match value.trim().to_lowercase().as_str() {
+                "true" | "1" | "yes" | "on" => "true".to_string(),
+                "false" | "0" | "no" | "off" => "false".to_string(),
+                _ => {
+                    return Err(ToolError::ParameterError(tr!(
+                        "param-not-bool",
+                        name = self.name,
+                        value = value
+                    )));
+                }
+            },
+            ParameterType::Enum => self
+                .allowed_values
+                .iter()
+                .find(|allowed| allowed.eq_ignore_ascii_case(value.trim()))
+                .cloned()
+                .ok_or_else(|| {
+                    let error = ToolError::ParameterError(tr!(
+                        "param-invalid-choice",
+                        name = self.name,
+                        value = value,
+                        allowed = self.allowed_values.join(", ")
+                    ));
+                    match closest_match(
+                        value.trim(),
+                        self.allowed_values.iter().map(String::as_str),
+                    ) {
+                        Some(allowed) => {
+                            error.with_hint(tr!("hint-did-you-mean-value", value = allowed))
+                        }
+                        None => error,
+                    }
+                })?,
+            ParameterType::String | ParameterType::Path | ParameterType::Multiline => {
+                value.to_string()
+            }
+        };
+
+        if let Some(pattern) = &self.pattern {
+            let regex = Regex::new(pattern).map_err(|e| {
+                ToolError::ParameterError(tr!("param-invalid-pattern", name = self.name, error = e))
+            })?;
+            if !regex.is_match(&normalized) {
+                return Err(ToolError::ParameterError(tr!(
+                    "param-pattern-mismatch",
+                    name = self.name,
+                    pattern = pattern,
+                    value = value
+                )));
+            }
+        }
+
+        Ok(normalized)
+    }
+
+    /// 检查数值是否在允许范围内
+    fn check_range(&self, number: f64) -> Result<(), ToolError> {
+        if let Some(min) = self.min
+            && number < min
+        {
+            return Err(ToolError::ParameterError(tr!(
+                "param-below-min",
+                name = self.name,
+                min = min,
+                value = number
+            )));
+        }
+        if let Some(max) = self.max
+            && number > max
+        {
+            return Err(ToolError::ParameterError(tr!(
+                "param-above-max",
+                name = self.name,
+                max = max,
+                value = number
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// 按参数规格校验参数，补全默认值并返回规范化后的参数表
+///
+/// 空字符串视为未提供；未在规格中声明的参数原样保留。
+pub fn validate_parameters(
+    specs: &[ParameterSpec],
+    parameters: &HashMap<String, String>,
+) -> Result<HashMap<String, String>, ToolError> {
+    let mut validated = parameters.clone();
+
+    for spec in specs {
+        let value = parameters
+            .get(&spec.name)
+            .filter(|v| !v.is_empty())
+            .or(spec.default.as_ref());
+
+        match value {
+            Some(value) => {
+                let normalized = spec.validate(value)?;
+                validated.insert(spec.name.clone(), normalized);
+            }
+            None if spec.required => {
+                let error = ToolError::ParameterError(tr!("param-missing", name = spec.name))
+                    .with_parameter(spec.name.clone());
+                // 提供了与之相近的未声明参数时，可能是拼错了参数名称
+                let misspelled = parameters.keys().any(|name| {
+                    !specs.iter().any(|spec| &spec.name == name)
+                        && closest_match(name, [spec.name.as_str()]).is_some()
+                });
+                return Err(if misspelled {
+                    error.with_hint(tr!("hint-did-you-mean-parameter", name = spec.name))
+                } else {
+                    error
+                });
+            }
+            None => {
+                validated.remove(&spec.name);
+            }
+        }
+    }
+
+    Ok(validated)
+}
+
+/// 按工具的配置规格解析后的工具配置
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ToolConfig {
+    /// 补全默认值并规范化后的配置值，按配置项名称索引
+    pub values: HashMap<String, String>,
+    /// 保存的配置中被忽略的配置项，例如取值无效或未在规格中声明
+    pub problems: Vec<String>,
+}
+
+impl ToolConfig {
+    /// 按配置规格解析保存的配置
+    ///
+    /// 保存的配置是以配置项名称为键的 JSON 对象，值可以是字符串、数字或布尔值。
+    /// 缺少的配置项使用默认值；无效或未声明的配置项记录在 `problems` 中，
+    /// 不会导致失败，有默认值时使用默认值。
+    pub fn resolve(schema: &[ParameterSpec], stored: Option<&serde_json::Value>) -> Self {
+        let mut config = Self::default();
+        let empty = serde_json::Map::new();
+        let stored = match stored {
+            None | Some(serde_json::Value::Null) => &empty,
+            Some(serde_json::Value::Object(stored)) => stored,
+            Some(_) => {
+                config.problems.push(tr!("tool-config-not-object"));
+                &empty
+            }
+        };
+
+        for (name, value) in stored {
+            let Some(spec) = schema.iter().find(|spec| spec.name == *name) else {
+                config
+                    .problems
+                    .push(tr!("tool-config-unknown", name = name));
+                continue;
+            };
+            let text = match value {
+                serde_json::Value::String(text) => text.clone(),
+                serde_json::Value::Number(_) | serde_json::Value::Bool(_) => value.to_string(),
+                _ => {
+                    config
+                        .problems
+                        .push(tr!("tool-config-unsupported", name = name));
+                    continue;
+                }
+            };
+            match spec.validate(&text) {
+                Ok(normalized) => {
+                    config.values.insert(name.clone(), normalized);
+                }
+                Err(error) => config.problems.push(tr!(
+                    "tool-config-invalid",
+                    name = name,
+                    error = error.message()
+                )),
+            }
+        }
+
+        for spec in schema {
+            if !config.values.contains_key(&spec.name)
+                && let Some(default) = &spec.default
+            {
+                let value = spec.validate(default).unwrap_or_else(|_| default.clone());
+                config.values.insert(spec.name.clone(), value);
+            }
+        }
+        config
+    }
+
+    /// 转换为保存到配置文件中的 JSON 对象
+    ///
+    /// 只保存与默认值不同的配置项，整数、浮点数和布尔类型保存为对应的 JSON 类型。
+    pub fn to_json(&self, schema: &[ParameterSpec]) -> serde_json::Value {
+        let mut stored = serde_json::Map::new();
+        for spec in schema {
+            let Some(value) = self.values.get(&spec.name) else {
+                continue;
+            };
+            let default = spec
+                .default
+                .as_ref()
+                .map(|d| spec.validate(d).unwrap_or(d.clone()));
+            if default.as_ref() == Some(value) {
+                continue;
+            }
+            let json = match spec.kind {
+                ParameterType::Int => value.parse::<i64>().ok().map(serde_json::Value::from),
+                ParameterType::Float => value
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(serde_json::Number::from_f64)
+                    .map(serde_json::Value::Number),
+                ParameterType::Bool => value.parse::<bool>().ok().map(serde_json::Value::Bool),
+                _ => None,
+            };
+            stored.insert(
+                spec.name.clone(),
+                json.unwrap_or_else(|| serde_json::Value::String(value.clone())),
+            );
+        }
+        serde_json::Value::Object(stored)
+    }
+}
+
+/// 取消令牌，用于请求正在执行的工具提前结束
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// 创建新的取消令牌
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 请求取消
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// 是否已请求取消
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// 如果已请求取消则返回 `ToolError::Cancelled`
+    pub fn check(&self) -> Result<(), ToolError> {
+        if self.is_cancelled() {
+            Err(ToolError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// 执行进度
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Progress {
+    /// 完成比例 (0.0 - 1.0)，未知时为 None
+    pub fraction: Option<f32>,
+    /// 当前步骤说明
+    pub message: String,
+}
+
+/// 进度报告器，工具通过它向调用方汇报执行进度
+#[derive(Debug, Clone, Default)]
+pub struct ProgressReporter {
+    state: Arc<Mutex<Progress>>,
+    /// 子任务的进度在整体进度中所占的区间和说明前缀，见 [`ProgressReporter::scoped`]
+    scope: Option<Arc<ProgressScope>>,
+}
+
+#[derive(Debug)]
+struct ProgressScope {
+    start: f32,
+    end: f32,
+    prefix: String,
+}
+
+impl ProgressReporter {
+    /// 创建新的进度报告器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 创建汇报子任务进度的报告器
+    ///
+    /// 子任务的完成比例映射到整体进度的 `start..end` 区间，未知时按 `start` 汇报；
+    /// 子任务的说明前加上 `prefix`。流水线用它把每一步的进度转发到整体进度。
+    pub fn scoped(&self, start: f32, end: f32, prefix: &str) -> ProgressReporter {
+        let (start, end, prefix) = match &self.scope {
+            Some(scope) => (
+                scope.map(start),
+                scope.map(end),
+                format!("{}: {}", scope.prefix, prefix),
+            ),
+            None => (
+                start.clamp(0.0, 1.0),
+                end.clamp(0.0, 1.0),
+                prefix.to_string(),
+            ),
+        };
+        ProgressReporter {
+            state: self.state.clone(),
+            scope: Some(Arc::new(ProgressScope { start, end, prefix })),
         }
     }
+
+    /// 汇报进度
+    pub fn report(&self, fraction: Option<f32>, message: &str) {
+        let fraction = fraction.map(|f| f.clamp(0.0, 1.0));
+        let mut state = self.state.lock().unwrap();
+        match &self.scope {
+            Some(scope) => {
+                state.fraction = Some(scope.map(fraction.unwrap_or(0.0)));
+                state.message = if message.is_empty() {
+                    scope.prefix.clone()
+                } else {
+                    format!("{}: {}", scope.prefix, message)
+                };
+            }
+            None => {
+                state.fraction = fraction;
+                state.message = message.to_string();
+            }
+        }
+    }
+
+    /// 按已完成数量和总数汇报进度
+    pub fn report_count(&self, done: usize, total: usize, message: &str) {
+        let fraction = if total == 0 {
+            None
+        } else {
+            Some(done as f32 / total as f32)
+        };
+        self.report(fraction, message);
+    }
+
+    /// 获取当前进度
+    pub fn snapshot(&self) -> Progress {
+        self.state.lock().unwrap().clone()
+    }
+}
+
+impl ProgressScope {
+    /// 把子任务的完成比例映射为整体进度
+    fn map(&self, fraction: f32) -> f32 {
+        self.start + (self.end - self.start) * fraction.clamp(0.0, 1.0)
+    }
 }
 
 /// 工具上下文，包含工具执行时所需的数据
+#[derive(Default)]
 pub struct ToolContext {
     pub input_data: Option<Vec<u8>>,
-    pub parameters: std::collections::HashMap<String, String>,
+    pub parameters: HashMap<String, String>,
+    /// 进度报告器
+    pub progress: ProgressReporter,
+    /// 取消令牌
+    pub cancellation: CancellationToken,
+    /// 授予的权限，默认不做限制；插件工具由 `PluginManager` 限制为已批准的能力
+    pub permissions: Permissions,
+    /// 当前工具的配置，由 `validate_and_execute` 按工具的配置规格从 `tool_configs` 解析
+    pub config: HashMap<String, String>,
+    /// 所有工具保存的配置，按工具标识索引，流水线等组合工具据此为每一步解析配置
+    pub tool_configs: Arc<HashMap<String, serde_json::Value>>,
+    /// 发布工具执行事件的事件总线
+    pub events: EventBus,
 }
 
-impl Default for ToolContext {
-    fn default() -> Self {
-        Self {
-            input_data: None,
-            parameters: std::collections::HashMap::new(),
+impl ToolContext {
+    /// 创建共享进度和取消令牌、但使用指定权限的上下文
+    pub fn with_permissions(&self, permissions: Permissions) -> ToolContext {
+        ToolContext {
+            input_data: self.input_data.clone(),
+            parameters: self.parameters.clone(),
+            progress: self.progress.clone(),
+            cancellation: self.cancellation.clone(),
+            permissions,
+            config: self.config.clone(),
+            tool_configs: self.tool_configs.clone(),
+            events: self.events.clone(),
+        }
+    }
+
+    /// 检查是否具有指定能力，网络、剪贴板等访问需在使用前调用
+    pub fn require(&self, capability: &Capability) -> Result<(), ToolError> {
+        self.permissions.check(capability)
+    }
+
+    /// 读取文件
+    pub fn read_file<P: AsRef<Path>>(&self, path: P) -> Result<Vec<u8>, ToolError> {
+        let path = path.as_ref();
+        self.require(&Capability::FsRead(Some(path.to_path_buf())))?;
+        Ok(fs::read(path)?)
+    }
+
+    /// 写入文件
+    pub fn write_file<P: AsRef<Path>>(&self, path: P, data: &[u8]) -> Result<(), ToolError> {
+        let path = path.as_ref();
+        self.require(&Capability::FsWrite(Some(path.to_path_buf())))?;
+        Ok(fs::write(path, data)?)
+    }
+
+    /// 获取文件或目录的元数据
+    pub fn metadata<P: AsRef<Path>>(&self, path: P) -> Result<fs::Metadata, ToolError> {
+        let path = path.as_ref();
+        self.require(&Capability::FsRead(Some(path.to_path_buf())))?;
+        Ok(fs::metadata(path)?)
+    }
+
+    /// 列出目录内容
+    pub fn read_dir<P: AsRef<Path>>(&self, path: P) -> Result<fs::ReadDir, ToolError> {
+        let path = path.as_ref();
+        self.require(&Capability::FsRead(Some(path.to_path_buf())))?;
+        Ok(fs::read_dir(path)?)
+    }
+
+    /// 运行子进程并等待其结束
+    pub fn run_process(&self, command: &mut Command) -> Result<Output, ToolError> {
+        self.require(&Capability::ProcessSpawn)?;
+        Ok(command.output()?)
+    }
+
+    /// 获取参数值
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.parameters.get(name).map(|v| v.as_str())
+    }
+
+    /// 获取参数值并解析为指定类型
+    pub fn parse_parameter<T: FromStr>(&self, name: &str) -> Result<Option<T>, ToolError> {
+        match self.parameters.get(name) {
+            Some(value) => value.parse::<T>().map(Some).map_err(|_| {
+                ToolError::ParameterError(tr!("param-unparsable", name = name, value = value))
+                    .with_parameter(name)
+            }),
+            None => Ok(None),
+        }
+    }
+
+    /// 获取配置值
+    pub fn config(&self, name: &str) -> Option<&str> {
+        self.config.get(name).map(|v| v.as_str())
+    }
+
+    /// 获取配置值并解析为指定类型
+    pub fn parse_config<T: FromStr>(&self, name: &str) -> Result<Option<T>, ToolError> {
+        match self.config.get(name) {
+            Some(value) => value.parse::<T>().map(Some).map_err(|_| {
+                ToolError::ParameterError(tr!("param-unparsable", name = name, value = value))
+            }),
+            None => Ok(None),
         }
     }
 }
 
+/// 工具输出
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "content", rename_all = "lowercase")]
+pub enum ToolOutput {
+    /// 纯文本
+    Text(String),
+    /// JSON 数据
+    Json(serde_json::Value),
+    /// 表格
+    Table {
+        headers: Vec<String>,
+        rows: Vec<Vec<String>>,
+    },
+    /// 二进制数据
+    Binary {
+        mime_type: String,
+        #[serde(with = "base64_bytes")]
+        data: Vec<u8>,
+    },
+    /// 图像数据（PNG、JPEG 等编码后的字节）
+    Image {
+        mime_type: String,
+        #[serde(with = "base64_bytes")]
+        data: Vec<u8>,
+    },
+    /// 多个输出
+    Multiple(Vec<ToolOutput>),
+}
+
+impl ToolOutput {
+    /// 创建表格输出
+    pub fn table(headers: &[&str], rows: Vec<Vec<String>>) -> Self {
+        ToolOutput::Table {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows,
+        }
+    }
+
+    /// 返回输出的 MIME 类型
+    pub fn mime_type(&self) -> &str {
+        match self {
+            ToolOutput::Text(_) => "text/plain",
+            ToolOutput::Json(_) => "application/json",
+            ToolOutput::Table { .. } => "text/csv",
+            ToolOutput::Binary { mime_type, .. } | ToolOutput::Image { mime_type, .. } => mime_type,
+            ToolOutput::Multiple(_) => "multipart/mixed",
+        }
+    }
+
+    /// 返回导出时建议使用的文件扩展名
+    pub fn file_extension(&self) -> &str {
+        match self.mime_type() {
+            "text/plain" => "txt",
+            "application/json" => "json",
+            "text/csv" => "csv",
+            "image/png" => "png",
+            "image/jpeg" => "jpg",
+            "image/gif" => "gif",
+            "image/bmp" => "bmp",
+            "image/webp" => "webp",
+            "image/svg+xml" => "svg",
+            _ => "bin",
+        }
+    }
+
+    /// 转换为便于阅读的文本
+    pub fn to_text(&self) -> String {
+        match self {
+            ToolOutput::Text(text) => text.clone(),
+            ToolOutput::Json(value) => {
+                serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+            }
+            ToolOutput::Table { headers, rows } => {
+                let mut lines = Vec::with_capacity(rows.len() + 1);
+                lines.push(headers.join("\t"));
+                lines.extend(rows.iter().map(|row| row.join("\t")));
+                lines.join("\n")
+            }
+            ToolOutput::Binary { mime_type, data } => {
+                tr!("output-binary", mime_type = mime_type, size = data.len())
+            }
+            ToolOutput::Image { mime_type, data } => {
+                tr!("output-image", mime_type = mime_type, size = data.len())
+            }
+            ToolOutput::Multiple(outputs) => outputs
+                .iter()
+                .map(|output| output.to_text())
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+        }
+    }
+
+    /// 转换为导出或传递给下一个工具时使用的字节
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            ToolOutput::Text(text) => text.clone().into_bytes(),
+            ToolOutput::Json(_) => self.to_text().into_bytes(),
+            ToolOutput::Table { headers, rows } => {
+                let mut csv = String::new();
+                for row in std::iter::once(headers).chain(rows.iter()) {
+                    let cells: Vec<String> = row.iter().map(|cell| escape_csv(cell)).collect();
+                    csv.push_str(&cells.join(","));
+                    csv.push('\n');
+                }
+                csv.into_bytes()
+            }
+            ToolOutput::Binary { data, .. } | ToolOutput::Image { data, .. } => data.clone(),
+            ToolOutput::Multiple(outputs) => {
+                let mut bytes = Vec::new();
+                for (i, output) in outputs.iter().enumerate() {
+                    if i > 0 {
+                        bytes.extend_from_slice(b"\n\n");
+                    }
+                    bytes.extend(output.to_bytes());
+                }
+                bytes
+            }
+        }
+    }
+}
+
+impl From<String> for ToolOutput {
+    fn from(text: String) -> Self {
+        ToolOutput::Text(text)
+    }
+}
+
+impl From<&str> for ToolOutput {
+    fn from(text: &str) -> Self {
+        ToolOutput::Text(text.to_string())
+    }
+}
+
+/// 按 CSV 规则转义单元格
+fn escape_csv(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+/// 以 base64 字符串序列化二进制数据
+pub(crate) mod base64_bytes {
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(data))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        STANDARD.decode(encoded).map_err(serde::de::Error::custom)
+    }
+}
+
+/// 以 base64 字符串序列化可选的二进制数据
+pub(crate) mod base64_option {
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        data: &Option<Vec<u8>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match data {
+            Some(data) => serializer.serialize_some(&STANDARD.encode(data)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Vec<u8>>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|encoded| STANDARD.decode(encoded).map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
+
 /// 工具执行结果
-pub type ToolResult = Result<Option<Vec<u8>>, ToolError>;
+pub type ToolResult = Result<Option<ToolOutput>, ToolError>;
+
+/// 可跨线程传递的装箱 Future
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// 工具描述，用于在插件协议等场景中传递工具的元信息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolDescriptor {
+    /// 工具标识，插件未提供时使用工具名称
+    #[serde(default)]
+    pub id: String,
+    /// 工具名称
+    pub name: String,
+    /// 工具描述
+    #[serde(default)]
+    pub description: String,
+    /// 工具类别
+    #[serde(default = "default_category")]
+    pub category: ToolCategory,
+    /// 参数规格
+    #[serde(default)]
+    pub parameters: Vec<ParameterSpec>,
+    /// 工具需要的能力
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub capabilities: Vec<Capability>,
+    /// 工具配置的规格
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub config: Vec<ParameterSpec>,
+    /// 各语言的名称、描述和参数说明，按语言标识索引
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub translations: HashMap<String, ToolTranslation>,
+}
+
+impl ToolDescriptor {
+    /// 根据工具生成描述
+    pub fn from_tool(tool: &dyn Tool) -> Self {
+        Self {
+            id: tool.id().to_string(),
+            name: tool.name().to_string(),
+            description: tool.description().to_string(),
+            category: tool.category(),
+            parameters: tool.parameters(),
+            capabilities: tool.capabilities(),
+            config: tool.config_schema(),
+            translations: i18n::LANGUAGES
+                .iter()
+                .map(|(language, _)| (language.to_string(), tool.translation(language)))
+                .filter(|(_, translation)| !translation.is_empty())
+                .collect(),
+        }
+    }
+
+    /// 指定语言下的翻译，描述中没有时从已加载的本地化资源中查找
+    pub fn translation(&self, language: &str) -> ToolTranslation {
+        self.translations.get(language).cloned().unwrap_or_else(|| {
+            ToolTranslation::lookup(language, self.tool_id(), &self.parameters, &self.config)
+        })
+    }
+
+    /// 工具标识，未提供时使用工具名称，使旧版插件仍可使用
+    pub fn tool_id(&self) -> &str {
+        if self.id.is_empty() {
+            &self.name
+        } else {
+            &self.id
+        }
+    }
+}
+
+/// 工具在某种语言下的名称、描述和参数说明，为空的项表示没有翻译
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToolTranslation {
+    /// 工具名称
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    /// 工具描述
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    /// 参数说明，按参数名索引
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub parameters: HashMap<String, String>,
+    /// 配置项说明，按配置项名称索引
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub config: HashMap<String, String>,
+}
+
+impl ToolTranslation {
+    /// 从已加载的本地化资源中查找工具的翻译
+    ///
+    /// 使用的文本标识为 `tool-<标识>-name`、`tool-<标识>-description`、
+    /// `tool-<标识>-param-<参数名>` 和 `tool-<标识>-config-<配置项名称>`，
+    /// 其中标识的 `.` 替换为 `-`，例如 `tool-text-case-name`。
+    pub fn lookup(
+        language: &str,
+        tool_id: &str,
+        parameters: &[ParameterSpec],
+        config: &[ParameterSpec],
+    ) -> Self {
+        let prefix = format!("tool-{}", tool_id.replace('.', "-"));
+        let lookup_specs = |kind: &str, specs: &[ParameterSpec]| {
+            specs
+                .iter()
+                .filter_map(|spec| {
+                    i18n::lookup(language, &format!("{}-{}-{}", prefix, kind, spec.name))
+                        .map(|text| (spec.name.clone(), text))
+                })
+                .collect()
+        };
+        Self {
+            name: i18n::lookup(language, &format!("{}-name", prefix)).unwrap_or_default(),
+            description: i18n::lookup(language, &format!("{}-description", prefix))
+                .unwrap_or_default(),
+            parameters: lookup_specs("param", parameters),
+            config: lookup_specs("config", config),
+        }
+    }
+
+    /// 是否没有任何翻译
+    pub fn is_empty(&self) -> bool {
+        self.name.is_empty()
+            && self.description.is_empty()
+            && self.parameters.is_empty()
+            && self.config.is_empty()
+    }
+}
+
+fn default_category() -> ToolCategory {
+    ToolCategory::Other
+}
 
 /// 工具特质/接口
 pub trait Tool: Debug + DynClone + Send + Sync {
-    /// 返回工具名称
+    /// 返回工具标识，例如 `text.analyze`
+    ///
+    /// 标识在所有工具中唯一且不随界面语言变化，用于查找工具和保存配置。
+    fn id(&self) -> &str;
+
+    /// 返回工具名称，仅用于显示
     fn name(&self) -> &str;
 
     /// 返回工具描述
@@ -60,10 +992,295 @@ pub trait Tool: Debug + DynClone + Send + Sync {
     /// 执行工具
     fn execute(&self, ctx: &ToolContext) -> ToolResult;
 
+    /// 返回工具参数规格
+    fn parameters(&self) -> Vec<ParameterSpec> {
+        Vec::new()
+    }
+
     /// 返回工具参数说明
     fn parameter_descriptions(&self) -> Vec<(String, String)> {
+        self.parameters()
+            .into_iter()
+            .map(|spec| (spec.name, spec.description))
+            .collect()
+    }
+
+    /// 返回工具需要的能力，默认不需要任何能力
+    fn capabilities(&self) -> Vec<Capability> {
+        Vec::new()
+    }
+
+    /// 返回工具配置的规格，默认没有配置
+    ///
+    /// 配置与参数使用相同的规格描述，区别是配置保存在应用配置中、对每次执行都生效，
+    /// 执行时通过 [`ToolContext::config`] 读取。
+    fn config_schema(&self) -> Vec<ParameterSpec> {
         Vec::new()
     }
+
+    /// 返回工具在指定语言下的名称、描述和参数说明
+    ///
+    /// 默认从已加载的本地化资源中查找，见 [`ToolTranslation::lookup`]。
+    fn translation(&self, language: &str) -> ToolTranslation {
+        ToolTranslation::lookup(
+            language,
+            self.id(),
+            &self.parameters(),
+            &self.config_schema(),
+        )
+    }
+
+    /// 返回当前界面语言下的工具名称，没有翻译时使用 `name`
+    fn localized_name(&self) -> String {
+        let translation = self.translation(&i18n::language());
+        if translation.name.is_empty() {
+            self.name().to_string()
+        } else {
+            translation.name
+        }
+    }
+
+    /// 返回当前界面语言下的工具描述，没有翻译时使用 `description`
+    fn localized_description(&self) -> String {
+        let translation = self.translation(&i18n::language());
+        if translation.description.is_empty() {
+            self.description().to_string()
+        } else {
+            translation.description
+        }
+    }
+
+    /// 返回参数说明使用当前界面语言的参数规格
+    fn localized_parameters(&self) -> Vec<ParameterSpec> {
+        let mut translation = self.translation(&i18n::language());
+        let mut parameters = self.parameters();
+        for spec in &mut parameters {
+            if let Some(description) = translation.parameters.remove(&spec.name) {
+                spec.description = description;
+            }
+        }
+        parameters
+    }
+
+    /// 返回配置项说明使用当前界面语言的配置规格
+    fn localized_config_schema(&self) -> Vec<ParameterSpec> {
+        let mut translation = self.translation(&i18n::language());
+        let mut schema = self.config_schema();
+        for spec in &mut schema {
+            if let Some(description) = translation.config.remove(&spec.name) {
+                spec.description = description;
+            }
+        }
+        schema
+    }
+
+    /// 异步执行工具
+    ///
+    /// 默认实现直接调用同步的 `execute`，使现有工具无需修改即可在异步路径上运行；
+    /// 需要真正异步执行的工具可以覆盖此方法。
+    fn execute_async<'a>(&'a self, ctx: &'a ToolContext) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move { self.execute(ctx) })
+    }
+
+    /// 按参数规格校验参数，返回补全默认值后的参数表
+    fn validate_parameters(
+        &self,
+        parameters: &HashMap<String, String>,
+    ) -> Result<HashMap<String, String>, ToolError> {
+        validate_parameters(&self.parameters(), parameters)
+    }
+
+    /// 校验参数并解析工具配置后执行工具，并在 `ctx.events` 上发布执行事件
+    fn validate_and_execute(&self, mut ctx: ToolContext) -> ToolResult {
+        let events = ctx.events.clone();
+        events.track_execution(self.id(), || {
+            ctx.parameters = self.validate_parameters(&ctx.parameters)?;
+            ctx.config =
+                ToolConfig::resolve(&self.config_schema(), ctx.tool_configs.get(self.id())).values;
+            self.execute(&ctx)
+        })
+    }
+
+    /// 与 [`Tool::validate_and_execute`] 相同，但通过 [`Tool::execute_async`] 执行工具
+    fn validate_and_execute_async<'a>(&'a self, mut ctx: ToolContext) -> BoxFuture<'a, ToolResult> {
+        Box::pin(async move {
+            let events = ctx.events.clone();
+            events
+                .track_execution_async(self.id(), async {
+                    ctx.parameters = self.validate_parameters(&ctx.parameters)?;
+                    ctx.config =
+                        ToolConfig::resolve(&self.config_schema(), ctx.tool_configs.get(self.id()))
+                            .values;
+                    self.execute_async(&ctx).await
+                })
+                .await
+        })
+    }
 }
 
 dyn_clone::clone_trait_object!(Tool);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameters(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn int_range_is_inclusive() {
+        let spec = ParameterSpec::int("count", "").with_range(Some(1.0), Some(10.0));
+
+        assert_eq!(spec.validate("1").unwrap(), "1");
+        assert_eq!(spec.validate(" 10 ").unwrap(), "10");
+        for value in ["0", "11", "-5"] {
+            let error = spec.validate(value).unwrap_err();
+            assert_eq!(error.code(), "parameter");
+            assert_eq!(error.parameter(), Some("count"));
+        }
+    }
+
+    #[test]
+    fn int_rejects_fractions_and_text() {
+        let spec = ParameterSpec::int("count", "");
+
+        assert!(spec.validate("1.5").is_err());
+        assert!(spec.validate("abc").is_err());
+        assert!(spec.validate("").is_err());
+    }
+
+    #[test]
+    fn float_range_and_non_finite_values() {
+        let spec = ParameterSpec::float("ratio", "").with_range(Some(0.0), Some(1.0));
+
+        assert_eq!(spec.validate("0.5").unwrap(), "0.5");
+        assert_eq!(spec.validate("1").unwrap(), "1");
+        assert!(spec.validate("1.01").is_err());
+        assert!(spec.validate("-0.1").is_err());
+        assert!(spec.validate("NaN").is_err());
+        assert!(spec.validate("inf").is_err());
+    }
+
+    #[test]
+    fn only_min_or_max_limits_one_side() {
+        let spec = ParameterSpec::int("count", "").with_range(Some(0.0), None);
+        assert!(spec.validate("-1").is_err());
+        assert_eq!(spec.validate("1000000").unwrap(), "1000000");
+
+        let spec = ParameterSpec::int("count", "").with_range(None, Some(0.0));
+        assert!(spec.validate("1").is_err());
+        assert_eq!(spec.validate("-1000000").unwrap(), "-1000000");
+    }
+
+    #[test]
+    fn bool_is_normalized() {
+        let spec = ParameterSpec::bool("flag", "");
+
+        for value in ["true", "YES", "1", "on"] {
+            assert_eq!(spec.validate(value).unwrap(), "true");
+        }
+        for value in ["false", "No", "0", "off"] {
+            assert_eq!(spec.validate(value).unwrap(), "false");
+        }
+        assert!(spec.validate("maybe").is_err());
+    }
+
+    #[test]
+    fn enum_matches_case_insensitively_and_returns_declared_value() {
+        let spec = ParameterSpec::enumeration("mode", "", &["Upper", "lower"]);
+
+        assert_eq!(spec.validate("upper").unwrap(), "Upper");
+        assert_eq!(spec.validate(" LOWER ").unwrap(), "lower");
+    }
+
+    #[test]
+    fn enum_rejects_unknown_value_with_hint() {
+        let spec = ParameterSpec::enumeration("mode", "", &["upper", "lower"]);
+
+        let error = spec.validate("uper").unwrap_err();
+        assert_eq!(error.code(), "parameter");
+        assert_eq!(error.parameter(), Some("mode"));
+        assert!(error.hint().is_some_and(|hint| hint.contains("upper")));
+
+        let error = spec.validate("something-else").unwrap_err();
+        assert!(error.hint().is_none());
+    }
+
+    #[test]
+    fn enum_without_allowed_values_rejects_everything() {
+        let spec = ParameterSpec::enumeration("mode", "", &[]);
+        assert!(spec.validate("").is_err());
+        assert!(spec.validate("any").is_err());
+    }
+
+    #[test]
+    fn pattern_is_checked_after_normalization() {
+        let spec = ParameterSpec::string("name", "").with_pattern("^[a-z]+$");
+        assert_eq!(spec.validate("abc").unwrap(), "abc");
+        assert!(spec.validate("ABC").is_err());
+
+        let spec = ParameterSpec::int("count", "").with_pattern("^[0-9]$");
+        assert_eq!(spec.validate(" 7").unwrap(), "7");
+        assert!(spec.validate("12").is_err());
+
+        let spec = ParameterSpec::string("name", "").with_pattern("(");
+        assert_eq!(spec.validate("abc").unwrap_err().code(), "parameter");
+    }
+
+    #[test]
+    fn missing_required_parameter_is_rejected() {
+        let specs = [ParameterSpec::string("text", "").required()];
+
+        let error = validate_parameters(&specs, &HashMap::new()).unwrap_err();
+        assert_eq!(error.code(), "parameter");
+        assert_eq!(error.parameter(), Some("text"));
+        assert!(error.hint().is_none());
+
+        // 空字符串视为未提供
+        let error = validate_parameters(&specs, &parameters(&[("text", "")])).unwrap_err();
+        assert_eq!(error.parameter(), Some("text"));
+    }
+
+    #[test]
+    fn missing_required_parameter_hints_at_misspelled_name() {
+        let specs = [ParameterSpec::string("pattern", "").required()];
+
+        let error = validate_parameters(&specs, &parameters(&[("patern", "a")])).unwrap_err();
+        assert_eq!(error.parameter(), Some("pattern"));
+        assert!(error.hint().is_some_and(|hint| hint.contains("pattern")));
+    }
+
+    #[test]
+    fn required_parameter_with_default_uses_default() {
+        let specs = [ParameterSpec::int("count", "").required().with_default("3")];
+
+        let validated = validate_parameters(&specs, &HashMap::new()).unwrap();
+        assert_eq!(validated.get("count").map(String::as_str), Some("3"));
+    }
+
+    #[test]
+    fn optional_parameters_are_normalized_removed_or_kept() {
+        let specs = [
+            ParameterSpec::bool("flag", ""),
+            ParameterSpec::string("empty", ""),
+        ];
+        let input = parameters(&[("flag", "yes"), ("empty", ""), ("extra", "value")]);
+
+        let validated = validate_parameters(&specs, &input).unwrap();
+        assert_eq!(validated.get("flag").map(String::as_str), Some("true"));
+        assert!(!validated.contains_key("empty"));
+        assert_eq!(validated.get("extra").map(String::as_str), Some("value"));
+    }
+
+    #[test]
+    fn invalid_default_is_reported() {
+        let specs = [ParameterSpec::int("count", "").with_default("many")];
+
+        let error = validate_parameters(&specs, &HashMap::new()).unwrap_err();
+        assert_eq!(error.parameter(), Some("count"));
+    }
+}
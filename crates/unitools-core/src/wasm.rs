@@ -0,0 +1,321 @@
+//! WebAssembly 沙箱插件
+//!
+//! 插件是一个不导入任何宿主函数的 `.wasm` 模块，因此无法访问文件系统、网络或进程。
+//! 模块需要导出以下内容：
+//!
+//! - `memory`：线性内存
+//! - `unitools_alloc(len: i32) -> i32`：分配 `len` 字节并返回起始地址，宿主用它写入请求
+//! - `unitools_describe() -> i64`：返回 JSON 编码的 [`PluginDescriptor`]
+//! - `unitools_execute(ptr: i32, len: i32) -> i64`：接收 JSON 编码的 [`ExecuteRequest`]，
+//!   返回 JSON 编码的 [`ExecuteResponse`]
+//!
+//! 返回 `i64` 的函数将结果所在位置打包为 `(ptr << 32) | len`。
+//!
+//! 每次调用都会创建新的实例，执行受指令配额（fuel）和内存上限约束，超出限制时返回
+//! `ToolError::PluginError`。
+
+use crate::capability::Capability;
+use crate::error::ToolError;
+use crate::plugin::Plugin;
+use crate::protocol::{ExecuteRequest, ExecuteResponse, PluginDescriptor};
+use crate::tool::{
+    ParameterSpec, Tool, ToolCategory, ToolContext, ToolDescriptor, ToolResult, ToolTranslation,
+};
+use crate::tr;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use wasmi::core::TrapCode;
+use wasmi::{Config, Engine, Linker, Memory, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+/// WASM 插件的资源限制
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WasmLimits {
+    /// 每次调用可消耗的指令配额
+    pub fuel: u64,
+    /// 线性内存上限（字节）
+    pub memory_bytes: usize,
+}
+
+impl Default for WasmLimits {
+    fn default() -> Self {
+        Self {
+            fuel: 1_000_000_000,
+            memory_bytes: 64 * 1024 * 1024,
+        }
+    }
+}
+
+/// 已编译的 WASM 模块
+struct WasmModule {
+    name: String,
+    engine: Engine,
+    module: Module,
+    limits: WasmLimits,
+}
+
+/// 单次调用使用的实例
+struct WasmInstance {
+    store: Store<StoreLimits>,
+    instance: wasmi::Instance,
+    memory: Memory,
+}
+
+impl WasmModule {
+    /// 创建受限制的新实例
+    fn instantiate(&self) -> Result<WasmInstance, ToolError> {
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(self.limits.memory_bytes)
+            .instances(1)
+            .build();
+        let mut store = Store::new(&self.engine, limits);
+        store.limiter(|limits| limits);
+        store
+            .set_fuel(self.limits.fuel)
+            .map_err(|e| self.error(tr!("wasm-fuel-failed", error = e)))?;
+
+        // 不提供任何宿主函数，模块只能进行纯计算
+        let linker = Linker::<StoreLimits>::new(&self.engine);
+        let instance = linker
+            .instantiate(&mut store, &self.module)
+            .and_then(|pre| pre.start(&mut store))
+            .map_err(|e| self.map_error(e))?;
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or_else(|| self.error(tr!("wasm-missing-export", export = "memory")))?;
+
+        Ok(WasmInstance {
+            store,
+            instance,
+            memory,
+        })
+    }
+
+    /// 调用 `unitools_describe`
+    fn describe(&self) -> Result<PluginDescriptor, ToolError> {
+        let mut instance = self.instantiate()?;
+        let describe = instance
+            .instance
+            .get_typed_func::<(), i64>(&instance.store, "unitools_describe")
+            .map_err(|e| {
+                self.error(tr!(
+                    "wasm-missing-function",
+                    export = "unitools_describe",
+                    error = e
+                ))
+            })?;
+        let packed = describe
+            .call(&mut instance.store, ())
+            .map_err(|e| self.map_error(e))?;
+        let json = self.read_packed(&instance, packed)?;
+
+        serde_json::from_slice(&json)
+            .map_err(|e| self.error(tr!("plugin-describe-invalid", error = e)))
+    }
+
+    /// 调用 `unitools_execute`
+    fn execute(&self, request: &ExecuteRequest) -> ToolResult {
+        let request =
+            serde_json::to_vec(request).map_err(|e| ToolError::FormatError(e.to_string()))?;
+        let mut instance = self.instantiate()?;
+
+        let alloc = instance
+            .instance
+            .get_typed_func::<i32, i32>(&instance.store, "unitools_alloc")
+            .map_err(|e| {
+                self.error(tr!(
+                    "wasm-missing-function",
+                    export = "unitools_alloc",
+                    error = e
+                ))
+            })?;
+        let execute = instance
+            .instance
+            .get_typed_func::<(i32, i32), i64>(&instance.store, "unitools_execute")
+            .map_err(|e| {
+                self.error(tr!(
+                    "wasm-missing-function",
+                    export = "unitools_execute",
+                    error = e
+                ))
+            })?;
+
+        let len =
+            i32::try_from(request.len()).map_err(|_| self.error(tr!("wasm-request-too-large")))?;
+        let ptr = alloc
+            .call(&mut instance.store, len)
+            .map_err(|e| self.map_error(e))?;
+        instance
+            .memory
+            .write(&mut instance.store, ptr as u32 as usize, &request)
+            .map_err(|e| self.error(tr!("wasm-write-failed", error = e)))?;
+
+        let packed = execute
+            .call(&mut instance.store, (ptr, len))
+            .map_err(|e| self.map_error(e))?;
+        let json = self.read_packed(&instance, packed)?;
+
+        let response: ExecuteResponse = serde_json::from_slice(&json)
+            .map_err(|e| self.error(tr!("plugin-result-invalid", error = e)))?;
+        response.into_result()
+    }
+
+    /// 按 `(ptr << 32) | len` 读取线性内存中的数据
+    fn read_packed(&self, instance: &WasmInstance, packed: i64) -> Result<Vec<u8>, ToolError> {
+        let ptr = (packed as u64 >> 32) as usize;
+        let len = (packed as u64 & 0xffff_ffff) as usize;
+        instance
+            .memory
+            .data(&instance.store)
+            .get(ptr..ptr.saturating_add(len))
+            .map(|data| data.to_vec())
+            .ok_or_else(|| self.error(tr!("wasm-out-of-bounds")))
+    }
+
+    /// 将运行时错误转换为插件错误
+    fn map_error(&self, error: wasmi::Error) -> ToolError {
+        match error.as_trap_code() {
+            Some(TrapCode::OutOfFuel) => self.error(tr!("plugin-out-of-fuel")),
+            Some(TrapCode::GrowthOperationLimited) => self.error(tr!("wasm-out-of-memory")),
+            _ => self.error(tr!("plugin-run-failed", error = error)),
+        }
+    }
+
+    fn error(&self, message: String) -> ToolError {
+        ToolError::PluginError(tr!("wasm-error", name = self.name, message = message))
+    }
+}
+
+/// 从 `.wasm` 模块加载的插件
+pub struct WasmPlugin {
+    descriptor: PluginDescriptor,
+    module: Arc<WasmModule>,
+}
+
+impl WasmPlugin {
+    /// 加载 WASM 插件
+    pub fn load<P: AsRef<Path>>(path: P, limits: WasmLimits) -> Result<Self, ToolError> {
+        let path = path.as_ref();
+        let bytes = fs::read(path)?;
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, &bytes[..]).map_err(|e| {
+            ToolError::PluginError(tr!("wasm-compile-failed", path = path.display(), error = e))
+        })?;
+
+        let module = Arc::new(WasmModule {
+            name,
+            engine,
+            module,
+            limits,
+        });
+        let descriptor = module.describe()?;
+
+        Ok(Self { descriptor, module })
+    }
+}
+
+impl Plugin for WasmPlugin {
+    fn name(&self) -> &str {
+        &self.descriptor.name
+    }
+
+    fn description(&self) -> &str {
+        &self.descriptor.description
+    }
+
+    fn version(&self) -> &str {
+        &self.descriptor.version
+    }
+
+    fn initialize(&mut self) -> Result<(), ToolError> {
+        Ok(())
+    }
+
+    fn get_tools(&self) -> Vec<Arc<dyn Tool>> {
+        self.descriptor
+            .tools
+            .iter()
+            .map(|descriptor| {
+                Arc::new(WasmTool {
+                    descriptor: descriptor.clone(),
+                    module: self.module.clone(),
+                }) as Arc<dyn Tool>
+            })
+            .collect()
+    }
+
+    fn cleanup(&mut self) -> Result<(), ToolError> {
+        Ok(())
+    }
+
+    fn is_sandboxed(&self) -> bool {
+        true
+    }
+}
+
+/// WASM 插件提供的工具
+#[derive(Clone)]
+struct WasmTool {
+    descriptor: ToolDescriptor,
+    module: Arc<WasmModule>,
+}
+
+impl std::fmt::Debug for WasmTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WasmTool")
+            .field("plugin", &self.module.name)
+            .field("tool", &self.descriptor.name)
+            .finish()
+    }
+}
+
+impl Tool for WasmTool {
+    fn id(&self) -> &str {
+        self.descriptor.tool_id()
+    }
+
+    fn name(&self) -> &str {
+        &self.descriptor.name
+    }
+
+    fn description(&self) -> &str {
+        &self.descriptor.description
+    }
+
+    fn category(&self) -> ToolCategory {
+        self.descriptor.category
+    }
+
+    fn parameters(&self) -> Vec<ParameterSpec> {
+        self.descriptor.parameters.clone()
+    }
+
+    fn capabilities(&self) -> Vec<Capability> {
+        self.descriptor.capabilities.clone()
+    }
+
+    fn config_schema(&self) -> Vec<ParameterSpec> {
+        self.descriptor.config.clone()
+    }
+
+    fn translation(&self, language: &str) -> ToolTranslation {
+        self.descriptor.translation(language)
+    }
+
+    fn execute(&self, ctx: &ToolContext) -> ToolResult {
+        ctx.cancellation.check()?;
+        self.module.execute(&ExecuteRequest {
+            tool: self.descriptor.tool_id().to_string(),
+            input: ctx.input_data.clone(),
+            parameters: ctx.parameters.clone(),
+            config: ctx.config.clone(),
+        })
+    }
+}
@@ -0,0 +1,48 @@
+//! 切换界面语言后生成的文本
+//!
+//! 当前语言是进程级的设置，放在单独的测试程序中，以免影响断言中文文本的单元测试。
+
+use unitools_core::ToolError;
+use unitools_core::i18n;
+use unitools_core::plugin::PluginManifest;
+use unitools_core::tool::ToolOutput;
+
+#[test]
+fn renders_errors_and_outputs_in_english() {
+    i18n::set_language("en-US").unwrap();
+
+    let error = PluginManifest::from_toml(
+        r#"
+        id = "demo"
+        version = "1.0.0"
+        entry = "demo.so"
+
+        [dependencies]
+        demo = "1"
+        "#,
+    )
+    .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Plugin error: Plugin 'demo' cannot depend on itself"
+    );
+
+    let error = ToolError::PipelineError {
+        step: 2,
+        tool: "Case Converter".to_string(),
+        source: Box::new(ToolError::ParameterError("bad mode".to_string())),
+    };
+    assert_eq!(
+        error.to_string(),
+        "Pipeline step 2 (Case Converter) failed: Invalid parameter: bad mode"
+    );
+
+    let output = ToolOutput::Binary {
+        mime_type: "application/octet-stream".to_string(),
+        data: vec![0; 3],
+    };
+    assert_eq!(
+        output.to_text(),
+        "[binary data: application/octet-stream, 3 bytes]"
+    );
+}
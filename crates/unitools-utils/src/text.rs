@@ -34,6 +34,30 @@ pub fn regex_find_all(text: &str, pattern: &str) -> Result<Vec<String>, ToolErro
     Ok(matches)
 }
 
+/// 使用正则表达式查找所有匹配，并返回每个匹配的起止字符位置
+pub fn regex_find_matches(
+    text: &str,
+    pattern: &str,
+) -> Result<Vec<(usize, usize, String)>, ToolError> {
+    let regex = Regex::new(pattern)
+        .map_err(|e| ToolError::ParseError(format!("无效的正则表达式: {}", e)))?;
+
+    let mut matches = Vec::new();
+    let mut last_byte = 0;
+    let mut last_char = 0;
+
+    for m in regex.find_iter(text) {
+        // 增量计算字符位置，避免对每个匹配都从头计数
+        let start = last_char + text[last_byte..m.start()].chars().count();
+        let end = start + m.as_str().chars().count();
+        matches.push((start, end, m.as_str().to_string()));
+        last_byte = m.end();
+        last_char = end;
+    }
+
+    Ok(matches)
+}
+
 /// 使用正则表达式替换文本
 pub fn regex_replace_all(
     text: &str,
@@ -0,0 +1,236 @@
+app-title = UniTools
+
+## Common
+
+common-error = Error: { $error }
+common-no-output = [no output]
+common-none = [none]
+common-not-set = [not set]
+common-run = Run
+common-clear = Clear
+common-cancel = Cancel
+common-export = Export...
+common-browse = Browse...
+common-pick-file = File...
+common-pick-folder = Folder...
+common-new = New
+common-save = Save
+common-delete = Delete
+common-remove = Remove
+common-choose-tool = Choose a tool...
+common-success = Succeeded
+common-failure = Failed
+common-version = Version: { $version }
+common-author = Author: { $author }
+
+## Sidebar
+
+nav-home = Home
+nav-pipeline = Pipelines
+nav-batch = Batch processing
+nav-categories = Categories
+nav-about = About
+nav-save-failed = Failed to save settings
+nav-config-unreadable = Configuration file is unreadable
+nav-settings = Settings
+
+## Home page
+
+home-welcome = Welcome to UniTools
+home-tagline = All your everyday tools in one place
+home-categories = Categories
+home-tool-count =
+    { $count ->
+        [one] 1 tool
+       *[other] { $count } tools
+    }
+home-view = View
+home-recent = Recently used tools
+home-no-recent = No tools used yet
+
+## About page
+
+about-heading = About UniTools
+about-tagline = A powerful, extensible cross-platform toolbox
+about-features = Highlights
+about-feature-integrated = • Many handy tools in a single application
+about-feature-plugins = • Extensible through plugins
+about-feature-platforms = • Cross-platform: Windows, macOS, Linux
+about-feature-lightweight = • Lightweight and fast
+about-feature-ui = • Simple, intuitive interface
+about-stack = Technology
+about-stack-rust = • The Rust programming language
+about-stack-egui = • egui/eframe for the cross-platform GUI
+about-stack-modular = • Modular architecture
+about-stack-plugins = • Dynamically loaded plugins
+about-license = License
+about-license-text = This software is released under the MIT license
+about-back = Back to home
+
+## Tool page
+
+tool-input = Input
+tool-parameters = Parameters
+tool-input-content = Input text
+tool-input-hint = Enter text...
+tool-output = Output
+tool-output-placeholder = Results will appear here after running the tool...
+tool-running = Running...
+param-not-selected = [not selected]
+output-rows =
+    { $count ->
+        [one] 1 row
+       *[other] { $count } rows
+    }
+output-binary-hint = Binary data cannot be displayed; use “Export...” to save it to a file
+output-image-error = Cannot decode image: { $error }
+export-done = Exported to: { $path }
+export-failed = Export failed: { $error }
+
+## Pipeline page
+
+pipeline-heading = Tool pipelines
+pipeline-tagline = Run several tools in order, feeding each step's output into the next
+pipeline-saved-list = Saved:
+pipeline-choose = Choose a pipeline...
+pipeline-loaded = Loaded pipeline: { $name }
+pipeline-name = Name:
+pipeline-name-required = Please enter a pipeline name first
+pipeline-saved = Pipeline '{ $name }' saved; click “Save settings” on the settings page to write it to the config file
+pipeline-deleted = Deleted pipeline: { $name }
+pipeline-steps = Steps
+pipeline-step = Step { $index }: { $tool }
+pipeline-tool-missing = Tool not found
+pipeline-add-step = Add step
+pipeline-output-placeholder = Results will appear here after running the pipeline...
+
+## Batch page
+
+batch-heading = Batch processing
+batch-tagline = Process every file in a directory or matching a glob with the same tool and parameters
+batch-aborted = Batch processing stopped unexpectedly
+batch-tool = Tool:
+batch-files = Files:
+batch-source-hint = Directory or glob pattern, e.g. docs/**/*.txt
+batch-output = Output:
+batch-mirror = Write to directory
+batch-in-place = Overwrite source files (keep .bak backups)
+batch-output-dir = Output directory:
+batch-workers = Threads:
+batch-workers-hint = 0 uses the number of CPU cores
+batch-start = Start
+batch-summary =
+    { $total ->
+        [one] 1 file
+       *[other] { $total } files
+    }: { $succeeded } succeeded, { $failed } failed
+
+## Settings page
+
+settings-heading = Settings
+settings-interface = Interface
+settings-theme = Theme:
+theme-light = Light
+theme-dark = Dark
+theme-system = System
+settings-language = Language:
+settings-user = User
+settings-username = User name:
+settings-working-directory = Default working directory:
+settings-plugins = Plugins
+settings-plugin-directory = Plugin directory:
+settings-default-directory = [default directory]
+settings-available-plugins = Available plugins:
+settings-plugin-authors = Authors: { $authors }
+settings-plugin-permissions-list = Permissions: { $permissions }
+settings-plugin-permissions = Plugin permissions:
+settings-plugin-pending = { $name } [pending approval]
+settings-approve-ellipsis = Approve...
+settings-revoke = Revoke
+settings-approve-title = Approve plugin permissions
+settings-approve-prompt = Plugin '{ $name }' requests the following permissions:
+settings-approve = Approve
+settings-plugin-load-failed = Failed to load: { $error }
+settings-tools = Tool settings
+settings-save = Save settings
+settings-reset = Restore defaults
+settings-source = Source: { $source }
+settings-locked = Locked by { $source }
+settings-saved = Settings saved
+settings-save-failed = Failed to save settings: { $error }
+settings-unsaved = Unsaved changes, saving shortly
+settings-config-unreadable = The configuration file is unreadable, changes will not be saved: { $error }
+settings-overwrite-config = Back up and overwrite configuration file
+
+## Command line
+
+describe-id = ID: { $id }
+describe-category = Category: { $category }
+describe-description = Description: { $description }
+describe-capabilities = Permissions: { $capabilities }
+describe-no-parameters = Parameters: none
+describe-parameters = Parameters:
+describe-config = Settings:
+describe-required = required
+describe-default = default: { $value }
+describe-choices = choices: { $values }
+cli-invalid-key-value = Expected key=value: { $value }
+cli-plugin-load-failed = Failed to load plugin: { $error }
+cli-tool-config = Tool settings: { $problem }
+cli-tool-not-found = Tool not found: { $id }
+cli-output-exists = { $path } already exists, use --force to overwrite it
+cli-config-migrated = Upgraded the configuration from version { $from } to version { $to }
+cli-config-converted = Converted { $input } ({ $input_format }) to { $output } ({ $output_format })
+cli-read-failed = Cannot read { $path }: { $error }
+cli-write-failed = Cannot write { $path }: { $error }
+cli-batch-failed =
+    { $count ->
+        [one] 1 file failed
+       *[other] { $count } files failed
+    }, the first failure: { $path }: { $error }
+cli-binary-to-terminal = { $output }; use --output to choose an output file or redirect standard output
+
+## Built-in tools
+
+tool-no-input = No input text provided
+tool-invalid-mode = Invalid mode: { $mode }
+text-analyze-result =
+    Text analysis:
+
+    Characters: { $chars }
+    Words: { $words }
+    Lines: { $lines }
+text-regex-missing-pattern = No regular expression provided
+text-regex-no-match = No matches found
+text-regex-column-index = #
+text-regex-column-start = Start
+text-regex-column-end = End
+text-regex-column-match = Match
+file-missing-path = No file path provided
+file-not-found = File not found: { $path }
+file-directory-not-found = Directory not found: { $path }
+file-no-extension = none
+file-unknown-name = unknown
+file-info-result =
+    Name: { $name }
+    Path: { $path }
+    Size: { $size } bytes
+    Extension: { $extension }
+file-reading = Reading { $path }
+file-size = { $size } bytes
+file-unknown-size = unknown size
+file-type-directory = Directory
+file-type-file = File
+file-column-name = Name
+file-column-type = Type
+file-column-size = Size
+
+## Configuration file
+
+config-reloaded = Applied changes from the configuration file
+config-reload-failed = Could not read the configuration file, keeping the current settings: { $error }
+config-load-failed = Could not read the configuration file { $path }; using default settings. Settings will not be saved until the file is fixed or you confirm overwriting it on the settings page: { $error }
+config-overwritten = Backed up the original configuration file as { $backup }
+plugin-watch-failed = Could not watch the plugin directory, plugin changes will not be reloaded automatically: { $error }
+config-watch-failed = Could not watch the configuration file, external changes will not be applied automatically: { $error }
+config-backup-failed = Could not back up the configuration file, it was not overwritten: { $error }
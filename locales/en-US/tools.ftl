@@ -0,0 +1,23 @@
+## 内置工具的翻译，文本标识的格式见 ToolTranslation::lookup
+
+tool-text-analyze-name = Text Analyzer
+tool-text-analyze-description = Analyze text and count characters, words and lines
+
+tool-text-case-name = Case Converter
+tool-text-case-description = Convert text to upper or lower case
+tool-text-case-param-mode = Conversion mode: upper=upper case, lower=lower case
+
+tool-text-regex-name = Regular Expressions
+tool-text-regex-description = Find or replace text with regular expressions
+tool-text-regex-param-pattern = Regular expression pattern
+tool-text-regex-param-replacement = Replacement text (replace mode only)
+tool-text-regex-param-mode = Operation: find=find matches, replace=replace matches
+tool-text-regex-config-case_insensitive = Ignore case when matching
+
+tool-file-info-name = File Info
+tool-file-info-description = Show basic file information (size, type, etc.)
+tool-file-info-param-path = File path
+
+tool-file-browse-name = File Browser
+tool-file-browse-description = Browse a directory and list its files and subdirectories
+tool-file-browse-param-directory = Directory to browse
@@ -0,0 +1,220 @@
+app-title = UniTools 工具箱
+
+## 通用
+
+common-error = 错误: { $error }
+common-no-output = [无输出]
+common-none = [无]
+common-not-set = [未设置]
+common-run = 执行
+common-clear = 清除
+common-cancel = 取消
+common-export = 导出...
+common-browse = 浏览...
+common-pick-file = 文件...
+common-pick-folder = 目录...
+common-new = 新建
+common-save = 保存
+common-delete = 删除
+common-remove = 移除
+common-choose-tool = 选择工具...
+common-success = 成功
+common-failure = 失败
+common-version = 版本: { $version }
+common-author = 作者: { $author }
+
+## 侧边栏
+
+nav-home = 主页
+nav-pipeline = 流水线
+nav-batch = 批量处理
+nav-categories = 工具分类
+nav-about = 关于
+nav-save-failed = 保存设置失败
+nav-config-unreadable = 配置文件无法读取
+nav-settings = 设置
+
+## 主页
+
+home-welcome = 欢迎使用 UniTools 工具箱
+home-tagline = 一站式解决各种工具需求
+home-categories = 工具分类
+home-tool-count = { $count }个工具
+home-view = 查看
+home-recent = 最近使用的工具
+home-no-recent = 暂无使用记录
+
+## 关于
+
+about-heading = 关于 UniTools 工具箱
+about-tagline = 一个功能强大、可扩展的跨平台工具集合
+about-features = 功能亮点
+about-feature-integrated = • 多种实用工具集成于一个应用
+about-feature-plugins = • 插件系统支持功能扩展
+about-feature-platforms = • 跨平台支持：Windows, macOS, Linux
+about-feature-lightweight = • 轻量级且高性能
+about-feature-ui = • 直观易用的界面
+about-stack = 技术栈
+about-stack-rust = • Rust 编程语言
+about-stack-egui = • egui/eframe 用于跨平台GUI
+about-stack-modular = • 模块化架构设计
+about-stack-plugins = • 插件系统支持动态加载
+about-license = 开源许可
+about-license-text = 本软件基于 MIT 许可证开源发布
+about-back = 返回主页
+
+## 工具页面
+
+tool-input = 输入
+tool-parameters = 参数
+tool-input-content = 输入内容
+tool-input-hint = 输入文本内容...
+tool-output = 输出
+tool-output-placeholder = 运行工具后将在此显示结果...
+tool-running = 正在执行...
+param-not-selected = [未选择]
+output-rows = 共 { $count } 行
+output-binary-hint = 二进制数据无法直接显示，请使用“导出...”保存到文件
+output-image-error = 无法解码图像: { $error }
+export-done = 已导出到: { $path }
+export-failed = 导出失败: { $error }
+
+## 流水线页面
+
+pipeline-heading = 工具流水线
+pipeline-tagline = 按顺序执行多个工具，前一步的输出作为下一步的输入
+pipeline-saved-list = 已保存:
+pipeline-choose = 选择流水线...
+pipeline-loaded = 已加载流水线: { $name }
+pipeline-name = 名称:
+pipeline-name-required = 请先填写流水线名称
+pipeline-saved = 流水线 '{ $name }' 已保存，在设置页点击“保存设置”后写入配置文件
+pipeline-deleted = 已删除流水线: { $name }
+pipeline-steps = 步骤
+pipeline-step = 第 { $index } 步: { $tool }
+pipeline-tool-missing = 找不到该工具
+pipeline-add-step = 添加步骤
+pipeline-output-placeholder = 运行流水线后将在此显示结果...
+
+## 批量处理页面
+
+batch-heading = 批量处理
+batch-tagline = 用同一个工具和参数处理目录或通配符匹配的所有文件
+batch-aborted = 批量处理意外终止
+batch-tool = 工具:
+batch-files = 文件:
+batch-source-hint = 目录或通配符模式，例如 docs/**/*.txt
+batch-output = 输出:
+batch-mirror = 写入目录
+batch-in-place = 覆盖源文件（保留 .bak 备份）
+batch-output-dir = 输出目录:
+batch-workers = 线程数:
+batch-workers-hint = 0 表示使用 CPU 核心数
+batch-start = 开始处理
+batch-summary = 共 { $total } 个文件: { $succeeded } 个成功，{ $failed } 个失败
+
+## 设置页面
+
+settings-heading = 设置
+settings-interface = 界面设置
+settings-theme = 主题:
+theme-light = 浅色
+theme-dark = 深色
+theme-system = 跟随系统
+settings-language = 语言:
+settings-user = 用户设置
+settings-username = 用户名:
+settings-working-directory = 默认工作目录:
+settings-plugins = 插件设置
+settings-plugin-directory = 插件目录:
+settings-default-directory = [使用默认目录]
+settings-available-plugins = 可用的插件:
+settings-plugin-authors = 作者: { $authors }
+settings-plugin-permissions-list = 权限: { $permissions }
+settings-plugin-permissions = 插件权限:
+settings-plugin-pending = { $name } [待批准]
+settings-approve-ellipsis = 批准...
+settings-revoke = 撤销
+settings-approve-title = 批准插件权限
+settings-approve-prompt = 插件 '{ $name }' 请求以下权限:
+settings-approve = 批准
+settings-plugin-load-failed = 加载失败: { $error }
+settings-tools = 工具设置
+settings-save = 保存设置
+settings-reset = 恢复默认
+settings-source = 来源: { $source }
+settings-locked = 已由{ $source }锁定
+settings-saved = 设置已保存
+settings-save-failed = 保存设置失败: { $error }
+settings-unsaved = 有未保存的修改，稍后自动保存
+settings-config-unreadable = 配置文件无法读取，修改不会保存: { $error }
+settings-overwrite-config = 备份并覆盖配置文件
+
+## 命令行
+
+describe-id = 标识: { $id }
+describe-category = 类别: { $category }
+describe-description = 说明: { $description }
+describe-capabilities = 权限: { $capabilities }
+describe-no-parameters = 参数: 无
+describe-parameters = 参数:
+describe-config = 配置:
+describe-required = 必填
+describe-default = 默认: { $value }
+describe-choices = 可选: { $values }
+cli-invalid-key-value = 参数格式应为 key=value: { $value }
+cli-plugin-load-failed = 加载插件失败: { $error }
+cli-tool-config = 工具配置: { $problem }
+cli-tool-not-found = 工具不存在: { $id }
+cli-output-exists = { $path } 已存在，使用 --force 覆盖
+cli-config-migrated = 配置已从版本 { $from } 升级到版本 { $to }
+cli-config-converted = 已将 { $input }（{ $input_format }）转换为 { $output }（{ $output_format }）
+cli-read-failed = 无法读取 { $path }: { $error }
+cli-write-failed = 无法写入 { $path }: { $error }
+cli-batch-failed = { $count } 个文件处理失败，首个失败: { $path }: { $error }
+cli-binary-to-terminal = { $output }，请使用 --output 指定输出文件或重定向标准输出
+
+## 内置工具
+
+tool-no-input = 没有提供输入文本
+tool-invalid-mode = 无效的模式参数: { $mode }
+text-analyze-result =
+    文本分析结果:
+
+    字符数: { $chars }
+    单词数: { $words }
+    行数: { $lines }
+text-regex-missing-pattern = 没有提供正则表达式模式
+text-regex-no-match = 未找到匹配项
+text-regex-column-index = 序号
+text-regex-column-start = 起始位置
+text-regex-column-end = 结束位置
+text-regex-column-match = 匹配内容
+file-missing-path = 没有提供文件路径
+file-not-found = 文件不存在: { $path }
+file-directory-not-found = 目录不存在: { $path }
+file-no-extension = 无
+file-unknown-name = 未知
+file-info-result =
+    文件名: { $name }
+    路径: { $path }
+    大小: { $size } 字节
+    扩展名: { $extension }
+file-reading = 正在读取 { $path }
+file-size = { $size } 字节
+file-unknown-size = 未知大小
+file-type-directory = 目录
+file-type-file = 文件
+file-column-name = 名称
+file-column-type = 类型
+file-column-size = 大小
+
+## 配置文件
+
+config-reloaded = 已应用配置文件的修改
+config-reload-failed = 无法读取配置文件，继续使用当前设置: { $error }
+config-load-failed = 无法读取配置文件 { $path }，已使用默认设置，修复该文件或在设置页面确认覆盖之前不会保存设置: { $error }
+config-overwritten = 已将原配置文件备份为 { $backup }
+plugin-watch-failed = 无法监视插件目录，插件文件的变化不会自动重新加载: { $error }
+config-watch-failed = 无法监视配置文件，外部的修改不会自动应用: { $error }
+config-backup-failed = 无法备份配置文件，未覆盖: { $error }
@@ -1,51 +1,187 @@
 use eframe::{Frame, egui};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use unitools_core::{
-    config::AppConfig,
+    capability::Capability,
+    config::{AppConfig, LayeredConfig},
+    event::{Event, EventBus},
+    pipeline::PipelineDefinition,
     plugin::PluginManager,
-    tool::{Tool, ToolCategory},
+    tool::{Tool, ToolCategory, ToolContext},
 };
 
 use crate::ui::{self, Page};
+use unitools_core::{i18n, tr};
+
+/// 插件健康检查的间隔
+const PLUGIN_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 插件目录变化后等待多久再重新加载，避免文件写入过程中反复加载
+const PLUGIN_RELOAD_DELAY: Duration = Duration::from_millis(500);
+
+/// 配置修改后等待多久再自动保存，避免连续输入时反复写入文件
+const CONFIG_SAVE_DELAY: Duration = Duration::from_secs(1);
+
+/// 配置文件变化后等待多久再重新读取，避免文件写入过程中读到不完整的内容
+const CONFIG_RELOAD_DELAY: Duration = Duration::from_millis(300);
+
+/// 普通通知显示多久后自动关闭，错误通知需要手动关闭
+const NOTIFICATION_DURATION: Duration = Duration::from_secs(5);
+
+/// 显示在窗口角落、不阻塞操作的通知
+pub struct Notification {
+    pub message: String,
+    pub is_error: bool,
+    pub shown_at: Instant,
+}
+
+impl Notification {
+    /// 是否已到自动关闭的时间
+    pub fn is_expired(&self) -> bool {
+        !self.is_error && self.shown_at.elapsed() >= NOTIFICATION_DURATION
+    }
+}
 
 /// 主应用状态
 pub struct UniToolsApp {
     pub config: Arc<Mutex<AppConfig>>,
+    /// 启动时加载的各配置层，用于显示配置来源和保存用户配置
+    pub layers: LayeredConfig,
+    /// 启动时加载的用户配置文件，修改后的配置保存到这里
+    config_path: PathBuf,
+    /// 最近一次检查时的配置内容，用于发现配置的修改
+    observed_config: serde_json::Value,
+    /// 最近一次修改配置的时间，已保存时为 `None`
+    config_changed_at: Option<Instant>,
+    /// 最近一次保存配置的结果
+    save_status: Option<Result<(), String>>,
+    /// 用户配置文件无法读取时的错误，此时不保存配置，以免覆盖用户的文件
+    config_load_error: Option<String>,
+    /// 与配置文件内容一致的配置，用于合并外部修改和界面上未保存的修改
+    synced_config: AppConfig,
+    /// 最近一次发布配置修改事件时的配置
+    published_config: AppConfig,
+    /// 配置文件监视器，需要在应用运行期间保持存活
+    config_watcher: Option<notify::RecommendedWatcher>,
+    /// 最近一次检测到配置文件变化的时间
+    config_file_changed_at: Arc<Mutex<Option<Instant>>>,
+    /// 当前显示的通知
+    pub notification: Option<Notification>,
     plugin_manager: Arc<PluginManager>,
     tools: Vec<Arc<dyn Tool>>,
     current_page: Page,
+    /// 当前工具的标识
     current_tool: Option<String>,
     pub categories: HashMap<ToolCategory, Vec<Arc<dyn Tool>>>,
+    pub plugin_errors: Vec<String>,
+    /// 已重置为默认值的无效工具配置
+    pub tool_config_problems: Vec<String>,
+    loaded_plugins: Vec<crate::plugins::LoadedPlugin>,
+    /// 插件目录监视器，需要在应用运行期间保持存活
+    plugin_watcher: Option<notify::RecommendedWatcher>,
+    /// 最近一次检测到插件目录变化的时间
+    plugins_changed_at: Arc<Mutex<Option<Instant>>>,
+    /// 插件健康检查发现的问题，在界面刷新时显示为通知
+    plugin_health_problems: Arc<Mutex<Vec<String>>>,
+    runtime: tokio::runtime::Runtime,
 }
 
 impl UniToolsApp {
     /// 创建新的应用实例
-    pub fn new(cc: &eframe::CreationContext<'_>, config: AppConfig) -> Self {
+    ///
+    /// `loaded` 为启动时加载的配置，其中的错误和提示显示为通知，见
+    /// [`crate::config::load_layered_config`]。
+    pub fn new(
+        cc: &eframe::CreationContext<'_>,
+        loaded: crate::config::LoadedConfig,
+        config_path: PathBuf,
+    ) -> Self {
+        let crate::config::LoadedConfig {
+            layers,
+            user_error,
+            messages,
+        } = loaded;
+        let config = layers.config().clone();
+        let observed_config = serde_json::to_value(&config).unwrap_or_default();
+
         // 设置默认主题
         ui::setup_theme(&cc.egui_ctx, config.theme);
 
         // 创建插件管理器
-        let plugin_manager = Arc::new(PluginManager::new());
+        let plugin_manager = Arc::new(crate::plugins::create_plugin_manager());
 
         // 加载内置工具
         let mut app = Self {
-            config: Arc::new(Mutex::new(config)),
+            config: Arc::new(Mutex::new(config.clone())),
+            layers,
+            config_path,
+            observed_config,
+            config_changed_at: None,
+            save_status: None,
+            config_load_error: None,
+            synced_config: config.clone(),
+            published_config: config,
+            config_watcher: None,
+            config_file_changed_at: Arc::new(Mutex::new(None)),
+            notification: None,
             plugin_manager: plugin_manager.clone(),
             tools: Vec::new(),
             current_page: Page::Home,
             current_tool: None,
             categories: HashMap::new(),
+            plugin_errors: Vec::new(),
+            tool_config_problems: Vec::new(),
+            loaded_plugins: Vec::new(),
+            plugin_watcher: None,
+            plugins_changed_at: Arc::new(Mutex::new(None)),
+            plugin_health_problems: Arc::new(Mutex::new(Vec::new())),
+            runtime: tokio::runtime::Runtime::new().expect("无法创建异步运行时"),
         };
 
         // 加载内置工具
         app.load_builtin_tools();
 
-        // TODO: 加载插件工具
+        // 加载插件工具
+        app.load_plugin_tools();
+
+        // 插件加载后再发布内置工具的注册事件，使插件也能收到
+        for tool in crate::tools::get_builtin_tools() {
+            app.events().publish(Event::ToolRegistered {
+                tool: tool.id().to_string(),
+                plugin: None,
+            });
+        }
 
         // 按类别分组工具
         app.categorize_tools();
 
+        // 检查工具配置
+        app.check_tool_configs();
+
+        // 监视插件目录并定期检查插件状态
+        app.watch_plugins(&cc.egui_ctx);
+        app.spawn_plugin_health_check(&cc.egui_ctx);
+
+        // 监视配置文件，应用外部的修改
+        app.watch_config(&cc.egui_ctx);
+
+        // 加载配置时的问题在通知中一并显示
+        let mut problems = Vec::new();
+        if let Some(error) = &user_error {
+            problems.push(tr!(
+                "config-load-failed",
+                path = app.config_path.display(),
+                error = error
+            ));
+        }
+        problems.extend(messages);
+        if !problems.is_empty() {
+            app.notify(problems.join("\n"), true);
+        }
+        app.config_load_error = user_error;
+
         app
     }
 
@@ -56,37 +192,421 @@ impl UniToolsApp {
         self.tools.extend(builtin_tools);
     }
 
+    /// 从插件目录加载已启用的插件
+    fn load_plugin_tools(&mut self) {
+        let config = self.config.lock().unwrap().clone();
+        self.plugin_errors =
+            crate::plugins::sync_plugins(&self.plugin_manager, &config, &mut self.loaded_plugins);
+        for error in &self.plugin_errors {
+            log::warn!("加载插件失败: {}", error);
+        }
+
+        self.tools.extend(self.plugin_manager.get_tools());
+    }
+
+    /// 重新加载发生变化的插件，并刷新工具列表和分类
+    fn reload_plugins(&mut self) {
+        self.tools.clear();
+        self.categories.clear();
+        self.load_builtin_tools();
+        self.load_plugin_tools();
+        self.categorize_tools();
+        self.check_tool_configs();
+    }
+
+    /// 检查已加载工具保存的配置，无效的配置项恢复为默认值
+    fn check_tool_configs(&mut self) {
+        self.tool_config_problems = self.config.lock().unwrap().repair_tool_configs(&self.tools);
+        for problem in &self.tool_config_problems {
+            log::warn!("工具配置: {}", problem);
+        }
+    }
+
+    /// 监视插件目录，目录变化时通知界面重新加载插件
+    fn watch_plugins(&mut self, ctx: &egui::Context) {
+        let dir = crate::plugins::plugin_directory(&self.config.lock().unwrap());
+        let changed_at = self.plugins_changed_at.clone();
+        let ctx = ctx.clone();
+
+        let watcher = crate::plugins::watch_plugin_directory(&dir, move || {
+            *changed_at.lock().unwrap() = Some(Instant::now());
+            ctx.request_repaint_after(PLUGIN_RELOAD_DELAY);
+        });
+        match watcher {
+            Ok(watcher) => self.plugin_watcher = Some(watcher),
+            Err(e) => {
+                log::warn!("无法监视插件目录 {}: {}", dir.display(), e);
+                self.notify(tr!("plugin-watch-failed", error = e), true);
+            }
+        }
+    }
+
+    /// 定期检查插件状态，重启无响应的外部进程
+    fn spawn_plugin_health_check(&self, ctx: &egui::Context) {
+        let plugin_manager = self.plugin_manager.clone();
+        let reported = self.plugin_health_problems.clone();
+        let ctx = ctx.clone();
+        self.runtime.spawn(async move {
+            let mut interval = tokio::time::interval(PLUGIN_HEALTH_CHECK_INTERVAL);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                let plugin_manager = plugin_manager.clone();
+                if let Ok(problems) =
+                    tokio::task::spawn_blocking(move || plugin_manager.check_health()).await
+                    && !problems.is_empty()
+                {
+                    for problem in &problems {
+                        log::warn!("{}", problem);
+                    }
+                    reported.lock().unwrap().extend(problems);
+                    ctx.request_repaint();
+                }
+            }
+        });
+    }
+
+    /// 将插件健康检查发现的问题显示为通知
+    fn show_plugin_health_problems(&mut self) {
+        let problems = std::mem::take(&mut *self.plugin_health_problems.lock().unwrap());
+        if !problems.is_empty() {
+            self.notify(problems.join("\n"), true);
+        }
+    }
+
+    /// 获取因权限未批准而未能加载的插件及其需要的能力
+    pub fn pending_plugin_approvals(&self) -> Vec<(String, Vec<Capability>)> {
+        self.plugin_manager.pending_approvals()
+    }
+
+    /// 请求在下一次刷新界面时重新加载插件，例如启用或停用插件后
+    pub fn request_plugin_reload(&self) {
+        *self.plugins_changed_at.lock().unwrap() = Some(Instant::now());
+    }
+
+    /// 插件目录变化并稳定一段时间后重新加载插件
+    fn reload_plugins_if_changed(&mut self, ctx: &egui::Context) {
+        let mut changed_at = self.plugins_changed_at.lock().unwrap();
+        let Some(time) = *changed_at else {
+            return;
+        };
+
+        let elapsed = time.elapsed();
+        if elapsed < PLUGIN_RELOAD_DELAY {
+            ctx.request_repaint_after(PLUGIN_RELOAD_DELAY - elapsed);
+            return;
+        }
+
+        *changed_at = None;
+        drop(changed_at);
+        self.reload_plugins();
+    }
+
+    /// 立即保存配置到用户配置文件
+    ///
+    /// 用户配置文件无法读取时不保存，需要先通过 [`UniToolsApp::overwrite_unreadable_config`]
+    /// 确认覆盖。
+    pub fn save_config(&mut self) {
+        if self.config_load_error.is_some() {
+            return;
+        }
+        let config = self.config.lock().unwrap().clone();
+        let result = crate::config::save_config(&self.layers, &config, &self.config_path);
+        if let Err(e) = &result {
+            log::error!("保存配置到 {} 失败: {}", self.config_path.display(), e);
+        }
+        self.observed_config = serde_json::to_value(&config).unwrap_or_default();
+        self.config_changed_at = None;
+        if result.is_ok() {
+            self.synced_config = config;
+        }
+        self.save_status = Some(result);
+    }
+
+    /// 用户配置文件无法读取时的错误，文件修复或确认覆盖后为 `None`
+    pub fn config_load_error(&self) -> Option<&str> {
+        self.config_load_error.as_deref()
+    }
+
+    /// 确认覆盖无法读取的用户配置文件，先备份原文件，再保存当前的配置
+    pub fn overwrite_unreadable_config(&mut self) {
+        if self.config_path.exists() {
+            match crate::config::backup_unreadable_config(&self.config_path) {
+                Ok(backup) => {
+                    self.notify(tr!("config-overwritten", backup = backup.display()), false)
+                }
+                Err(e) => {
+                    self.notify(tr!("config-backup-failed", error = e), true);
+                    return;
+                }
+            }
+        }
+        self.config_load_error = None;
+        self.save_config();
+    }
+
+    /// 显示通知，替换当前的通知
+    pub fn notify(&mut self, message: String, is_error: bool) {
+        self.notification = Some(Notification {
+            message,
+            is_error,
+            shown_at: Instant::now(),
+        });
+    }
+
+    /// 监视配置文件，文件变化时通知界面重新读取
+    fn watch_config(&mut self, ctx: &egui::Context) {
+        let changed_at = self.config_file_changed_at.clone();
+        let ctx = ctx.clone();
+
+        let watcher = crate::config::watch_config_file(&self.config_path, move || {
+            *changed_at.lock().unwrap() = Some(Instant::now());
+            ctx.request_repaint_after(CONFIG_RELOAD_DELAY);
+        });
+        match watcher {
+            Ok(watcher) => self.config_watcher = Some(watcher),
+            Err(e) => {
+                log::warn!("无法监视配置文件 {}: {}", self.config_path.display(), e);
+                self.notify(tr!("config-watch-failed", error = e), true);
+            }
+        }
+    }
+
+    /// 配置文件变化并稳定一段时间后重新读取，并与界面上未保存的修改合并
+    ///
+    /// 配置文件无法解析时保留当前的配置并显示错误通知，在文件修复或确认覆盖之前不再保存配置。
+    fn reload_config_if_changed(&mut self, ctx: &egui::Context) {
+        let mut changed_at = self.config_file_changed_at.lock().unwrap();
+        let Some(time) = *changed_at else {
+            return;
+        };
+
+        let elapsed = time.elapsed();
+        if elapsed < CONFIG_RELOAD_DELAY {
+            ctx.request_repaint_after(CONFIG_RELOAD_DELAY - elapsed);
+            return;
+        }
+        *changed_at = None;
+        drop(changed_at);
+
+        // 文件被删除时（例如替换文件的过程中）等待下一次变化
+        if !self.config_path.exists() {
+            return;
+        }
+
+        let (layers, report) = match self.layers.reload_user(&self.config_path) {
+            Ok(result) => result,
+            Err(e) => {
+                self.notify(tr!("config-reload-failed", error = &e), true);
+                self.config_load_error = Some(e.to_string());
+                return;
+            }
+        };
+        self.config_load_error = None;
+        let warnings: Vec<String> = report
+            .warnings
+            .iter()
+            .chain(layers.warnings())
+            .cloned()
+            .collect();
+        let incoming = layers.config().clone();
+        self.layers = layers;
+
+        // 自己保存配置也会触发文件变化，内容没有变化时不需要处理
+        let to_value = |config: &AppConfig| serde_json::to_value(config).unwrap_or_default();
+        if to_value(&incoming) == to_value(&self.synced_config) {
+            return;
+        }
+
+        let mut config = self.config.lock().unwrap();
+        let previous = config.clone();
+        let has_local_changes =
+            self.config_changed_at.is_some() || to_value(&previous) != self.observed_config;
+        let merged = AppConfig::merge(&self.synced_config, &previous, &incoming);
+        *config = merged.clone();
+        drop(config);
+
+        self.synced_config = incoming;
+        if !has_local_changes {
+            // 没有未保存的修改时合并结果与文件一致，不需要写回
+            self.observed_config = to_value(&merged);
+        }
+        self.apply_config_changes(ctx, &previous, &merged);
+
+        if warnings.is_empty() {
+            self.notify(tr!("config-reloaded"), false);
+        } else {
+            self.notify(warnings.join("\n"), true);
+        }
+    }
+
+    /// 使配置的变化生效
+    fn apply_config_changes(
+        &mut self,
+        ctx: &egui::Context,
+        previous: &AppConfig,
+        current: &AppConfig,
+    ) {
+        if previous.theme != current.theme {
+            ui::setup_theme(ctx, current.theme);
+        }
+        if previous.language != current.language && i18n::set_language(&current.language).is_ok() {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Title(tr!("app-title")));
+        }
+        if previous.plugin_directory != current.plugin_directory {
+            self.watch_plugins(ctx);
+            self.request_plugin_reload();
+        }
+        if previous.enabled_plugins != current.enabled_plugins
+            || previous.plugin_permissions != current.plugin_permissions
+        {
+            self.request_plugin_reload();
+        }
+        if previous.tool_configs != current.tool_configs {
+            self.check_tool_configs();
+        }
+        self.publish_config_changes();
+    }
+
+    /// 配置与最近一次发布时不同时发布配置修改事件，主题变化时同时发布主题切换事件
+    fn publish_config_changes(&mut self) {
+        let config = self.config.lock().unwrap().clone();
+        let changed = config.changed_fields(&self.published_config);
+        if changed.is_empty() {
+            return;
+        }
+
+        let events = self.events();
+        if config.theme != self.published_config.theme {
+            events.publish(Event::ThemeChanged {
+                theme: config.theme,
+            });
+        }
+        events.publish(Event::ConfigChanged {
+            changed,
+            config: Box::new(config.clone()),
+        });
+        self.published_config = config;
+    }
+
+    /// 最近一次保存配置的结果，本次运行尚未保存过时为 `None`
+    pub fn save_status(&self) -> Option<&Result<(), String>> {
+        self.save_status.as_ref()
+    }
+
+    /// 是否有尚未保存的配置修改
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.config_changed_at.is_some()
+    }
+
+    /// 配置修改后稳定一段时间再自动保存
+    fn save_config_if_changed(&mut self, ctx: &egui::Context) {
+        let current = serde_json::to_value(&*self.config.lock().unwrap()).unwrap_or_default();
+        if current != self.observed_config {
+            self.observed_config = current;
+            self.config_changed_at = Some(Instant::now());
+            self.publish_config_changes();
+        }
+
+        // 用户配置文件无法读取时保留修改，不自动覆盖该文件
+        let Some(time) = self.config_changed_at else {
+            return;
+        };
+        if self.config_load_error.is_some() {
+            return;
+        }
+        let elapsed = time.elapsed();
+        if elapsed < CONFIG_SAVE_DELAY {
+            ctx.request_repaint_after(CONFIG_SAVE_DELAY - elapsed);
+            return;
+        }
+        self.save_config();
+    }
+
     /// 按类别分组工具
     fn categorize_tools(&mut self) {
         for tool in &self.tools {
             let category = tool.category();
             self.categories
                 .entry(category)
-                .or_insert_with(Vec::new)
+                .or_default()
                 .push(tool.clone());
         }
     }
 
+    /// 获取所有已加载的工具
+    pub fn tools(&self) -> &[Arc<dyn Tool>] {
+        &self.tools
+    }
+
+    /// 获取内置工具和插件管理器中当前注册的所有插件工具，可以在流水线和批量处理中使用
+    ///
+    /// 与 [`UniToolsApp::tools`] 不同，包含工具列表刷新后才注册的插件工具。
+    pub fn all_tools(&self) -> Vec<Arc<dyn Tool>> {
+        let mut tools = self.tools.clone();
+        for tool in self.plugin_manager.get_tools() {
+            if !tools.iter().any(|t| t.id() == tool.id()) {
+                tools.push(tool);
+            }
+        }
+        tools
+    }
+
+    /// 按标识查找工具
+    pub fn find_tool(&self, tool_id: &str) -> Option<Arc<dyn Tool>> {
+        // 先从工具列表中查找
+        if let Some(tool) = self.tools.iter().find(|tool| tool.id() == tool_id) {
+            return Some(tool.clone());
+        }
+
+        // 再尝试从插件管理器中获取
+        self.plugin_manager.get_tool(tool_id)
+    }
+
+    /// 读取保存的流水线
+    ///
+    /// 旧版本以工具名称保存步骤，找不到对应标识的步骤按名称换成工具标识。
+    pub fn load_pipeline(&self, name: &str) -> Option<PipelineDefinition> {
+        let mut definition = self.config.lock().unwrap().get_pipeline(name)?.clone();
+        for step in &mut definition.steps {
+            if self.find_tool(&step.tool).is_none()
+                && let Some(tool) = self.tools.iter().find(|tool| tool.name() == step.tool)
+            {
+                step.tool = tool.id().to_string();
+            }
+        }
+        Some(definition)
+    }
+
     /// 获取当前工具
     fn get_current_tool(&self) -> Option<Arc<dyn Tool>> {
-        if let Some(tool_name) = &self.current_tool {
-            // 先从工具列表中查找
-            for tool in &self.tools {
-                if tool.name() == tool_name {
-                    return Some(tool.clone());
-                }
-            }
+        self.current_tool
+            .as_deref()
+            .and_then(|tool_id| self.find_tool(tool_id))
+    }
 
-            // 再尝试从插件管理器中获取
-            self.plugin_manager.get_tool(tool_name)
-        } else {
-            None
+    /// 创建带有当前工具配置和事件总线的执行上下文
+    pub fn tool_context(&self) -> ToolContext {
+        ToolContext {
+            tool_configs: Arc::new(self.config.lock().unwrap().tool_configs.clone()),
+            events: self.events().clone(),
+            ..Default::default()
         }
     }
 
+    /// 获取应用的事件总线
+    pub fn events(&self) -> &EventBus {
+        self.plugin_manager.events()
+    }
+
+    /// 获取用于后台执行工具的异步运行时
+    pub fn runtime(&self) -> &tokio::runtime::Handle {
+        self.runtime.handle()
+    }
+
     /// 切换到工具页面
-    pub fn navigate_to_tool(&mut self, tool_name: &str) {
-        self.current_tool = Some(tool_name.to_string());
+    pub fn navigate_to_tool(&mut self, tool_id: &str) {
+        self.current_tool = Some(tool_id.to_string());
         self.current_page = Page::Tool;
     }
 
@@ -98,19 +618,35 @@ impl UniToolsApp {
 
 impl eframe::App for UniToolsApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut Frame) {
+        // 插件目录变化时热重载插件
+        self.reload_plugins_if_changed(ctx);
+
+        // 显示插件健康检查发现的问题
+        self.show_plugin_health_problems();
+
+        // 配置文件被外部修改时重新读取
+        self.reload_config_if_changed(ctx);
+
+        // 配置修改后自动保存
+        self.save_config_if_changed(ctx);
+
         // 根据当前页面渲染不同的UI
         match self.current_page {
             Page::Home => ui::render_home_page(ctx, self),
             Page::Tool => {
                 if let Some(tool) = self.get_current_tool() {
-                    ui::render_tool_page(ctx, tool.as_ref(), self);
+                    ui::render_tool_page(ctx, tool, self);
                 } else {
                     // 无效工具，返回主页
                     self.current_page = Page::Home;
                 }
             }
+            Page::Pipeline => ui::render_pipeline_page(ctx, self),
+            Page::Batch => ui::render_batch_page(ctx, self),
             Page::Settings => ui::render_settings_page(ctx, self),
             Page::About => ui::render_about_page(ctx, self),
         }
+
+        ui::render_notification(ctx, self);
     }
 }
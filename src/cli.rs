@@ -0,0 +1,611 @@
+//! 命令行模式
+//!
+//! 不启动图形界面，直接运行已注册的工具，便于在脚本和持续集成中使用。
+//! 工具执行失败时以不同的退出码区分错误类别，见 [`exit_code`]。
+
+use clap::{Subcommand, ValueEnum};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+use unitools_core::batch::{self, Batch, BatchDestination};
+use unitools_core::config::{self, AppConfig, ConfigFormat};
+use unitools_core::error::{ToolError, closest_match};
+use unitools_core::event::Event;
+use unitools_core::plugin::PluginManager;
+use unitools_core::protocol::ErrorPayload;
+use unitools_core::tool::{
+    CancellationToken, ParameterSpec, Tool, ToolContext, ToolDescriptor, ToolOutput,
+};
+use unitools_core::tr;
+
+/// 命令行子命令
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// 运行工具
+    Run {
+        /// 工具标识，例如 `text.case`
+        tool: String,
+
+        /// 工具参数，格式为 key=value，可重复指定
+        #[arg(short, long = "param", value_name = "KEY=VALUE", value_parser = parse_key_value)]
+        params: Vec<(String, String)>,
+
+        /// 输入文件，`-` 表示从标准输入读取
+        #[arg(short, long, value_name = "FILE")]
+        input: Option<PathBuf>,
+
+        /// 输出文件，未指定时写到标准输出
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+
+    /// 用同一个工具批量处理多个文件
+    Batch {
+        /// 工具标识，例如 `text.case`
+        tool: String,
+
+        /// 目录或通配符模式，例如 `docs/**/*.txt`
+        source: String,
+
+        /// 工具参数，格式为 key=value，可重复指定
+        #[arg(short, long = "param", value_name = "KEY=VALUE", value_parser = parse_key_value)]
+        params: Vec<(String, String)>,
+
+        /// 输出目录，按源文件的相对路径写入
+        #[arg(short, long, value_name = "DIR", required_unless_present = "in_place")]
+        out_dir: Option<PathBuf>,
+
+        /// 覆盖源文件，覆盖前备份为 `<文件名>.bak`
+        #[arg(long, conflicts_with = "out_dir")]
+        in_place: bool,
+
+        /// 并行处理的线程数，默认为 CPU 核心数
+        #[arg(short, long, default_value_t = 0)]
+        jobs: usize,
+    },
+
+    /// 启动本机 HTTP 服务，通过 REST API 调用工具
+    #[cfg(feature = "server")]
+    Serve {
+        /// 监听端口
+        #[arg(short, long, default_value_t = crate::server::DEFAULT_PORT)]
+        port: u16,
+
+        /// 访问令牌，指定后请求需要携带 `Authorization: Bearer <令牌>`
+        #[arg(long, env = "UNITOOLS_TOKEN")]
+        token: Option<String>,
+    },
+
+    /// 通过标准输入输出提供 Model Context Protocol 服务
+    Mcp,
+
+    /// 列出所有可用的工具
+    List {
+        /// 以 JSON 格式输出
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// 显示工具的说明和参数
+    Describe {
+        /// 工具标识，例如 `text.case`
+        tool: String,
+
+        /// 以 JSON 格式输出
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// 管理配置文件
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+/// 配置文件子命令
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// 转换配置文件格式，格式由扩展名决定（.json、.toml、.yaml/.yml）
+    Convert {
+        /// 原配置文件
+        input: PathBuf,
+
+        /// 转换后的配置文件
+        output: PathBuf,
+
+        /// 覆盖已存在的输出文件
+        #[arg(short, long)]
+        force: bool,
+    },
+}
+
+/// 解析 `key=value` 形式的参数
+pub(crate) fn parse_key_value(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .ok_or_else(|| tr!("cli-invalid-key-value", value = s))
+}
+
+/// 工具执行失败时的退出码
+///
+/// | 退出码 | 错误类别 |
+/// | --- | --- |
+/// | 1 | 其他错误 |
+/// | 2 | 命令行用法错误（由 clap 返回） |
+/// | 3 | 参数错误 |
+/// | 4 | IO 错误 |
+/// | 5 | 格式错误 |
+/// | 6 | 解析错误 |
+/// | 7 | 未找到 |
+/// | 8 | 网络错误 |
+/// | 9 | 插件错误 |
+/// | 10 | 流水线错误 |
+/// | 11 | 权限不足 |
+/// | 12 | 未实现 |
+/// | 130 | 操作已取消 |
+pub fn exit_code(error: &ToolError) -> u8 {
+    match error {
+        ToolError::Other(_) => 1,
+        ToolError::ParameterError(_) => 3,
+        ToolError::IoError(_) => 4,
+        ToolError::FormatError(_) => 5,
+        ToolError::ParseError(_) => 6,
+        ToolError::NotFoundError(_) => 7,
+        ToolError::NetworkError(_) => 8,
+        ToolError::PluginError(_) => 9,
+        ToolError::PipelineError { .. } => 10,
+        ToolError::PermissionDenied(_) => 11,
+        ToolError::NotImplementedError(_) => 12,
+        // 与被 Ctrl+C 中断的进程一致
+        ToolError::Cancelled => 130,
+        ToolError::Diagnostic { error, .. } => exit_code(error),
+    }
+}
+
+/// 内置工具和已启用插件提供的工具
+pub struct Toolbox {
+    /// 插件管理器，插件在工具使用期间需要保持加载，插件通过它的事件总线订阅事件
+    plugin_manager: PluginManager,
+    tools: Vec<Arc<dyn Tool>>,
+    /// 工具保存的配置，按工具标识索引
+    tool_configs: Arc<HashMap<String, serde_json::Value>>,
+}
+
+impl Toolbox {
+    /// 加载内置工具和配置中启用的插件，插件加载错误和无效的工具配置输出到标准错误
+    pub fn load(config: &AppConfig) -> Self {
+        let plugin_manager = crate::plugins::create_plugin_manager();
+        let mut loaded = Vec::new();
+        for error in crate::plugins::sync_plugins(&plugin_manager, config, &mut loaded) {
+            eprintln!("{}", tr!("cli-plugin-load-failed", error = error));
+        }
+
+        let mut tools = crate::tools::get_builtin_tools();
+        for tool in &tools {
+            plugin_manager.events().publish(Event::ToolRegistered {
+                tool: tool.id().to_string(),
+                plugin: None,
+            });
+        }
+        tools.extend(plugin_manager.get_tools());
+
+        // 命令行模式不修改配置文件，无效的配置项只在本次运行中使用默认值
+        let mut config = config.clone();
+        for problem in config.repair_tool_configs(&tools) {
+            eprintln!("{}", tr!("cli-tool-config", problem = problem));
+        }
+
+        Self {
+            plugin_manager,
+            tools,
+            tool_configs: Arc::new(config.tool_configs),
+        }
+    }
+
+    /// 创建带有工具配置和事件总线的执行上下文
+    pub fn context(&self) -> ToolContext {
+        ToolContext {
+            tool_configs: self.tool_configs.clone(),
+            events: self.plugin_manager.events().clone(),
+            ..Default::default()
+        }
+    }
+
+    /// 获取所有工具
+    pub fn tools(&self) -> &[Arc<dyn Tool>] {
+        &self.tools
+    }
+
+    /// 按标识查找工具，找不到时在错误中提示相近的工具标识
+    pub fn find(&self, id: &str) -> Result<Arc<dyn Tool>, ToolError> {
+        self.tools
+            .iter()
+            .find(|tool| tool.id() == id)
+            .cloned()
+            .ok_or_else(|| {
+                let error = ToolError::NotFoundError(tr!("cli-tool-not-found", id = id));
+                match closest_match(id, self.tools.iter().map(|tool| tool.id())) {
+                    Some(similar) => error.with_hint(tr!("hint-did-you-mean-tool", id = similar)),
+                    None => error,
+                }
+            })
+    }
+}
+
+/// 错误信息的输出格式
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorFormat {
+    /// 错误信息，以及输入位置、修改建议和原因
+    Text,
+    /// `{"error": {"code": ..., "message": ..., ...}}`，字段见 `ErrorPayload`
+    Json,
+}
+
+/// 执行子命令，返回进程退出码，错误信息按 `error_format` 输出到标准错误
+pub fn run(command: Command, config: &AppConfig, error_format: ErrorFormat) -> ExitCode {
+    let toolbox = Toolbox::load(config);
+
+    let result = match command {
+        Command::Run {
+            tool,
+            params,
+            input,
+            output,
+        } => run_tool(&toolbox, &tool, params.into_iter().collect(), input, output),
+        Command::Batch {
+            tool,
+            source,
+            params,
+            out_dir,
+            in_place: _,
+            jobs,
+        } => {
+            // 未指定输出目录时 clap 保证指定了 --in-place
+            let destination = match out_dir {
+                Some(dir) => BatchDestination::Mirror(dir),
+                None => BatchDestination::InPlace,
+            };
+            run_batch(
+                &toolbox,
+                &tool,
+                &source,
+                params.into_iter().collect(),
+                destination,
+                jobs,
+            )
+        }
+        #[cfg(feature = "server")]
+        Command::Serve { port, token } => crate::server::serve(toolbox, port, token),
+        Command::Mcp => crate::mcp::serve(toolbox),
+        Command::List { json } => list_tools(&toolbox, json),
+        Command::Describe { tool, json } => describe_tool(&toolbox, &tool, json),
+        Command::Config {
+            command:
+                ConfigCommand::Convert {
+                    input,
+                    output,
+                    force,
+                },
+        } => convert_config(&input, &output, force),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        // 输出被提前关闭（例如通过管道传给 head）时不视为错误
+        Err(ToolError::IoError(e)) if e.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(error) => {
+            match error_format {
+                ErrorFormat::Text => eprintln!("{}", error.describe()),
+                ErrorFormat::Json => eprintln!(
+                    "{}",
+                    serde_json::json!({ "error": ErrorPayload::from(&error) })
+                ),
+            }
+            ExitCode::from(exit_code(&error))
+        }
+    }
+}
+
+/// 转换配置文件格式，原文件保持不变
+fn convert_config(input: &Path, output: &Path, force: bool) -> Result<(), ToolError> {
+    if output.exists() && !force {
+        return Err(ToolError::IoError(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            tr!("cli-output-exists", path = output.display()),
+        )));
+    }
+
+    let report = config::convert_config_file(input, output)?;
+    if let Some(version) = report.migrated_from {
+        eprintln!(
+            "{}",
+            tr!(
+                "cli-config-migrated",
+                from = version,
+                to = config::CONFIG_VERSION
+            )
+        );
+    }
+    eprintln!(
+        "{}",
+        tr!(
+            "cli-config-converted",
+            input = input.display(),
+            input_format = ConfigFormat::from_path(input),
+            output = output.display(),
+            output_format = ConfigFormat::from_path(output)
+        )
+    );
+    Ok(())
+}
+
+/// 运行工具并输出结果，按 Ctrl+C 取消
+fn run_tool(
+    toolbox: &Toolbox,
+    name: &str,
+    parameters: HashMap<String, String>,
+    input: Option<PathBuf>,
+    output: Option<PathBuf>,
+) -> Result<(), ToolError> {
+    let tool = toolbox.find(name)?;
+
+    let input_data = match input {
+        Some(path) if path.as_os_str() == "-" => {
+            let mut data = Vec::new();
+            io::stdin().read_to_end(&mut data)?;
+            Some(data)
+        }
+        Some(path) => Some(fs::read(&path).map_err(|e| {
+            ToolError::IoError(io::Error::new(
+                e.kind(),
+                tr!("cli-read-failed", path = path.display(), error = e),
+            ))
+        })?),
+        None => None,
+    };
+
+    let ctx = ToolContext {
+        input_data,
+        parameters,
+        ..toolbox.context()
+    };
+
+    let runtime = tokio::runtime::Runtime::new()?;
+    cancel_on_ctrl_c(&runtime, ctx.cancellation.clone());
+    let result = runtime.block_on(tool.validate_and_execute_async(ctx))?;
+
+    let Some(result) = result else {
+        return Ok(());
+    };
+
+    match output {
+        Some(path) => fs::write(&path, result.to_bytes()).map_err(|e| {
+            ToolError::IoError(io::Error::new(
+                e.kind(),
+                tr!("cli-write-failed", path = path.display(), error = e),
+            ))
+        }),
+        None => write_stdout(&result),
+    }
+}
+
+/// 批量处理文件，逐个输出处理结果
+///
+/// 有文件处理失败时，以第一个失败文件的错误类别作为退出码。
+fn run_batch(
+    toolbox: &Toolbox,
+    name: &str,
+    source: &str,
+    parameters: HashMap<String, String>,
+    destination: BatchDestination,
+    workers: usize,
+) -> Result<(), ToolError> {
+    let tool = toolbox.find(name)?;
+    let (root, files) = batch::collect_files(source, &destination)?;
+    let batch = Batch::new(tool, parameters, root, files, destination).with_workers(workers);
+
+    let ctx = toolbox.context();
+    let runtime = tokio::runtime::Runtime::new()?;
+    cancel_on_ctrl_c(&runtime, ctx.cancellation.clone());
+    let summary = batch.run(&ctx)?;
+
+    let mut stdout = io::stdout().lock();
+    for file in &summary.results {
+        match &file.result {
+            Ok(Some(output)) => writeln!(
+                stdout,
+                "{}\t{}\t{}",
+                tr!("batch-succeeded"),
+                file.path.display(),
+                output.display()
+            )?,
+            Ok(None) => writeln!(
+                stdout,
+                "{}\t{}\t{}",
+                tr!("batch-succeeded"),
+                file.path.display(),
+                tr!("batch-no-output")
+            )?,
+            Err(error) => writeln!(
+                stdout,
+                "{}\t{}\t{}",
+                tr!("batch-failed"),
+                file.path.display(),
+                error
+            )?,
+        }
+    }
+
+    let failed = summary.failures().count();
+    eprintln!(
+        "{}",
+        tr!(
+            "batch-summary",
+            total = summary.results.len(),
+            succeeded = summary.succeeded(),
+            failed = failed
+        )
+    );
+
+    match summary.failures().next() {
+        Some((path, error)) => Err(ToolError::from_code(
+            error.code(),
+            tr!(
+                "cli-batch-failed",
+                count = failed,
+                path = path.display(),
+                error = error.message()
+            ),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// 按 Ctrl+C 时触发取消令牌
+fn cancel_on_ctrl_c(runtime: &tokio::runtime::Runtime, cancellation: CancellationToken) {
+    runtime.spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            cancellation.cancel();
+        }
+    });
+}
+
+/// 将结果写到标准输出，二进制数据不会写到终端
+fn write_stdout(output: &ToolOutput) -> Result<(), ToolError> {
+    let mut stdout = io::stdout().lock();
+    match output {
+        ToolOutput::Binary { .. } | ToolOutput::Image { .. } => {
+            if stdout.is_terminal() {
+                return Err(ToolError::ParameterError(tr!(
+                    "cli-binary-to-terminal",
+                    output = output.to_text()
+                )));
+            }
+            stdout.write_all(&output.to_bytes())?;
+        }
+        _ => writeln!(stdout, "{}", output.to_text())?,
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+/// 列出所有工具
+fn list_tools(toolbox: &Toolbox, json: bool) -> Result<(), ToolError> {
+    if json {
+        let descriptors: Vec<ToolDescriptor> = toolbox
+            .tools()
+            .iter()
+            .map(|tool| ToolDescriptor::from_tool(tool.as_ref()))
+            .collect();
+        return print_json(&descriptors);
+    }
+
+    let mut stdout = io::stdout().lock();
+    for tool in toolbox.tools() {
+        writeln!(
+            stdout,
+            "{}\t{}\t{}\t{}",
+            tool.id(),
+            tool.localized_name(),
+            tool.category(),
+            tool.localized_description()
+        )?;
+    }
+    Ok(())
+}
+
+/// 显示工具的说明和参数
+fn describe_tool(toolbox: &Toolbox, id: &str, json: bool) -> Result<(), ToolError> {
+    let tool = toolbox.find(id)?;
+    let descriptor = ToolDescriptor::from_tool(tool.as_ref());
+    if json {
+        return print_json(&descriptor);
+    }
+
+    let mut stdout = io::stdout().lock();
+    writeln!(stdout, "{}", tool.localized_name())?;
+    writeln!(stdout, "{}", tr!("describe-id", id = tool.id()))?;
+    writeln!(
+        stdout,
+        "{}",
+        tr!("describe-category", category = tool.category())
+    )?;
+    writeln!(
+        stdout,
+        "{}",
+        tr!(
+            "describe-description",
+            description = tool.localized_description()
+        )
+    )?;
+
+    if !descriptor.capabilities.is_empty() {
+        let capabilities: Vec<String> = descriptor
+            .capabilities
+            .iter()
+            .map(|c| c.description())
+            .collect();
+        writeln!(
+            stdout,
+            "{}",
+            tr!(
+                "describe-capabilities",
+                capabilities = capabilities.join(", ")
+            )
+        )?;
+    }
+
+    let parameters = tool.localized_parameters();
+    if parameters.is_empty() {
+        writeln!(stdout, "{}", tr!("describe-no-parameters"))?;
+    } else {
+        writeln!(stdout, "{}", tr!("describe-parameters"))?;
+        for spec in &parameters {
+            writeln!(stdout, "{}", describe_spec(spec))?;
+        }
+    }
+
+    let config = tool.localized_config_schema();
+    if !config.is_empty() {
+        writeln!(stdout, "{}", tr!("describe-config"))?;
+        for spec in &config {
+            writeln!(stdout, "{}", describe_spec(spec))?;
+        }
+    }
+    Ok(())
+}
+
+/// 将参数或配置项的规格格式化为一行说明
+fn describe_spec(spec: &ParameterSpec) -> String {
+    let kind = serde_json::to_value(spec.kind)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default();
+    let mut line = format!("  {} ({}", spec.name, kind);
+    if spec.required {
+        line.push_str(&format!(", {}", tr!("describe-required")));
+    }
+    if let Some(default) = &spec.default {
+        line.push_str(&format!(", {}", tr!("describe-default", value = default)));
+    }
+    line.push_str(&format!(") {}", spec.description));
+    if !spec.allowed_values.is_empty() {
+        line.push_str(&format!(
+            " [{}]",
+            tr!("describe-choices", values = spec.allowed_values.join(", "))
+        ));
+    }
+    line
+}
+
+/// 以格式化的 JSON 输出
+fn print_json<T: serde::Serialize>(value: &T) -> Result<(), ToolError> {
+    let json =
+        serde_json::to_string_pretty(value).map_err(|e| ToolError::FormatError(e.to_string()))?;
+    writeln!(io::stdout().lock(), "{}", json)?;
+    Ok(())
+}
@@ -1,53 +1,235 @@
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use std::fs;
 use std::path::{Path, PathBuf};
-use unitools_core::config::AppConfig;
+use unitools_core::config::{
+    AppConfig, CONFIG_VERSION, ConfigLayer, ConfigSource, LayeredConfig, save_config_file,
+};
+use unitools_core::error::ToolError;
 
-/// 加载或创建新的配置
-pub fn load_or_create_config<P: AsRef<Path>>(path: P) -> AppConfig {
+/// 系统配置文件的路径，可以通过环境变量 `UNITOOLS_SYSTEM_CONFIG` 指定
+pub fn system_config_path() -> PathBuf {
+    if let Some(path) = std::env::var_os("UNITOOLS_SYSTEM_CONFIG") {
+        return PathBuf::from(path);
+    }
+
+    #[cfg(windows)]
+    let mut path = std::env::var_os("PROGRAMDATA")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(r"C:\ProgramData"));
+    #[cfg(target_os = "macos")]
+    let mut path = PathBuf::from("/Library/Application Support");
+    #[cfg(not(any(windows, target_os = "macos")))]
+    let mut path = PathBuf::from("/etc");
+
+    path.push("unitools");
+    path.push("config.json");
+    path
+}
+
+/// 加载配置的结果
+pub struct LoadedConfig {
+    /// 生效的各配置层
+    pub layers: LayeredConfig,
+    /// 用户配置文件无法读取时的错误，此时不应自动保存配置，以免覆盖该文件
+    pub user_error: Option<String>,
+    /// 加载过程中的提示，例如配置文件的迁移、被忽略的配置层和无效的配置项
+    pub messages: Vec<String>,
+}
+
+/// 依次叠加系统配置、用户配置、环境变量和命令行参数，得到生效的配置
+///
+/// 用户配置文件不存在时创建一个只有版本号的配置文件，无法读取的配置层会被跳过。
+/// 加载过程中的问题写入日志，同时在返回值中交给界面显示。
+pub fn load_layered_config<P: AsRef<Path>>(
+    path: P,
+    overrides: &[(String, String)],
+) -> LoadedConfig {
     let config_path = path.as_ref();
+    let mut layers = Vec::new();
+    let mut messages = Vec::new();
 
-    // 如果配置文件不存在，确保目录存在
-    if !config_path.exists() {
-        if let Some(parent) = config_path.parent() {
-            if !parent.exists() {
-                let _ = fs::create_dir_all(parent);
+    let system_path = system_config_path();
+    if system_path.exists() {
+        match load_layer(
+            ConfigSource::System(system_path.clone()),
+            &system_path,
+            &mut messages,
+        ) {
+            Ok(layer) => layers.push(layer),
+            Err(e) => {
+                let message = format!("加载配置文件 {} 失败: {}，已忽略", system_path.display(), e);
+                log::warn!("{}", message);
+                messages.push(message);
             }
         }
+    }
+
+    // 如果配置文件不存在，确保目录存在并创建空的配置
+    if !config_path.exists() {
+        if let Some(parent) = config_path.parent()
+            && !parent.exists()
+        {
+            let _ = fs::create_dir_all(parent);
+        }
 
-        // 创建默认配置并保存
-        let default_config = AppConfig::default();
-        let _ = save_config(&default_config, config_path);
-        return default_config;
+        let fields = serde_json::Map::from_iter([(
+            "config_version".to_string(),
+            serde_json::Value::from(CONFIG_VERSION),
+        )]);
+        if let Err(e) = save_config_file(config_path, &fields) {
+            let message = format!("无法创建配置文件 {}: {}", config_path.display(), e);
+            log::warn!("{}", message);
+            messages.push(message);
+        }
+    }
+    let mut user_error = None;
+    if config_path.exists() {
+        match load_layer(
+            ConfigSource::User(config_path.to_path_buf()),
+            config_path,
+            &mut messages,
+        ) {
+            Ok(layer) => layers.push(layer),
+            Err(e) => {
+                log::error!("加载配置文件 {} 失败: {}，已忽略", config_path.display(), e);
+                user_error = Some(e.to_string());
+            }
+        }
     }
 
-    // 尝试加载配置，如果失败则返回默认值
-    match AppConfig::load_from_file(config_path) {
-        Ok(config) => config,
+    layers.extend(ConfigLayer::from_env(std::env::vars()));
+    match ConfigLayer::from_args(overrides) {
+        Ok(layer) => layers.push(layer),
         Err(e) => {
-            eprintln!("加载配置文件失败: {:?}，将使用默认配置", e);
-            AppConfig::default()
+            let message = format!("忽略命令行中的配置: {}", e);
+            log::warn!("{}", message);
+            messages.push(message);
         }
     }
+
+    let config = LayeredConfig::resolve(layers);
+    for warning in config.warnings() {
+        log::warn!("{}", warning);
+        messages.push(warning.clone());
+    }
+    LoadedConfig {
+        layers: config,
+        user_error,
+        messages,
+    }
 }
 
-/// 保存配置到文件
-pub fn save_config<P: AsRef<Path>>(config: &AppConfig, path: P) -> Result<(), String> {
-    config
-        .save_to_file(&path)
-        .map_err(|e| format!("保存配置失败: {:?}", e))
+/// 读取一个配置文件层，旧版本的配置会先迁移，迁移情况和警告记录在 `messages` 中
+fn load_layer(
+    source: ConfigSource,
+    path: &Path,
+    messages: &mut Vec<String>,
+) -> Result<ConfigLayer, ToolError> {
+    let (layer, report) = ConfigLayer::from_file(source, path)?;
+    if let (Some(version), Some(backup)) = (report.migrated_from, &report.backup) {
+        let message = format!(
+            "配置文件 {} 已从版本 {} 升级到版本 {}，原文件备份为 {}",
+            path.display(),
+            version,
+            CONFIG_VERSION,
+            backup.display()
+        );
+        log::info!("{}", message);
+        messages.push(message);
+    }
+    for warning in &report.warnings {
+        let message = format!("配置文件 {}: {}", path.display(), warning);
+        log::warn!("{}", message);
+        messages.push(message);
+    }
+    Ok(layer)
 }
 
-/// 获取工具配置目录
-pub fn get_tools_config_dir() -> PathBuf {
-    let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
-    path.push("unitools");
-    path.push("tools");
+/// 将无法读取的用户配置文件备份为 `<文件名>.invalid.bak`，已存在时依次尝试
+/// `.invalid.bak.1`、`.invalid.bak.2` 等，返回备份的路径
+///
+/// 在覆盖无法读取的配置文件之前调用，保留用户手动修复的机会。
+pub fn backup_unreadable_config(path: &Path) -> std::io::Result<PathBuf> {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
 
-    if !path.exists() {
-        let _ = fs::create_dir_all(&path);
+    let mut backup = path.with_file_name(format!("{}.invalid.bak", name));
+    let mut index = 1;
+    while backup.exists() {
+        backup = path.with_file_name(format!("{}.invalid.bak.{}", name, index));
+        index += 1;
+    }
+    fs::copy(path, &backup)?;
+    Ok(backup)
+}
+
+/// 保存配置到用户配置文件
+///
+/// 只写入与系统配置和默认值不同的设置，来自环境变量和命令行参数的值不会写入。
+/// 文件格式由扩展名决定并尽量保留原有注释，文件以原子方式替换，原文件轮换备份。
+pub fn save_config<P: AsRef<Path>>(
+    layers: &LayeredConfig,
+    config: &AppConfig,
+    path: P,
+) -> Result<(), String> {
+    save_config_file(path.as_ref(), &layers.user_values(config)).map_err(|e| e.to_string())
+}
+
+/// 默认的用户配置文件路径
+///
+/// 配置目录中已有 `config.toml`、`config.yaml` 或 `config.yml` 时使用它，否则使用
+/// `config.json`。
+pub fn default_config_path() -> PathBuf {
+    let mut dir = dirs::config_dir().unwrap_or_default();
+    dir.push("unitools");
+    ["config.toml", "config.yaml", "config.yml"]
+        .into_iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.exists())
+        .unwrap_or_else(|| dir.join("config.json"))
+}
+
+/// 监视配置文件，文件被修改、替换或删除时调用 `on_change`
+///
+/// 监视的是配置文件所在的目录，以便发现编辑器和配置管理工具以重命名方式替换文件；
+/// 配置文件是符号链接时同时监视链接指向的文件。
+pub fn watch_config_file<P: AsRef<Path>>(
+    path: P,
+    on_change: impl Fn() + Send + 'static,
+) -> notify::Result<RecommendedWatcher> {
+    let path = path.as_ref();
+    let mut targets = vec![path.to_path_buf()];
+    if let Ok(resolved) = fs::canonicalize(path)
+        && resolved != path
+    {
+        targets.push(resolved);
     }
+    let names: Vec<_> = targets
+        .iter()
+        .filter_map(|target| target.file_name().map(|name| name.to_os_string()))
+        .collect();
 
-    path
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if let Ok(event) = event
+            && !event.kind.is_access()
+            && event.paths.iter().any(|path| {
+                path.file_name()
+                    .is_some_and(|name| names.iter().any(|target| target == name))
+            })
+        {
+            on_change();
+        }
+    })?;
+    for target in &targets {
+        let dir = match target.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        watcher.watch(dir, RecursiveMode::NonRecursive)?;
+    }
+    Ok(watcher)
 }
 
 /// 获取默认的插件目录
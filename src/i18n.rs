@@ -0,0 +1,22 @@
+//! 应用的本地化资源
+//!
+//! 界面文本和内置工具的翻译放在 `locales/<语言>/` 目录下，编译时嵌入程序。
+
+use unitools_core::i18n;
+
+/// 应用的 Fluent 资源
+const RESOURCES: &[(&str, &str)] = &[
+    ("zh-CN", include_str!("../locales/zh-CN/app.ftl")),
+    ("en-US", include_str!("../locales/en-US/app.ftl")),
+    ("en-US", include_str!("../locales/en-US/tools.ftl")),
+];
+
+/// 加载应用的本地化资源并切换到配置的语言，不支持的语言使用默认语言
+pub fn init(language: &str) {
+    for (resource_language, source) in RESOURCES {
+        i18n::add_resource(resource_language, source).expect("内置的本地化资源无效");
+    }
+    if let Err(e) = i18n::set_language(language) {
+        log::warn!("{}，将使用 {}", e, i18n::DEFAULT_LANGUAGE);
+    }
+}
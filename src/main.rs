@@ -1,26 +1,45 @@
 mod app;
+mod cli;
 mod config;
+mod i18n;
+mod mcp;
+mod plugins;
+#[cfg(feature = "server")]
+mod server;
 mod tools;
 mod ui;
 
 use clap::Parser;
 use eframe::egui;
 use std::path::Path;
+use std::process::ExitCode;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// 配置文件路径
+    /// 配置文件路径，格式由扩展名决定（.json、.toml、.yaml/.yml）
     #[arg(short, long, value_name = "FILE")]
     config: Option<String>,
 
-    /// 启动特定工具
+    /// 启动后打开指定工具
     #[arg(short, long)]
     tool: Option<String>,
 
     /// 调试模式
     #[arg(short, long)]
     debug: bool,
+
+    /// 覆盖配置项，例如 `--set theme=dark`，可以重复指定，只在本次运行中生效
+    #[arg(short, long = "set", value_name = "KEY=VALUE", value_parser = cli::parse_key_value)]
+    set: Vec<(String, String)>,
+
+    /// 命令行模式下错误信息的输出格式，`json` 与 HTTP 接口的错误响应一致
+    #[arg(long, value_enum, default_value_t = cli::ErrorFormat::Text)]
+    error_format: cli::ErrorFormat,
+
+    /// 不启动图形界面，直接执行命令
+    #[command(subcommand)]
+    command: Option<cli::Command>,
 }
 
 /// 加载应用图标
@@ -31,19 +50,19 @@ fn load_icon() -> Option<egui::IconData> {
             let (icon_rgba, icon_width, icon_height) =
                 match ico::IconDir::read(std::io::Cursor::new(icon_data)) {
                     Ok(icon_dir) => {
-                        if let Some(entry) = icon_dir.entries().get(0) {
+                        if let Some(entry) = icon_dir.entries().first() {
                             let icon = entry.decode().unwrap();
-                            let width = icon.width() as u32;
-                            let height = icon.height() as u32;
+                            let width = icon.width();
+                            let height = icon.height();
                             let rgba = icon.rgba_data().to_vec();
                             (rgba, width, height)
                         } else {
-                            eprintln!("图标文件不包含任何图标");
+                            log::warn!("图标文件不包含任何图标");
                             return None;
                         }
                     }
                     Err(e) => {
-                        eprintln!("无法解析图标文件: {}", e);
+                        log::warn!("无法解析图标文件: {}", e);
                         return None;
                     }
                 };
@@ -55,33 +74,42 @@ fn load_icon() -> Option<egui::IconData> {
             })
         }
         Err(e) => {
-            eprintln!("无法加载图标文件: {}", e);
+            log::warn!("无法加载图标文件: {}", e);
             None
         }
     }
 }
 
-fn main() -> Result<(), eframe::Error> {
+fn main() -> ExitCode {
     // 解析命令行参数
     let args = Args::parse();
 
     // 设置日志
-    if args.debug {
-        env_logger::init();
-    }
+    // 默认只输出本程序的提示和其他库的警告，调试模式输出更详细的日志，可以用 RUST_LOG 覆盖
+    let filter = if args.debug {
+        "info,unitools_app=debug"
+    } else {
+        "warn,unitools_app=info"
+    };
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(filter)).init();
 
     // 加载配置
-    let config_path = args.config.unwrap_or_else(|| {
-        let mut path = dirs::config_dir().unwrap_or_default();
-        path.push("unitools");
-        path.push("config.json");
-        path.to_string_lossy().to_string()
-    });
+    let config_path = args
+        .config
+        .unwrap_or_else(|| config::default_config_path().to_string_lossy().to_string());
 
-    let app_config = config::load_or_create_config(&config_path);
+    let loaded_config = config::load_layered_config(&config_path, &args.set);
+    i18n::init(&loaded_config.layers.config().language);
+
+    // 指定子命令时以命令行模式运行，命令行模式不保存配置，加载配置时的问题已写入日志
+    if let Some(command) = args.command {
+        return cli::run(command, loaded_config.layers.config(), args.error_format);
+    }
 
     // 创建视口构建器
-    let mut viewport_builder = egui::ViewportBuilder::default().with_inner_size([1024.0, 768.0]);
+    let mut viewport_builder = egui::ViewportBuilder::default()
+        .with_inner_size([1024.0, 768.0])
+        .with_title(unitools_core::tr!("app-title"));
 
     // 如果图标加载成功，则添加图标
     if let Some(icon) = load_icon() {
@@ -94,9 +122,24 @@ fn main() -> Result<(), eframe::Error> {
     };
 
     // 创建并运行应用
-    eframe::run_native(
+    let tool = args.tool;
+    let result = eframe::run_native(
         "UniTools 工具箱",
         options,
-        Box::new(|cc| Ok(Box::new(app::UniToolsApp::new(cc, app_config)))),
-    )
+        Box::new(move |cc| {
+            let mut app = app::UniToolsApp::new(cc, loaded_config, config_path.into());
+            if let Some(tool) = tool {
+                app.navigate_to_tool(&tool);
+            }
+            Ok(Box::new(app))
+        }),
+    );
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            log::error!("无法启动图形界面: {}", e);
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,319 @@
+//! Model Context Protocol 服务
+//!
+//! 通过标准输入输出以 MCP 协议提供所有工具，每条 JSON-RPC 消息占一行。
+//! 工具的输入内容通过参数 `input` 传递，其余参数按工具的参数规格生成 JSON Schema。
+//! 工具执行失败时返回 `isError` 为真的结果，而不是协议错误。
+
+use base64::Engine;
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use unitools_core::error::ToolError;
+use unitools_core::protocol::ErrorPayload;
+use unitools_core::tool::{
+    CancellationToken, ParameterSpec, ParameterType, Tool, ToolContext, ToolOutput,
+};
+
+use crate::cli::Toolbox;
+
+/// 支持的协议版本，客户端请求其他版本时仍按此版本响应
+const PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// 传递工具输入内容的参数名
+const INPUT_ARGUMENT: &str = "input";
+
+/// JSON-RPC 错误码：方法不存在
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// JSON-RPC 错误码：参数无效
+const INVALID_PARAMS: i64 = -32602;
+
+/// JSON-RPC 错误码：解析错误
+const PARSE_ERROR: i64 = -32700;
+
+/// 服务状态，工具调用在独立线程中执行
+struct McpServer {
+    toolbox: Toolbox,
+    stdout: Mutex<io::Stdout>,
+    /// 正在执行的调用，按请求 id 索引，用于处理取消通知
+    running: Mutex<HashMap<String, CancellationToken>>,
+}
+
+/// 在标准输入输出上运行服务，直到输入结束
+pub fn serve(toolbox: Toolbox) -> Result<(), ToolError> {
+    let server = Arc::new(McpServer {
+        toolbox,
+        stdout: Mutex::new(io::stdout()),
+        running: Mutex::new(HashMap::new()),
+    });
+
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let message: Value = match serde_json::from_str(&line) {
+            Ok(message) => message,
+            Err(e) => {
+                server.send_error(Value::Null, PARSE_ERROR, &e.to_string())?;
+                continue;
+            }
+        };
+        server.clone().handle(message)?;
+    }
+
+    // 输入结束表示客户端已断开，取消正在执行的调用
+    for cancellation in server.running.lock().unwrap().values() {
+        cancellation.cancel();
+    }
+    Ok(())
+}
+
+impl McpServer {
+    /// 处理一条消息
+    fn handle(self: Arc<Self>, message: Value) -> Result<(), ToolError> {
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+        let Some(id) = message.get("id").cloned() else {
+            // 通知不需要响应
+            if method == "notifications/cancelled"
+                && let Some(cancellation) = self
+                    .running
+                    .lock()
+                    .unwrap()
+                    .get(&params["requestId"].to_string())
+            {
+                cancellation.cancel();
+            }
+            return Ok(());
+        };
+
+        match method {
+            "initialize" => self.send_result(
+                id,
+                json!({
+                    "protocolVersion": PROTOCOL_VERSION,
+                    "capabilities": { "tools": { "listChanged": false } },
+                    "serverInfo": {
+                        "name": "unitools",
+                        "version": env!("CARGO_PKG_VERSION"),
+                    },
+                }),
+            ),
+            "ping" => self.send_result(id, json!({})),
+            "tools/list" => {
+                let tools: Vec<Value> = self
+                    .toolbox
+                    .tools()
+                    .iter()
+                    .map(|tool| tool_definition(tool.as_ref()))
+                    .collect();
+                self.send_result(id, json!({ "tools": tools }))
+            }
+            "tools/call" => {
+                let name = params["name"].as_str().unwrap_or_default();
+                let tool = match self.toolbox.find(name) {
+                    Ok(tool) => tool,
+                    Err(error) => return self.send_error(id, INVALID_PARAMS, &error.to_string()),
+                };
+                let ctx = match tool_context(&params["arguments"], self.toolbox.context()) {
+                    Ok(ctx) => ctx,
+                    Err(error) => {
+                        return self.send_result(id, error_result(&error));
+                    }
+                };
+
+                let key = id.to_string();
+                self.running
+                    .lock()
+                    .unwrap()
+                    .insert(key.clone(), ctx.cancellation.clone());
+                thread::spawn(move || {
+                    let result = match tool.validate_and_execute(ctx) {
+                        Ok(output) => call_result(output),
+                        Err(error) => error_result(&error),
+                    };
+                    self.running.lock().unwrap().remove(&key);
+                    if let Err(e) = self.send_result(id, result) {
+                        log::error!("无法发送响应: {}", e);
+                    }
+                });
+                Ok(())
+            }
+            _ => self.send_error(id, METHOD_NOT_FOUND, &format!("不支持的方法: {}", method)),
+        }
+    }
+
+    fn send_result(&self, id: Value, result: Value) -> Result<(), ToolError> {
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+    }
+
+    fn send_error(&self, id: Value, code: i64, message: &str) -> Result<(), ToolError> {
+        self.send(json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": code, "message": message },
+        }))
+    }
+
+    /// 写出一条消息
+    fn send(&self, message: Value) -> Result<(), ToolError> {
+        let mut stdout = self.stdout.lock().unwrap();
+        writeln!(stdout, "{}", message)?;
+        stdout.flush()?;
+        Ok(())
+    }
+}
+
+/// 生成工具的 MCP 定义
+fn tool_definition(tool: &dyn Tool) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+
+    properties.insert(
+        INPUT_ARGUMENT.to_string(),
+        json!({ "type": "string", "description": "输入内容" }),
+    );
+    for spec in tool.localized_parameters() {
+        if spec.required {
+            required.push(spec.name.clone());
+        }
+        properties.insert(spec.name.clone(), parameter_schema(&spec));
+    }
+
+    json!({
+        "name": tool.id(),
+        "title": tool.localized_name(),
+        "description": tool.localized_description(),
+        "inputSchema": {
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        },
+    })
+}
+
+/// 根据参数规格生成 JSON Schema
+fn parameter_schema(spec: &ParameterSpec) -> Value {
+    let kind = match spec.kind {
+        ParameterType::Int => "integer",
+        ParameterType::Float => "number",
+        ParameterType::Bool => "boolean",
+        ParameterType::String
+        | ParameterType::Enum
+        | ParameterType::Path
+        | ParameterType::Multiline => "string",
+    };
+
+    let mut schema = Map::new();
+    schema.insert("type".to_string(), json!(kind));
+    schema.insert("description".to_string(), json!(spec.description));
+    if !spec.allowed_values.is_empty() {
+        schema.insert("enum".to_string(), json!(spec.allowed_values));
+    }
+    if let Some(min) = spec.min {
+        schema.insert("minimum".to_string(), json!(min));
+    }
+    if let Some(max) = spec.max {
+        schema.insert("maximum".to_string(), json!(max));
+    }
+    if let Some(pattern) = &spec.pattern {
+        schema.insert("pattern".to_string(), json!(pattern));
+    }
+    if let Some(default) = &spec.default {
+        // 默认值按参数类型转换，无法转换时保留原文本
+        let value = match kind {
+            "string" => None,
+            _ => serde_json::from_str::<Value>(default).ok(),
+        };
+        schema.insert(
+            "default".to_string(),
+            value.unwrap_or_else(|| json!(default)),
+        );
+    }
+    Value::Object(schema)
+}
+
+/// 由调用参数填充工具上下文，参数值统一转换为字符串
+fn tool_context(arguments: &Value, mut ctx: ToolContext) -> Result<ToolContext, ToolError> {
+    let Some(arguments) = arguments.as_object() else {
+        return Ok(ctx);
+    };
+
+    for (name, value) in arguments {
+        let text = match value {
+            Value::Null => continue,
+            Value::String(text) => text.clone(),
+            Value::Bool(_) | Value::Number(_) => value.to_string(),
+            _ => {
+                return Err(ToolError::ParameterError(format!(
+                    "参数 '{}' 必须是字符串、数字或布尔值",
+                    name
+                )));
+            }
+        };
+
+        if name == INPUT_ARGUMENT {
+            ctx.input_data = Some(text.into_bytes());
+        } else {
+            ctx.parameters.insert(name.clone(), text);
+        }
+    }
+    Ok(ctx)
+}
+
+/// 工具执行成功时的结果
+fn call_result(output: Option<ToolOutput>) -> Value {
+    let mut content = Vec::new();
+    if let Some(output) = &output {
+        output_content(output, &mut content);
+    }
+
+    let mut result = json!({ "content": content, "isError": false });
+    if let Some(ToolOutput::Json(value)) = &output
+        && value.is_object()
+    {
+        result["structuredContent"] = value.clone();
+    }
+    result
+}
+
+/// 工具执行失败时的结果
+fn error_result(error: &ToolError) -> Value {
+    json!({
+        "content": [{ "type": "text", "text": error.describe() }],
+        "structuredContent": { "error": ErrorPayload::from(error) },
+        "isError": true,
+    })
+}
+
+/// 将工具输出转换为 MCP 内容
+fn output_content(output: &ToolOutput, content: &mut Vec<Value>) {
+    let base64 = base64::engine::general_purpose::STANDARD;
+    match output {
+        ToolOutput::Text(_) | ToolOutput::Json(_) | ToolOutput::Table { .. } => {
+            content.push(json!({ "type": "text", "text": output.to_text() }));
+        }
+        ToolOutput::Image { mime_type, data } => content.push(json!({
+            "type": "image",
+            "data": base64.encode(data),
+            "mimeType": mime_type,
+        })),
+        ToolOutput::Binary { mime_type, data } => content.push(json!({
+            "type": "resource",
+            "resource": {
+                "uri": format!("unitools://output.{}", output.file_extension()),
+                "mimeType": mime_type,
+                "blob": base64.encode(data),
+            },
+        })),
+        ToolOutput::Multiple(outputs) => {
+            for output in outputs {
+                output_content(output, content);
+            }
+        }
+    }
+}
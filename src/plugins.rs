@@ -0,0 +1,319 @@
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use unitools_core::capability::Capability;
+use unitools_core::config::AppConfig;
+use unitools_core::error::ToolError;
+use unitools_core::native::NativePlugin;
+use unitools_core::plugin::{
+    MANIFEST_FILE_NAME, Plugin, PluginManager, PluginManifest, UNSANDBOXED_CAPABILITY,
+};
+use unitools_core::process::ProcessPlugin;
+use unitools_core::script::ScriptPlugin;
+use unitools_core::wasm::{WasmLimits, WasmPlugin};
+
+use crate::config::get_default_plugin_dir;
+
+/// 创建插件管理器，按应用版本检查插件兼容性，并禁止插件工具使用内置工具的标识
+pub fn create_plugin_manager() -> PluginManager {
+    let app_version = semver::Version::parse(env!("CARGO_PKG_VERSION")).expect("应用版本号无效");
+    let manager = PluginManager::with_app_version(app_version);
+    manager.reserve_tool_ids(
+        crate::tools::get_builtin_tools()
+            .iter()
+            .map(|tool| tool.id().to_string()),
+    );
+    manager
+}
+
+/// 获取配置中的插件目录，未设置时使用默认目录
+pub fn plugin_directory(config: &AppConfig) -> PathBuf {
+    config
+        .plugin_directory
+        .clone()
+        .unwrap_or_else(get_default_plugin_dir)
+}
+
+/// 插件文件的标识，即去掉扩展名和 `lib` 前缀后的文件名
+pub fn plugin_id<P: AsRef<Path>>(path: P) -> Option<String> {
+    let stem = path.as_ref().file_stem()?.to_string_lossy().to_string();
+    let id = if cfg!(windows) {
+        stem.as_str()
+    } else {
+        stem.strip_prefix("lib").unwrap_or(&stem)
+    };
+    Some(id.to_string())
+}
+
+/// WASM 插件文件扩展名
+const WASM_EXTENSION: &str = "wasm";
+
+/// 脚本工具文件扩展名
+const SCRIPT_EXTENSION: &str = "rhai";
+
+/// 插件目录中发现的插件
+#[derive(Debug, Clone)]
+pub struct DiscoveredPlugin {
+    /// 插件标识，有清单时取清单中的 id，否则由文件名得出
+    pub id: String,
+    /// 插件入口文件
+    pub path: PathBuf,
+    /// 插件清单，单文件插件没有清单
+    pub manifest: Option<PluginManifest>,
+    /// 插件文件及其修改时间
+    fingerprint: Fingerprint,
+}
+
+/// 插件文件及其修改时间，用于判断插件是否发生变化
+type Fingerprint = Vec<(PathBuf, Option<SystemTime>)>;
+
+/// 计算一组文件的指纹
+fn fingerprint(paths: &[&Path]) -> Fingerprint {
+    paths
+        .iter()
+        .map(|path| {
+            let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+            (path.to_path_buf(), modified)
+        })
+        .collect()
+}
+
+/// 已加载的插件
+#[derive(Debug, Clone)]
+pub struct LoadedPlugin {
+    /// 插件标识，也是在插件管理器中注册的名称
+    pub id: String,
+    /// 加载时的文件指纹
+    fingerprint: Fingerprint,
+    /// 依赖的插件标识
+    dependencies: Vec<String>,
+    /// 加载时用户批准的权限
+    approved: Option<Vec<Capability>>,
+}
+
+/// 判断文件是否为可以单独加载的插件文件
+///
+/// 外部进程插件必须放在带有清单的子目录中，避免把插件目录中无关的可执行文件当作插件运行。
+fn is_plugin_file(path: &Path) -> bool {
+    path.is_file()
+        && path.extension().is_some_and(|ext| {
+            ext == std::env::consts::DLL_EXTENSION
+                || ext == WASM_EXTENSION
+                || ext == SCRIPT_EXTENSION
+        })
+}
+
+/// 查找插件目录中的所有插件
+///
+/// 目录中的原生、WASM 和脚本插件文件直接作为插件；包含 `plugin.toml` 的子目录按清单加载，
+/// 外部进程插件只能以这种方式提供。清单无效的子目录以错误信息返回。
+pub fn discover_plugins<P: AsRef<Path>>(dir: P) -> Vec<Result<DiscoveredPlugin, String>> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .collect();
+    paths.sort();
+
+    let mut plugins = Vec::new();
+    for path in paths {
+        let manifest_path = path.join(MANIFEST_FILE_NAME);
+        if path.is_dir() && manifest_path.is_file() {
+            plugins.push(
+                PluginManifest::load(&manifest_path)
+                    .map(|manifest| {
+                        let entry = path.join(&manifest.entry);
+                        DiscoveredPlugin {
+                            id: manifest.id.clone(),
+                            fingerprint: fingerprint(&[&manifest_path, &entry]),
+                            path: entry,
+                            manifest: Some(manifest),
+                        }
+                    })
+                    .map_err(|e| e.to_string()),
+            );
+        } else if is_plugin_file(&path)
+            && let Some(id) = plugin_id(&path)
+        {
+            plugins.push(Ok(DiscoveredPlugin {
+                id,
+                fingerprint: fingerprint(&[&path]),
+                path,
+                manifest: None,
+            }));
+        }
+    }
+
+    plugins
+}
+
+/// 使已加载的插件与插件目录保持一致，返回加载失败的错误信息
+///
+/// 文件发生变化、被删除、不再启用或权限批准发生变化的插件会先清理并卸载
+/// （依赖它们的插件一并卸载），随后加载所有已启用但尚未加载的插件。
+/// 首次加载时 `loaded` 为空。
+pub fn sync_plugins(
+    manager: &PluginManager,
+    config: &AppConfig,
+    loaded: &mut Vec<LoadedPlugin>,
+) -> Vec<String> {
+    let mut errors = Vec::new();
+    let mut enabled = Vec::new();
+    manager.set_approved_capabilities(config.plugin_permissions.clone());
+
+    for plugin in discover_plugins(plugin_directory(config)) {
+        match plugin {
+            // 只加载用户明确启用的插件
+            Ok(plugin) if config.enabled_plugins.contains(&plugin.id) => enabled.push(plugin),
+            Ok(_) => {}
+            Err(e) => errors.push(e),
+        }
+    }
+
+    // 找出需要卸载的插件
+    let mut stale: Vec<String> = loaded
+        .iter()
+        .filter(|loaded| {
+            !enabled
+                .iter()
+                .any(|p| p.id == loaded.id && p.fingerprint == loaded.fingerprint)
+                || config.plugin_permissions.get(&loaded.id) != loaded.approved.as_ref()
+        })
+        .map(|loaded| loaded.id.clone())
+        .collect();
+    loop {
+        let dependents: Vec<String> = loaded
+            .iter()
+            .filter(|l| !stale.contains(&l.id) && l.dependencies.iter().any(|d| stale.contains(d)))
+            .map(|l| l.id.clone())
+            .collect();
+        if dependents.is_empty() {
+            break;
+        }
+        stale.extend(dependents);
+    }
+
+    // 按加载顺序的逆序卸载，依赖方先于被依赖方
+    for plugin in loaded.iter().rev().filter(|l| stale.contains(&l.id)) {
+        if let Err(e) = manager.unload_plugin(&plugin.id) {
+            errors.push(format!("{}: {}", plugin.id, e));
+        }
+    }
+    loaded.retain(|l| !stale.contains(&l.id));
+
+    // 加载尚未加载的插件
+    let mut manifests = Vec::new();
+    let mut entries = HashMap::new();
+
+    for plugin in enabled {
+        if loaded.iter().any(|l| l.id == plugin.id) {
+            continue;
+        }
+
+        match &plugin.manifest {
+            Some(manifest) => {
+                manifests.push(manifest.clone());
+                // 标识重复的插件会在解析加载顺序时被拒绝
+                entries.entry(plugin.id.clone()).or_insert(plugin);
+            }
+            None => {
+                let result = load_approved_plugin(manager, &plugin.id, &plugin.path, None)
+                    .and_then(|p| manager.register_plugin_as(&plugin.id, p));
+                match result {
+                    Ok(()) => loaded.push(LoadedPlugin {
+                        approved: config.plugin_permissions.get(&plugin.id).cloned(),
+                        id: plugin.id,
+                        fingerprint: plugin.fingerprint,
+                        dependencies: Vec::new(),
+                    }),
+                    Err(e) => errors.push(format!("{}: {}", plugin.id, e)),
+                }
+            }
+        }
+    }
+
+    // 按依赖顺序加载带清单的插件
+    let plan = manager.resolve_load_order(&manifests);
+    for (id, error) in plan.rejected {
+        errors.push(format!("{}: {}", id, error));
+    }
+    for manifest in plan.order {
+        let plugin = &entries[&manifest.id];
+        let result = load_approved_plugin(manager, &manifest.id, &plugin.path, Some(manifest))
+            .and_then(|p| manager.register_plugin_with_manifest(p, manifest.clone()));
+        match result {
+            Ok(()) => loaded.push(LoadedPlugin {
+                id: manifest.id.clone(),
+                fingerprint: plugin.fingerprint.clone(),
+                dependencies: manifest.dependencies.keys().cloned().collect(),
+                approved: config.plugin_permissions.get(&manifest.id).cloned(),
+            }),
+            Err(e) => errors.push(format!("{}: {}", manifest.id, e)),
+        }
+    }
+
+    errors
+}
+
+/// 监视插件目录，目录内容变化时调用 `on_change`
+pub fn watch_plugin_directory<P: AsRef<Path>>(
+    dir: P,
+    on_change: impl Fn() + Send + 'static,
+) -> notify::Result<RecommendedWatcher> {
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if event.is_ok_and(|event| !event.kind.is_access()) {
+            on_change();
+        }
+    })?;
+    watcher.watch(dir.as_ref(), RecursiveMode::Recursive)?;
+    Ok(watcher)
+}
+
+/// 插件文件是否运行在沙箱中，即 WASM 插件和脚本工具
+fn is_sandboxed_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext == WASM_EXTENSION || ext == SCRIPT_EXTENSION)
+}
+
+/// 检查权限后加载插件文件
+///
+/// 原生插件和外部进程插件在加载时就会执行插件代码，必须在加载前检查权限：有清单时按清单
+/// 声明的权限检查，否则只检查隐含的能力。沙箱插件加载后注册时还会按其工具声明的能力检查。
+fn load_approved_plugin(
+    manager: &PluginManager,
+    id: &str,
+    path: &Path,
+    manifest: Option<&PluginManifest>,
+) -> Result<Box<dyn Plugin>, ToolError> {
+    let sandboxed = is_sandboxed_file(path);
+    match manifest {
+        Some(manifest) => manager.check_approval(id, &manifest.required_capabilities(sandboxed))?,
+        None if !sandboxed => manager.check_approval(id, &[UNSANDBOXED_CAPABILITY])?,
+        None => {}
+    }
+    load_plugin_file(path)
+}
+
+/// 按文件类型加载单个插件
+fn load_plugin_file(path: &Path) -> Result<Box<dyn Plugin>, ToolError> {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let plugin: Box<dyn Plugin> = match extension.as_str() {
+        // WASM 插件运行在沙箱中
+        WASM_EXTENSION => Box::new(WasmPlugin::load(path, WasmLimits::default())?),
+        // 脚本工具由嵌入的脚本引擎执行
+        SCRIPT_EXTENSION => Box::new(ScriptPlugin::load(path)?),
+        // 原生插件已由用户启用并批准权限，视为可信
+        std::env::consts::DLL_EXTENSION => Box::new(unsafe { NativePlugin::load(path)? }),
+        // 清单指定的其他入口文件作为外部进程运行
+        _ => Box::new(ProcessPlugin::load(path)?),
+    };
+    Ok(plugin)
+}
@@ -0,0 +1,218 @@
+//! HTTP 服务模式
+//!
+//! 在本机回环地址上提供 REST API，供同一台机器上的其他服务调用工具：
+//!
+//! - `GET /tools`：所有工具的描述
+//! - `GET /tools/{id}`：单个工具的描述，包括类别和参数说明
+//! - `POST /tools/{id}/execute`：执行工具
+//!
+//! 执行请求有两种形式：`Content-Type: application/json` 时请求体为
+//! `{"input": "输入文本", "parameters": {"key": "value"}}`；其他类型的请求体原样作为输入数据，
+//! 参数通过查询字符串传递，例如 `?mode=upper`。
+//!
+//! 响应与插件协议的 `ExecuteResponse` 一致，成功时为 `{"output": ...}`，
+//! 失败时为 `{"error": {"code": ..., "message": ...}}`，出错的参数、输入位置和修改建议等字段见
+//! `ErrorPayload`，HTTP 状态码见 [`status_code`]。
+//! 启动时指定令牌后，请求需要携带 `Authorization: Bearer <令牌>` 请求头。
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use unitools_core::error::{InputPosition, ToolError};
+use unitools_core::protocol::{ErrorPayload, ExecuteResponse};
+use unitools_core::tool::{CancellationToken, ToolContext, ToolDescriptor};
+
+use crate::cli::Toolbox;
+
+/// 默认端口
+pub const DEFAULT_PORT: u16 = 7878;
+
+/// 服务共享的状态
+struct ServerState {
+    toolbox: Toolbox,
+    token: Option<String>,
+}
+
+/// JSON 形式的执行请求
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ExecuteBody {
+    /// 输入文本
+    input: Option<String>,
+    /// 参数
+    parameters: HashMap<String, String>,
+}
+
+/// 工具错误对应的 HTTP 状态码
+///
+/// | 状态码 | 错误类别 |
+/// | --- | --- |
+/// | 400 | 参数错误 |
+/// | 403 | 权限不足 |
+/// | 404 | 未找到 |
+/// | 422 | 格式错误、解析错误 |
+/// | 499 | 操作已取消（客户端断开连接） |
+/// | 500 | IO 错误、其他错误 |
+/// | 501 | 未实现 |
+/// | 502 | 网络错误、插件错误 |
+///
+/// 流水线错误使用失败步骤的错误对应的状态码。
+pub fn status_code(error: &ToolError) -> StatusCode {
+    match error {
+        ToolError::ParameterError(_) => StatusCode::BAD_REQUEST,
+        ToolError::PermissionDenied(_) => StatusCode::FORBIDDEN,
+        ToolError::NotFoundError(_) => StatusCode::NOT_FOUND,
+        ToolError::FormatError(_) | ToolError::ParseError(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        ToolError::Cancelled => StatusCode::from_u16(499).unwrap_or(StatusCode::BAD_REQUEST),
+        ToolError::NotImplementedError(_) => StatusCode::NOT_IMPLEMENTED,
+        ToolError::NetworkError(_) | ToolError::PluginError(_) => StatusCode::BAD_GATEWAY,
+        ToolError::PipelineError { source, .. } => status_code(source),
+        ToolError::Diagnostic { error, .. } => status_code(error),
+        ToolError::IoError(_) | ToolError::Other(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// 将工具错误转换为响应
+fn error_response(error: &ToolError) -> Response {
+    let body = ExecuteResponse {
+        output: None,
+        error: Some(ErrorPayload::from(error)),
+    };
+    (status_code(error), axum::Json(body)).into_response()
+}
+
+/// 在本机启动服务，直到进程被终止
+pub fn serve(toolbox: Toolbox, port: u16, token: Option<String>) -> Result<(), ToolError> {
+    let state = Arc::new(ServerState { toolbox, token });
+    let app = Router::new()
+        .route("/tools", get(list_tools))
+        .route("/tools/{id}", get(describe_tool))
+        .route("/tools/{id}/execute", post(execute_tool))
+        .layer(middleware::from_fn_with_state(state.clone(), authorize))
+        .with_state(state);
+
+    // 只监听回环地址，不对外暴露
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(async move {
+        let listener = tokio::net::TcpListener::bind(address).await?;
+        log::info!("UniTools 服务已启动: http://{}", address);
+        axum::serve(listener, app).await?;
+        Ok(())
+    })
+}
+
+/// 检查请求携带的令牌
+async fn authorize(
+    State(state): State<Arc<ServerState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if let Some(token) = &state.token {
+        let provided = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        if provided != Some(token.as_str()) {
+            let error = ToolError::PermissionDenied("缺少或无效的访问令牌".to_string());
+            let mut response = error_response(&error);
+            *response.status_mut() = StatusCode::UNAUTHORIZED;
+            return response;
+        }
+    }
+    next.run(request).await
+}
+
+/// `GET /tools`
+async fn list_tools(State(state): State<Arc<ServerState>>) -> Response {
+    let descriptors: Vec<ToolDescriptor> = state
+        .toolbox
+        .tools()
+        .iter()
+        .map(|tool| ToolDescriptor::from_tool(tool.as_ref()))
+        .collect();
+    axum::Json(descriptors).into_response()
+}
+
+/// `GET /tools/{id}`
+async fn describe_tool(State(state): State<Arc<ServerState>>, Path(id): Path<String>) -> Response {
+    match state.toolbox.find(&id) {
+        Ok(tool) => axum::Json(ToolDescriptor::from_tool(tool.as_ref())).into_response(),
+        Err(error) => error_response(&error),
+    }
+}
+
+/// `POST /tools/{id}/execute`
+async fn execute_tool(
+    State(state): State<Arc<ServerState>>,
+    Path(id): Path<String>,
+    Query(query): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let tool = match state.toolbox.find(&id) {
+        Ok(tool) => tool,
+        Err(error) => return error_response(&error),
+    };
+
+    let is_json = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+
+    let (input_data, parameters) = if is_json {
+        match serde_json::from_slice::<ExecuteBody>(&body) {
+            Ok(body) => (body.input.map(String::into_bytes), body.parameters),
+            Err(e) => {
+                let error = ToolError::ParseError(format!("无效的请求体: {}", e)).with_position(
+                    InputPosition {
+                        line: e.line(),
+                        column: e.column(),
+                    },
+                );
+                return error_response(&error);
+            }
+        }
+    } else {
+        let input = (!body.is_empty()).then(|| body.to_vec());
+        (input, query)
+    };
+
+    let ctx = ToolContext {
+        input_data,
+        parameters,
+        ..state.toolbox.context()
+    };
+
+    // 客户端断开连接时请求被丢弃，此时取消正在执行的工具
+    let _guard = CancelOnDrop(ctx.cancellation.clone());
+    let result = tokio::task::spawn_blocking(move || tool.validate_and_execute(ctx)).await;
+
+    match result {
+        Ok(Ok(output)) => axum::Json(ExecuteResponse {
+            output,
+            error: None,
+        })
+        .into_response(),
+        Ok(Err(error)) => error_response(&error),
+        Err(e) => error_response(&ToolError::Other(format!("工具执行异常终止: {}", e))),
+    }
+}
+
+/// 被丢弃时触发取消令牌
+struct CancelOnDrop(CancellationToken);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
@@ -1,7 +1,10 @@
 use std::fmt::Debug;
+use std::path::PathBuf;
 use std::sync::Arc;
+use unitools_core::capability::Capability;
 use unitools_core::error::ToolError;
-use unitools_core::tool::{Tool, ToolCategory, ToolContext, ToolResult};
+use unitools_core::tool::{ParameterSpec, Tool, ToolCategory, ToolContext, ToolOutput, ToolResult};
+use unitools_core::tr;
 use unitools_utils::file;
 
 /// 获取所有文件工具
@@ -14,6 +17,10 @@ pub fn get_file_tools() -> Vec<Arc<dyn Tool>> {
 pub struct FileInfoTool {}
 
 impl Tool for FileInfoTool {
+    fn id(&self) -> &str {
+        "file.info"
+    }
+
     fn name(&self) -> &str {
         "文件信息"
     }
@@ -26,36 +33,43 @@ impl Tool for FileInfoTool {
         ToolCategory::File
     }
 
-    fn parameter_descriptions(&self) -> Vec<(String, String)> {
-        vec![("path".to_string(), "文件路径".to_string())]
+    fn parameters(&self) -> Vec<ParameterSpec> {
+        vec![ParameterSpec::path("path", "文件路径").required()]
+    }
+
+    fn capabilities(&self) -> Vec<Capability> {
+        vec![Capability::FsRead(None)]
     }
 
     fn execute(&self, ctx: &ToolContext) -> ToolResult {
         // 获取文件路径参数
         let path = match ctx.parameters.get("path") {
             Some(p) => p,
-            None => return Err(ToolError::ParameterError("没有提供文件路径".to_string())),
+            None => return Err(ToolError::ParameterError(tr!("file-missing-path"))),
         };
+        ctx.require(&Capability::FsRead(Some(PathBuf::from(path))))?;
 
         // 检查文件是否存在
         if !file::file_exists(path) {
-            return Err(ToolError::NotFoundError(format!("文件不存在: {}", path)));
+            return Err(ToolError::NotFoundError(tr!("file-not-found", path = path)));
         }
 
         // 收集文件信息
         let size = file::file_size(path)?;
-        let extension = file::file_extension(path).unwrap_or_else(|| "无".to_string());
-        let filename = file::file_name(path).unwrap_or_else(|| "未知".to_string());
+        let extension = file::file_extension(path).unwrap_or_else(|| tr!("file-no-extension"));
+        let filename = file::file_name(path).unwrap_or_else(|| tr!("file-unknown-name"));
 
         // 显示文件基本信息
-        let mut info = String::new();
-        info.push_str(&format!("文件名: {}\n", filename));
-        info.push_str(&format!("路径: {}\n", path));
-        info.push_str(&format!("大小: {} 字节\n", size));
-        info.push_str(&format!("扩展名: {}\n", extension));
+        let info = tr!(
+            "file-info-result",
+            name = filename,
+            path = path,
+            size = size,
+            extension = extension
+        );
 
         // 返回结果
-        Ok(Some(info.into_bytes()))
+        Ok(Some(ToolOutput::Text(info)))
     }
 }
 
@@ -64,6 +78,10 @@ impl Tool for FileInfoTool {
 pub struct FileBrowserTool {}
 
 impl Tool for FileBrowserTool {
+    fn id(&self) -> &str {
+        "file.browse"
+    }
+
     fn name(&self) -> &str {
         "文件浏览器"
     }
@@ -76,25 +94,24 @@ impl Tool for FileBrowserTool {
         ToolCategory::File
     }
 
-    fn parameter_descriptions(&self) -> Vec<(String, String)> {
-        vec![("directory".to_string(), "要浏览的目录路径".to_string())]
+    fn parameters(&self) -> Vec<ParameterSpec> {
+        vec![ParameterSpec::path("directory", "要浏览的目录路径").with_default(".")]
+    }
+
+    fn capabilities(&self) -> Vec<Capability> {
+        vec![Capability::FsRead(None)]
     }
 
     fn execute(&self, ctx: &ToolContext) -> ToolResult {
-        // 获取目录路径参数
-        let dir_path = match ctx.parameters.get("directory") {
-            Some(p) => p,
-            None => {
-                // 如果没有提供目录参数，则使用当前目录
-                "."
-            }
-        };
+        // 获取目录路径参数，如果没有提供则使用当前目录
+        let dir_path = ctx.parameter("directory").unwrap_or(".");
+        ctx.require(&Capability::FsRead(Some(PathBuf::from(dir_path))))?;
 
         // 检查目录是否存在
         if !file::directory_exists(dir_path) {
-            return Err(ToolError::NotFoundError(format!(
-                "目录不存在: {}",
-                dir_path
+            return Err(ToolError::NotFoundError(tr!(
+                "file-directory-not-found",
+                path = dir_path
             )));
         }
 
@@ -102,40 +119,51 @@ impl Tool for FileBrowserTool {
         let files = file::list_dir_files(dir_path)?;
         let subdirs = file::list_dir_subdirs(dir_path)?;
 
-        // 构建结果
-        let mut result = String::new();
-        result.push_str(&format!("目录: {}\n\n", dir_path));
-
-        // 列出子目录
-        result.push_str("子目录:\n");
-        if subdirs.is_empty() {
-            result.push_str("  (无)\n");
-        } else {
-            for dir in subdirs {
-                result.push_str(&format!(
-                    "  [DIR] {}\n",
-                    dir.file_name().unwrap_or_default().to_string_lossy()
-                ));
-            }
+        // 构建结果表格，子目录在前
+        let total = subdirs.len() + files.len();
+        let mut rows = Vec::with_capacity(total);
+        for dir in subdirs {
+            ctx.cancellation.check()?;
+            rows.push(vec![
+                dir.file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string(),
+                tr!("file-type-directory"),
+                String::new(),
+            ]);
         }
 
-        result.push_str("\n文件:\n");
-        if files.is_empty() {
-            result.push_str("  (无)\n");
-        } else {
-            for file_path in files {
-                let file_size = match file::file_size(&file_path) {
-                    Ok(size) => format!("{} 字节", size),
-                    Err(_) => "未知大小".to_string(),
-                };
-                result.push_str(&format!(
-                    "  {} ({})\n",
-                    file_path.file_name().unwrap_or_default().to_string_lossy(),
-                    file_size
-                ));
-            }
+        for file_path in files {
+            ctx.cancellation.check()?;
+            ctx.progress.report_count(
+                rows.len(),
+                total,
+                &tr!("file-reading", path = file_path.display()),
+            );
+
+            let file_size = match file::file_size(&file_path) {
+                Ok(size) => tr!("file-size", size = size),
+                Err(_) => tr!("file-unknown-size"),
+            };
+            rows.push(vec![
+                file_path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string(),
+                tr!("file-type-file"),
+                file_size,
+            ]);
         }
 
-        Ok(Some(result.into_bytes()))
+        Ok(Some(ToolOutput::Table {
+            headers: vec![
+                tr!("file-column-name"),
+                tr!("file-column-type"),
+                tr!("file-column-size"),
+            ],
+            rows,
+        }))
     }
 }
@@ -1,7 +1,8 @@
 use std::fmt::Debug;
 use std::sync::Arc;
-use unitools_core::error::ToolError;
-use unitools_core::tool::{Tool, ToolCategory, ToolContext, ToolResult};
+use unitools_core::error::{InputPosition, ToolError};
+use unitools_core::tool::{ParameterSpec, Tool, ToolCategory, ToolContext, ToolOutput, ToolResult};
+use unitools_core::tr;
 use unitools_utils::text;
 
 /// 获取所有文本工具
@@ -18,6 +19,10 @@ pub fn get_text_tools() -> Vec<Arc<dyn Tool>> {
 pub struct TextAnalyzer {}
 
 impl Tool for TextAnalyzer {
+    fn id(&self) -> &str {
+        "text.analyze"
+    }
+
     fn name(&self) -> &str {
         "文本分析器"
     }
@@ -35,13 +40,13 @@ impl Tool for TextAnalyzer {
         let input = match &ctx.input_data {
             Some(data) => match String::from_utf8(data.clone()) {
                 Ok(text) => text,
-                Err(_) => {
-                    return Err(ToolError::FormatError(
-                        "输入数据不是有效的UTF-8文本".to_string(),
-                    ))
+                Err(e) => {
+                    return Err(ToolError::FormatError(tr!("input-not-utf8")).with_position(
+                        InputPosition::from_offset(data, e.utf8_error().valid_up_to()),
+                    ));
                 }
             },
-            None => return Err(ToolError::ParameterError("没有提供输入文本".to_string())),
+            None => return Err(ToolError::ParameterError(tr!("tool-no-input"))),
         };
 
         // 分析文本
@@ -50,12 +55,14 @@ impl Tool for TextAnalyzer {
         let line_count = text::count_lines(&input);
 
         // 构建结果
-        let result = format!(
-            "文本分析结果:\n\n字符数: {}\n单词数: {}\n行数: {}",
-            char_count, word_count, line_count
+        let result = tr!(
+            "text-analyze-result",
+            chars = char_count,
+            words = word_count,
+            lines = line_count
         );
 
-        Ok(Some(result.into_bytes()))
+        Ok(Some(ToolOutput::Text(result)))
     }
 }
 
@@ -64,6 +71,10 @@ impl Tool for TextAnalyzer {
 pub struct TextCaseConverter {}
 
 impl Tool for TextCaseConverter {
+    fn id(&self) -> &str {
+        "text.case"
+    }
+
     fn name(&self) -> &str {
         "大小写转换"
     }
@@ -76,11 +87,15 @@ impl Tool for TextCaseConverter {
         ToolCategory::Text
     }
 
-    fn parameter_descriptions(&self) -> Vec<(String, String)> {
-        vec![(
-            "mode".to_string(),
-            "转换模式: upper=大写, lower=小写".to_string(),
-        )]
+    fn parameters(&self) -> Vec<ParameterSpec> {
+        vec![
+            ParameterSpec::enumeration(
+                "mode",
+                "转换模式: upper=大写, lower=小写",
+                &["upper", "lower"],
+            )
+            .with_default("upper"),
+        ]
     }
 
     fn execute(&self, ctx: &ToolContext) -> ToolResult {
@@ -88,35 +103,31 @@ impl Tool for TextCaseConverter {
         let input = match &ctx.input_data {
             Some(data) => match String::from_utf8(data.clone()) {
                 Ok(text) => text,
-                Err(_) => {
-                    return Err(ToolError::FormatError(
-                        "输入数据不是有效的UTF-8文本".to_string(),
-                    ))
+                Err(e) => {
+                    return Err(ToolError::FormatError(tr!("input-not-utf8")).with_position(
+                        InputPosition::from_offset(data, e.utf8_error().valid_up_to()),
+                    ));
                 }
             },
-            None => return Err(ToolError::ParameterError("没有提供输入文本".to_string())),
+            None => return Err(ToolError::ParameterError(tr!("tool-no-input"))),
         };
 
         // 获取转换模式参数
-        let mode = ctx
-            .parameters
-            .get("mode")
-            .cloned()
-            .unwrap_or_else(|| "upper".to_string());
+        let mode = ctx.parameter("mode").unwrap_or("upper");
 
         // 根据模式转换文本
-        let result = match mode.to_lowercase().as_str() {
+        let result = match mode {
             "upper" => text::to_uppercase(&input),
             "lower" => text::to_lowercase(&input),
             _ => {
-                return Err(ToolError::ParameterError(format!(
-                    "无效的模式参数: {}",
-                    mode
-                )))
+                return Err(
+                    ToolError::ParameterError(tr!("tool-invalid-mode", mode = mode))
+                        .with_parameter("mode"),
+                );
             }
         };
 
-        Ok(Some(result.into_bytes()))
+        Ok(Some(ToolOutput::Text(result)))
     }
 }
 
@@ -125,6 +136,10 @@ impl Tool for TextCaseConverter {
 pub struct TextRegexTool {}
 
 impl Tool for TextRegexTool {
+    fn id(&self) -> &str {
+        "text.regex"
+    }
+
     fn name(&self) -> &str {
         "正则表达式工具"
     }
@@ -137,77 +152,105 @@ impl Tool for TextRegexTool {
         ToolCategory::Text
     }
 
-    fn parameter_descriptions(&self) -> Vec<(String, String)> {
+    fn parameters(&self) -> Vec<ParameterSpec> {
         vec![
-            ("pattern".to_string(), "正则表达式模式".to_string()),
-            (
-                "replacement".to_string(),
-                "替换文本(可选，如果提供则执行替换操作)".to_string(),
-            ),
-            (
-                "mode".to_string(),
-                "操作模式: find=查找, replace=替换(默认为find)".to_string(),
-            ),
+            ParameterSpec::string("pattern", "正则表达式模式").required(),
+            ParameterSpec::string("replacement", "替换文本(仅在replace模式下使用)"),
+            ParameterSpec::enumeration(
+                "mode",
+                "操作模式: find=查找, replace=替换",
+                &["find", "replace"],
+            )
+            .with_default("find"),
         ]
     }
 
+    fn config_schema(&self) -> Vec<ParameterSpec> {
+        vec![ParameterSpec::bool("case_insensitive", "匹配时忽略大小写").with_default("false")]
+    }
+
     fn execute(&self, ctx: &ToolContext) -> ToolResult {
         // 获取输入数据
         let input = match &ctx.input_data {
             Some(data) => match String::from_utf8(data.clone()) {
                 Ok(text) => text,
-                Err(_) => {
-                    return Err(ToolError::FormatError(
-                        "输入数据不是有效的UTF-8文本".to_string(),
-                    ))
+                Err(e) => {
+                    return Err(ToolError::FormatError(tr!("input-not-utf8")).with_position(
+                        InputPosition::from_offset(data, e.utf8_error().valid_up_to()),
+                    ));
                 }
             },
-            None => return Err(ToolError::ParameterError("没有提供输入文本".to_string())),
+            None => return Err(ToolError::ParameterError(tr!("tool-no-input"))),
         };
 
         // 获取正则表达式参数
         let pattern = match ctx.parameters.get("pattern") {
             Some(p) => p,
             None => {
-                return Err(ToolError::ParameterError(
-                    "没有提供正则表达式模式".to_string(),
-                ))
+                return Err(ToolError::ParameterError(tr!("text-regex-missing-pattern"))
+                    .with_parameter("pattern"));
             }
         };
 
+        // 按配置忽略大小写
+        let pattern = &if ctx
+            .parse_config::<bool>("case_insensitive")?
+            .unwrap_or(false)
+        {
+            format!("(?i){}", pattern)
+        } else {
+            pattern.clone()
+        };
+
         // 获取操作模式
-        let mode = ctx
-            .parameters
-            .get("mode")
-            .cloned()
-            .unwrap_or_else(|| "find".to_string());
+        let mode = ctx.parameter("mode").unwrap_or("find");
 
         // 根据模式执行操作
-        let result = match mode.to_lowercase().as_str() {
+        let result = match mode {
             "find" => {
-                let matches = text::regex_find_all(&input, pattern)?;
+                let matches = text::regex_find_matches(&input, pattern)
+                    .map_err(|e| e.with_parameter("pattern"))?;
                 if matches.is_empty() {
-                    "未找到匹配项".to_string()
+                    ToolOutput::Text(tr!("text-regex-no-match"))
                 } else {
-                    format!("找到 {} 个匹配项:\n\n{}", matches.len(), matches.join("\n"))
+                    let rows = matches
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, (start, end, matched))| {
+                            vec![
+                                (i + 1).to_string(),
+                                start.to_string(),
+                                end.to_string(),
+                                matched,
+                            ]
+                        })
+                        .collect();
+                    ToolOutput::Table {
+                        headers: vec![
+                            tr!("text-regex-column-index"),
+                            tr!("text-regex-column-start"),
+                            tr!("text-regex-column-end"),
+                            tr!("text-regex-column-match"),
+                        ],
+                        rows,
+                    }
                 }
             }
             "replace" => {
-                let replacement = ctx
-                    .parameters
-                    .get("replacement")
-                    .cloned()
-                    .unwrap_or_else(|| "".to_string());
-                text::regex_replace_all(&input, pattern, &replacement)?
+                let replacement = ctx.parameter("replacement").unwrap_or("");
+                ToolOutput::Text(
+                    text::regex_replace_all(&input, pattern, replacement)
+                        .map_err(|e| e.with_parameter("pattern"))?,
+                )
             }
             _ => {
-                return Err(ToolError::ParameterError(format!(
-                    "无效的模式参数: {}",
-                    mode
-                )))
+                return Err(
+                    ToolError::ParameterError(tr!("tool-invalid-mode", mode = mode))
+                        .with_parameter("mode"),
+                );
             }
         };
 
-        Ok(Some(result.into_bytes()))
+        Ok(Some(result))
     }
 }
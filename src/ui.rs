@@ -1,13 +1,18 @@
 mod about;
+mod batch;
 mod home;
+mod pipeline;
 mod settings;
 mod theme;
 mod tool;
 
 use eframe::egui;
+use unitools_core::tr;
 
 pub use about::render_about_page;
+pub use batch::render_batch_page;
 pub use home::render_home_page;
+pub use pipeline::render_pipeline_page;
 pub use settings::render_settings_page;
 pub use theme::setup_theme;
 pub use tool::render_tool_page;
@@ -17,10 +22,47 @@ pub use tool::render_tool_page;
 pub enum Page {
     Home,
     Tool,
+    Pipeline,
+    Batch,
     Settings,
     About,
 }
 
+/// 在窗口右下角渲染通知，不阻塞其他操作
+pub fn render_notification(ctx: &egui::Context, app: &mut crate::app::UniToolsApp) {
+    let Some(notification) = &app.notification else {
+        return;
+    };
+    if notification.is_expired() {
+        app.notification = None;
+        return;
+    }
+
+    let mut closed = false;
+    egui::Window::new("notification")
+        .title_bar(false)
+        .resizable(false)
+        .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-12.0, -12.0))
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if notification.is_error {
+                    ui.colored_label(egui::Color32::RED, &notification.message);
+                } else {
+                    ui.label(&notification.message);
+                }
+                if ui.small_button("✕").clicked() {
+                    closed = true;
+                }
+            });
+        });
+
+    if closed {
+        app.notification = None;
+    } else if !notification.is_error {
+        ctx.request_repaint_after(std::time::Duration::from_millis(500));
+    }
+}
+
 /// 渲染侧边导航栏
 pub fn render_sidebar(ctx: &egui::Context, app: &mut crate::app::UniToolsApp) {
     // 预先收集工具信息，避免在闭包中直接访问app
@@ -29,8 +71,11 @@ pub fn render_sidebar(ctx: &egui::Context, app: &mut crate::app::UniToolsApp) {
     let categories: Vec<_> = app.categories.keys().collect();
     for &category in &categories {
         if let Some(tools) = app.categories.get(category) {
-            let tool_names: Vec<String> = tools.iter().map(|t| t.name().to_string()).collect();
-            categorized_tools.push((category.to_string(), tool_names));
+            let tool_entries: Vec<(String, String)> = tools
+                .iter()
+                .map(|t| (t.id().to_string(), t.localized_name()))
+                .collect();
+            categorized_tools.push((category.to_string(), tool_entries));
         }
     }
 
@@ -39,26 +84,34 @@ pub fn render_sidebar(ctx: &egui::Context, app: &mut crate::app::UniToolsApp) {
 
     egui::SidePanel::left("sidebar").show(ctx, |ui| {
         ui.vertical_centered(|ui| {
-            ui.heading("UniTools 工具箱");
+            ui.heading(tr!("app-title"));
         });
 
         ui.separator();
 
-        if ui.button("主页").clicked() {
+        if ui.button(tr!("nav-home")).clicked() {
             app.navigate_to_page(Page::Home);
         }
 
+        if ui.button(tr!("nav-pipeline")).clicked() {
+            app.navigate_to_page(Page::Pipeline);
+        }
+
+        if ui.button(tr!("nav-batch")).clicked() {
+            app.navigate_to_page(Page::Batch);
+        }
+
         ui.separator();
 
         // 分类菜单
-        egui::CollapsingHeader::new("工具分类")
+        egui::CollapsingHeader::new(tr!("nav-categories"))
             .default_open(true)
             .show(ui, |ui| {
-                for (category, tool_names) in &categorized_tools {
-                    ui.collapsing(format!("{} ({})", category, tool_names.len()), |ui| {
-                        for tool_name in tool_names {
+                for (category, tool_entries) in &categorized_tools {
+                    ui.collapsing(format!("{} ({})", category, tool_entries.len()), |ui| {
+                        for (tool_id, tool_name) in tool_entries {
                             if ui.button(tool_name).clicked() {
-                                app.navigate_to_tool(tool_name);
+                                app.navigate_to_tool(tool_id);
                             }
                         }
                     });
@@ -68,13 +121,22 @@ pub fn render_sidebar(ctx: &egui::Context, app: &mut crate::app::UniToolsApp) {
         ui.separator();
 
         ui.with_layout(egui::Layout::bottom_up(egui::Align::Center), |ui| {
-            if ui.button("关于").clicked() {
+            if ui.button(tr!("nav-about")).clicked() {
                 app.navigate_to_page(Page::About);
             }
 
-            if ui.button("设置").clicked() {
+            if ui.button(tr!("nav-settings")).clicked() {
                 app.navigate_to_page(Page::Settings);
             }
+
+            // 配置文件无法读取或自动保存失败时在所有页面提示
+            if let Some(error) = app.config_load_error() {
+                ui.colored_label(egui::Color32::RED, tr!("nav-config-unreadable"))
+                    .on_hover_text(error);
+            } else if let Some(Err(error)) = app.save_status() {
+                ui.colored_label(egui::Color32::RED, tr!("nav-save-failed"))
+                    .on_hover_text(error);
+            }
         });
     });
 }
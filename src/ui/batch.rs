@@ -0,0 +1,281 @@
+use eframe::egui;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::time::Duration;
+use unitools_core::batch::{self, Batch, BatchDestination, BatchSummary};
+use unitools_core::error::ToolError;
+use unitools_core::tool::{CancellationToken, ProgressReporter};
+use unitools_core::tr;
+
+use super::tool::render_parameter_input;
+
+// 用于存储批量处理页面的状态
+thread_local! {
+    static BATCH_STATE: std::cell::RefCell<BatchState> = std::cell::RefCell::new(BatchState::default());
+}
+
+#[derive(Default)]
+struct BatchState {
+    tool: String,
+    parameters: HashMap<String, String>,
+    source: String,
+    in_place: bool,
+    output_dir: String,
+    workers: usize,
+    running: Option<RunningBatch>,
+    summary: Option<BatchSummary>,
+    error_message: Option<String>,
+}
+
+/// 正在后台运行的批量处理
+struct RunningBatch {
+    progress: ProgressReporter,
+    cancellation: CancellationToken,
+    receiver: Receiver<Result<BatchSummary, ToolError>>,
+}
+
+/// 渲染批量处理页面
+pub fn render_batch_page(ctx: &egui::Context, app: &mut crate::app::UniToolsApp) {
+    // 渲染侧边栏
+    super::render_sidebar(ctx, app);
+
+    let runtime = app.runtime().clone();
+    let mut tool_names: Vec<(String, String)> = app
+        .all_tools()
+        .iter()
+        .map(|t| (t.id().to_string(), t.localized_name()))
+        .collect();
+    tool_names.sort_by(|a, b| a.1.cmp(&b.1));
+
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.vertical_centered(|ui| {
+            ui.heading(tr!("batch-heading"));
+            ui.label(tr!("batch-tagline"));
+        });
+
+        ui.separator();
+
+        BATCH_STATE.with(|state| {
+            let mut state = state.borrow_mut();
+            let state = &mut *state;
+
+            // 检查后台任务是否已完成
+            if let Some(running) = &state.running {
+                match running.receiver.try_recv() {
+                    Ok(result) => {
+                        state.running = None;
+                        match result {
+                            Ok(summary) => state.summary = Some(summary),
+                            Err(error) => {
+                                state.error_message =
+                                    Some(tr!("common-error", error = error.describe()))
+                            }
+                        }
+                    }
+                    Err(TryRecvError::Empty) => {
+                        ctx.request_repaint_after(Duration::from_millis(100));
+                    }
+                    Err(TryRecvError::Disconnected) => {
+                        state.running = None;
+                        state.error_message =
+                            Some(tr!("common-error", error = tr!("batch-aborted")));
+                    }
+                }
+            }
+
+            // 工具和参数
+            ui.horizontal(|ui| {
+                ui.label(tr!("batch-tool"));
+                let selected = tool_names
+                    .iter()
+                    .find(|(id, _)| *id == state.tool)
+                    .map_or_else(|| tr!("common-choose-tool"), |(_, name)| name.clone());
+                egui::ComboBox::from_id_salt("batch_tool")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        for (id, name) in &tool_names {
+                            if ui
+                                .selectable_value(&mut state.tool, id.clone(), name)
+                                .changed()
+                            {
+                                state.parameters.clear();
+                            }
+                        }
+                    });
+            });
+
+            let tool = app.find_tool(&state.tool);
+            if let Some(tool) = &tool {
+                for spec in tool.localized_parameters() {
+                    let value = state
+                        .parameters
+                        .entry(spec.name.clone())
+                        .or_insert_with(|| spec.default.clone().unwrap_or_default());
+                    render_parameter_input(ui, &spec, value);
+                }
+            }
+
+            ui.separator();
+
+            // 输入文件
+            ui.horizontal(|ui| {
+                ui.label(tr!("batch-files"));
+                ui.add(
+                    egui::TextEdit::singleline(&mut state.source)
+                        .hint_text(tr!("batch-source-hint")),
+                );
+                if ui.button(tr!("common-pick-folder")).clicked()
+                    && let Some(path) = rfd::FileDialog::new().pick_folder()
+                {
+                    state.source = path.to_string_lossy().to_string();
+                }
+            });
+
+            // 输出位置
+            ui.horizontal(|ui| {
+                ui.label(tr!("batch-output"));
+                ui.radio_value(&mut state.in_place, false, tr!("batch-mirror"));
+                ui.radio_value(&mut state.in_place, true, tr!("batch-in-place"));
+            });
+            if !state.in_place {
+                ui.horizontal(|ui| {
+                    ui.label(tr!("batch-output-dir"));
+                    ui.text_edit_singleline(&mut state.output_dir);
+                    if ui.button(tr!("common-browse")).clicked()
+                        && let Some(path) = rfd::FileDialog::new().pick_folder()
+                    {
+                        state.output_dir = path.to_string_lossy().to_string();
+                    }
+                });
+            }
+
+            ui.horizontal(|ui| {
+                ui.label(tr!("batch-workers"));
+                ui.add(egui::DragValue::new(&mut state.workers).range(0..=64));
+                ui.weak(tr!("batch-workers-hint"));
+            });
+
+            ui.separator();
+
+            // 执行
+            let can_run = state.running.is_none()
+                && tool.is_some()
+                && !state.source.trim().is_empty()
+                && (state.in_place || !state.output_dir.trim().is_empty());
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(can_run, egui::Button::new(tr!("batch-start")))
+                    .clicked()
+                    && let Some(tool) = tool
+                {
+                    state.summary = None;
+                    state.error_message = None;
+
+                    let destination = if state.in_place {
+                        BatchDestination::InPlace
+                    } else {
+                        BatchDestination::Mirror(PathBuf::from(state.output_dir.trim()))
+                    };
+                    match batch::collect_files(state.source.trim(), &destination) {
+                        Ok((root, files)) => {
+                            let batch = Batch::new(
+                                tool,
+                                state.parameters.clone(),
+                                root,
+                                files,
+                                destination,
+                            )
+                            .with_workers(state.workers);
+                            let context = app.tool_context();
+                            let (sender, receiver) = mpsc::channel();
+                            state.running = Some(RunningBatch {
+                                progress: context.progress.clone(),
+                                cancellation: context.cancellation.clone(),
+                                receiver,
+                            });
+                            runtime.spawn_blocking(move || {
+                                let _ = sender.send(batch.run(&context));
+                            });
+                        }
+                        Err(error) => {
+                            state.error_message =
+                                Some(tr!("common-error", error = error.describe()))
+                        }
+                    }
+                }
+
+                // 显示执行进度
+                if let Some(running) = &state.running {
+                    let progress = running.progress.snapshot();
+                    if let Some(fraction) = progress.fraction {
+                        ui.add(
+                            egui::ProgressBar::new(fraction)
+                                .desired_width(200.0)
+                                .show_percentage(),
+                        );
+                    } else {
+                        ui.spinner();
+                    }
+                    ui.label(&progress.message);
+                    if ui.button(tr!("common-cancel")).clicked() {
+                        running.cancellation.cancel();
+                    }
+                }
+            });
+
+            if let Some(message) = &state.error_message {
+                ui.colored_label(egui::Color32::RED, message);
+            }
+
+            // 每个文件的处理结果
+            if let Some(summary) = &state.summary {
+                ui.separator();
+                let failed = summary.failures().count();
+                ui.label(tr!(
+                    "batch-summary",
+                    total = summary.results.len(),
+                    succeeded = summary.succeeded(),
+                    failed = failed
+                ));
+
+                egui::ScrollArea::vertical()
+                    .id_salt("batch_results")
+                    .show(ui, |ui| {
+                        egui::Grid::new("batch_results_grid")
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong(tr!("batch-column-file"));
+                                ui.strong(tr!("batch-column-result"));
+                                ui.strong(tr!("batch-column-detail"));
+                                ui.end_row();
+
+                                for file in &summary.results {
+                                    ui.label(file.path.display().to_string());
+                                    match &file.result {
+                                        Ok(output) => {
+                                            ui.colored_label(
+                                                egui::Color32::GREEN,
+                                                tr!("common-success"),
+                                            );
+                                            ui.label(match output {
+                                                Some(path) => path.display().to_string(),
+                                                None => tr!("common-no-output"),
+                                            });
+                                        }
+                                        Err(error) => {
+                                            ui.colored_label(
+                                                egui::Color32::RED,
+                                                tr!("common-failure"),
+                                            );
+                                            ui.label(error.to_string());
+                                        }
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            }
+        });
+    });
+}
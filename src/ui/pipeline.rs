@@ -0,0 +1,359 @@
+use eframe::egui;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use unitools_core::error::ToolError;
+use unitools_core::pipeline::{Pipeline, PipelineDefinition, PipelineStepDefinition};
+use unitools_core::task::ToolTask;
+use unitools_core::tool::ToolOutput;
+use unitools_core::tr;
+
+use super::tool::{export_output, render_output, render_parameter_input, render_task_progress};
+
+// 用于存储流水线编辑器的状态
+thread_local! {
+    static PIPELINE_EDITOR: std::cell::RefCell<PipelineEditorState> = std::cell::RefCell::new(PipelineEditorState::default());
+}
+
+#[derive(Default)]
+struct PipelineEditorState {
+    definition: PipelineDefinition,
+    tool_to_add: String,
+    input_text: String,
+    output: Option<ToolOutput>,
+    has_error: bool,
+    error_message: String,
+    status_message: Option<String>,
+    textures: HashMap<String, egui::TextureHandle>,
+    task: Option<ToolTask>,
+}
+
+/// 步骤列表上的编辑操作
+enum StepAction {
+    MoveUp(usize),
+    MoveDown(usize),
+    Remove(usize),
+}
+
+/// 渲染流水线编辑页面
+pub fn render_pipeline_page(ctx: &egui::Context, app: &mut crate::app::UniToolsApp) {
+    // 渲染侧边栏
+    super::render_sidebar(ctx, app);
+
+    let runtime = app.runtime().clone();
+    let mut tool_names: Vec<(String, String)> = app
+        .all_tools()
+        .iter()
+        .map(|t| (t.id().to_string(), t.localized_name()))
+        .collect();
+    tool_names.sort_by(|a, b| a.1.cmp(&b.1));
+
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.vertical_centered(|ui| {
+            ui.heading(tr!("pipeline-heading"));
+            ui.label(tr!("pipeline-tagline"));
+        });
+
+        ui.separator();
+
+        PIPELINE_EDITOR.with(|editor| {
+            let mut state = editor.borrow_mut();
+            let state = &mut *state;
+
+            // 检查后台任务是否已完成
+            if let Some(task) = &mut state.task {
+                if let Some(result) = task.try_result() {
+                    state.task = None;
+                    state.textures.clear();
+                    match result {
+                        Ok(output) => {
+                            state.has_error = false;
+                            state.output = Some(
+                                output.unwrap_or_else(|| ToolOutput::from(tr!("common-no-output"))),
+                            );
+                        }
+                        Err(error) => {
+                            state.has_error = true;
+                            state.error_message = tr!("common-error", error = error.describe());
+                        }
+                    }
+                } else {
+                    ctx.request_repaint_after(Duration::from_millis(100));
+                }
+            }
+
+            // 已保存的流水线
+            let saved_names: Vec<String> = app
+                .config
+                .lock()
+                .unwrap()
+                .pipelines
+                .iter()
+                .map(|p| p.name.clone())
+                .collect();
+
+            ui.horizontal(|ui| {
+                ui.label(tr!("pipeline-saved-list"));
+                egui::ComboBox::from_id_salt("saved_pipelines")
+                    .selected_text(tr!("pipeline-choose"))
+                    .show_ui(ui, |ui| {
+                        for name in &saved_names {
+                            if ui.selectable_label(false, name).clicked()
+                                && let Some(definition) = app.load_pipeline(name)
+                            {
+                                state.definition = definition;
+                                state.status_message = Some(tr!("pipeline-loaded", name = name));
+                            }
+                        }
+                    });
+
+                if ui.button(tr!("common-new")).clicked() {
+                    state.definition = PipelineDefinition::default();
+                    state.status_message = None;
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label(tr!("pipeline-name"));
+                ui.text_edit_singleline(&mut state.definition.name);
+
+                if ui.button(tr!("common-save")).clicked() {
+                    if state.definition.name.trim().is_empty() {
+                        state.status_message = Some(tr!("pipeline-name-required"));
+                    } else {
+                        app.config
+                            .lock()
+                            .unwrap()
+                            .save_pipeline(state.definition.clone());
+                        state.status_message =
+                            Some(tr!("pipeline-saved", name = state.definition.name));
+                    }
+                }
+
+                if ui.button(tr!("common-delete")).clicked() {
+                    app.config
+                        .lock()
+                        .unwrap()
+                        .remove_pipeline(&state.definition.name);
+                    state.status_message =
+                        Some(tr!("pipeline-deleted", name = state.definition.name));
+                }
+            });
+
+            if let Some(message) = &state.status_message {
+                ui.label(message);
+            }
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.vertical(|ui| {
+                    ui.group(|ui| {
+                        ui.heading(tr!("pipeline-steps"));
+
+                        let mut action = None;
+                        let step_count = state.definition.steps.len();
+                        egui::ScrollArea::vertical()
+                            .id_salt("pipeline_steps")
+                            .max_height(ui.available_height() * 0.5)
+                            .show(ui, |ui| {
+                                for (index, step) in state.definition.steps.iter_mut().enumerate() {
+                                    ui.push_id(index, |ui| {
+                                        ui.horizontal(|ui| {
+                                            let tool_name = app.find_tool(&step.tool).map_or_else(
+                                                || step.tool.clone(),
+                                                |tool| tool.localized_name(),
+                                            );
+                                            ui.strong(tr!(
+                                                "pipeline-step",
+                                                index = index + 1,
+                                                tool = tool_name
+                                            ));
+                                            if ui
+                                                .add_enabled(index > 0, egui::Button::new("↑"))
+                                                .clicked()
+                                            {
+                                                action = Some(StepAction::MoveUp(index));
+                                            }
+                                            if ui
+                                                .add_enabled(
+                                                    index + 1 < step_count,
+                                                    egui::Button::new("↓"),
+                                                )
+                                                .clicked()
+                                            {
+                                                action = Some(StepAction::MoveDown(index));
+                                            }
+                                            if ui.button(tr!("common-remove")).clicked() {
+                                                action = Some(StepAction::Remove(index));
+                                            }
+                                        });
+
+                                        match app.find_tool(&step.tool) {
+                                            Some(tool) => {
+                                                for spec in tool.localized_parameters() {
+                                                    let value = step
+                                                        .parameters
+                                                        .entry(spec.name.clone())
+                                                        .or_insert_with(|| {
+                                                            spec.default.clone().unwrap_or_default()
+                                                        });
+                                                    render_parameter_input(ui, &spec, value);
+                                                }
+                                            }
+                                            None => {
+                                                ui.colored_label(
+                                                    egui::Color32::RED,
+                                                    tr!("pipeline-tool-missing"),
+                                                );
+                                            }
+                                        }
+                                        ui.separator();
+                                    });
+                                }
+                            });
+
+                        match action {
+                            Some(StepAction::MoveUp(index)) => {
+                                state.definition.steps.swap(index, index - 1)
+                            }
+                            Some(StepAction::MoveDown(index)) => {
+                                state.definition.steps.swap(index, index + 1)
+                            }
+                            Some(StepAction::Remove(index)) => {
+                                state.definition.steps.remove(index);
+                            }
+                            None => {}
+                        }
+
+                        ui.horizontal(|ui| {
+                            let selected = tool_names
+                                .iter()
+                                .find(|(id, _)| *id == state.tool_to_add)
+                                .map_or_else(
+                                    || tr!("common-choose-tool"),
+                                    |(_, name)| name.clone(),
+                                );
+                            egui::ComboBox::from_id_salt("tool_to_add")
+                                .selected_text(selected)
+                                .show_ui(ui, |ui| {
+                                    for (id, name) in &tool_names {
+                                        ui.selectable_value(
+                                            &mut state.tool_to_add,
+                                            id.clone(),
+                                            name,
+                                        );
+                                    }
+                                });
+
+                            if ui
+                                .add_enabled(
+                                    !state.tool_to_add.is_empty(),
+                                    egui::Button::new(tr!("pipeline-add-step")),
+                                )
+                                .clicked()
+                            {
+                                state.definition.steps.push(PipelineStepDefinition {
+                                    tool: state.tool_to_add.clone(),
+                                    parameters: HashMap::new(),
+                                });
+                            }
+                        });
+
+                        ui.separator();
+
+                        ui.heading(tr!("tool-input-content"));
+                        egui::ScrollArea::vertical()
+                            .id_salt("pipeline_input")
+                            .max_height(ui.available_height() * 0.4)
+                            .show(ui, |ui| {
+                                ui.add(
+                                    egui::TextEdit::multiline(&mut state.input_text)
+                                        .desired_width(f32::INFINITY)
+                                        .desired_rows(8)
+                                        .hint_text(tr!("tool-input-hint")),
+                                );
+                            });
+
+                        ui.horizontal(|ui| {
+                            let can_run =
+                                state.task.is_none() && !state.definition.steps.is_empty();
+                            if ui
+                                .add_enabled(can_run, egui::Button::new(tr!("common-run")))
+                                .clicked()
+                            {
+                                let pipeline =
+                                    Pipeline::from_definition(&state.definition, |name| {
+                                        app.find_tool(name)
+                                    });
+                                match pipeline {
+                                    Ok(pipeline) => {
+                                        let mut context = app.tool_context();
+                                        if !state.input_text.is_empty() {
+                                            context.input_data =
+                                                Some(state.input_text.as_bytes().to_vec());
+                                        }
+                                        state.task = Some(ToolTask::spawn(
+                                            &runtime,
+                                            Arc::new(pipeline),
+                                            context,
+                                        ));
+                                    }
+                                    Err(error) => {
+                                        state.has_error = true;
+                                        state.error_message =
+                                            tr!("common-error", error = error.describe());
+                                    }
+                                }
+                            }
+                        });
+
+                        // 显示执行进度
+                        let cancel_requested = match &state.task {
+                            Some(task) => render_task_progress(ui, task),
+                            None => false,
+                        };
+
+                        if cancel_requested && let Some(task) = state.task.take() {
+                            task.cancel();
+                            state.has_error = true;
+                            state.error_message = tr!("common-error", error = ToolError::Cancelled);
+                        }
+                    });
+                });
+
+                // 输出区域
+                ui.vertical(|ui| {
+                    ui.group(|ui| {
+                        ui.horizontal(|ui| {
+                            ui.heading(tr!("tool-output"));
+                            if let Some(output) = &state.output
+                                && !state.has_error
+                                && ui.button(tr!("common-export")).clicked()
+                            {
+                                state.status_message = export_output(output);
+                            }
+                        });
+                        egui::ScrollArea::vertical()
+                            .id_salt("pipeline_output")
+                            .max_height(ui.available_height() * 0.8)
+                            .show(ui, |ui| {
+                                if state.has_error {
+                                    ui.colored_label(egui::Color32::RED, &state.error_message);
+                                } else if let Some(output) = &state.output {
+                                    render_output(
+                                        ui,
+                                        output,
+                                        "pipeline_output",
+                                        &mut state.textures,
+                                    );
+                                } else {
+                                    ui.weak(tr!("pipeline-output-placeholder"));
+                                }
+                            });
+                    });
+                });
+            });
+        });
+    });
+}
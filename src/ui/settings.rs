@@ -1,104 +1,442 @@
 use eframe::egui;
-use unitools_core::config::{AppConfig, Theme};
+use std::collections::HashMap;
+use unitools_core::capability::Capability;
+use unitools_core::config::{AppConfig, LayeredConfig, Theme};
+use unitools_core::tool::{Tool, ToolConfig};
+use unitools_core::{i18n, tr};
+
+use super::tool::render_parameter_input;
+
+// 等待用户确认的插件权限批准
+thread_local! {
+    static PENDING_APPROVAL: std::cell::RefCell<Option<(String, Vec<Capability>)>> = const { std::cell::RefCell::new(None) };
+}
+
+// 尚未通过校验的工具配置输入，按工具标识和配置项名称索引
+thread_local! {
+    static TOOL_CONFIG_EDITS: std::cell::RefCell<HashMap<String, HashMap<String, String>>> = std::cell::RefCell::new(HashMap::new());
+}
 
 /// 渲染设置页面
 pub fn render_settings_page(ctx: &egui::Context, app: &mut crate::app::UniToolsApp) {
     // 渲染侧边栏
     super::render_sidebar(ctx, app);
 
+    let mut save_requested = false;
+    let mut overwrite_requested = false;
+
     // 渲染主内容区域
     egui::CentralPanel::default().show(ctx, |ui| {
         ui.vertical_centered(|ui| {
-            ui.heading("设置");
+            ui.heading(tr!("settings-heading"));
         });
 
         ui.separator();
 
         // 获取配置的可变引用
         let mut config_guard = app.config.lock().unwrap();
+        let layers = &app.layers;
 
         // 主题设置
-        ui.heading("界面设置");
+        ui.heading(tr!("settings-interface"));
         ui.horizontal(|ui| {
-            ui.label("主题: ");
-            ui.radio_value(&mut config_guard.theme, Theme::Light, "浅色");
-            ui.radio_value(&mut config_guard.theme, Theme::Dark, "深色");
-            ui.radio_value(&mut config_guard.theme, Theme::System, "跟随系统");
+            ui.label(tr!("settings-theme"));
+            ui.add_enabled_ui(!layers.is_locked("theme"), |ui| {
+                ui.radio_value(&mut config_guard.theme, Theme::Light, tr!("theme-light"));
+                ui.radio_value(&mut config_guard.theme, Theme::Dark, tr!("theme-dark"));
+                ui.radio_value(&mut config_guard.theme, Theme::System, tr!("theme-system"));
+            });
+            render_source(ui, layers, "theme");
         });
 
         // 应用主题变更
         super::setup_theme(ctx, config_guard.theme);
 
+        // 界面语言，切换后立即生效
+        ui.horizontal(|ui| {
+            ui.label(tr!("settings-language"));
+            let selected = i18n::LANGUAGES
+                .iter()
+                .find(|(id, _)| *id == config_guard.language)
+                .map_or(config_guard.language.clone(), |(_, name)| name.to_string());
+            ui.add_enabled_ui(!layers.is_locked("language"), |ui| {
+                egui::ComboBox::from_id_salt("language")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        for (id, name) in i18n::LANGUAGES {
+                            if ui
+                                .selectable_value(&mut config_guard.language, id.to_string(), *name)
+                                .changed()
+                                && i18n::set_language(id).is_ok()
+                            {
+                                ctx.send_viewport_cmd(egui::ViewportCommand::Title(tr!(
+                                    "app-title"
+                                )));
+                            }
+                        }
+                    });
+            });
+            render_source(ui, layers, "language");
+        });
+
         ui.separator();
 
         // 用户设置
-        ui.heading("用户设置");
+        ui.heading(tr!("settings-user"));
         ui.horizontal(|ui| {
-            ui.label("用户名: ");
-            ui.text_edit_singleline(&mut config_guard.user.username);
+            ui.label(tr!("settings-username"));
+            ui.add_enabled(
+                !layers.is_locked("user.username"),
+                egui::TextEdit::singleline(&mut config_guard.user.username),
+            );
+            render_source(ui, layers, "user.username");
         });
 
         ui.horizontal(|ui| {
-            ui.label("默认工作目录: ");
+            ui.label(tr!("settings-working-directory"));
             if let Some(dir) = &config_guard.user.working_directory {
                 ui.label(dir.to_string_lossy().to_string());
             } else {
-                ui.label("[未设置]");
+                ui.label(tr!("common-not-set"));
             }
 
-            if ui.button("浏览...").clicked() {
-                // 在实际应用中，这里会打开一个文件选择对话框
-                // 但在这个简化版本中，我们只是添加一个占位符
-                if let Some(path) = rfd::FileDialog::new().pick_folder() {
-                    config_guard.user.working_directory = Some(path);
-                }
+            if ui
+                .add_enabled(
+                    !layers.is_locked("user.working_directory"),
+                    egui::Button::new(tr!("common-browse")),
+                )
+                .clicked()
+                && let Some(path) = rfd::FileDialog::new().pick_folder()
+            {
+                config_guard.user.working_directory = Some(path);
             }
+            render_source(ui, layers, "user.working_directory");
         });
 
         ui.separator();
 
         // 插件设置
-        ui.heading("插件设置");
+        ui.heading(tr!("settings-plugins"));
         ui.horizontal(|ui| {
-            ui.label("插件目录: ");
+            ui.label(tr!("settings-plugin-directory"));
             if let Some(dir) = &config_guard.plugin_directory {
                 ui.label(dir.to_string_lossy().to_string());
             } else {
-                ui.label("[使用默认目录]");
+                ui.label(tr!("settings-default-directory"));
             }
 
-            if ui.button("浏览...").clicked() {
-                if let Some(path) = rfd::FileDialog::new().pick_folder() {
-                    config_guard.plugin_directory = Some(path);
-                }
+            if ui
+                .add_enabled(
+                    !layers.is_locked("plugin_directory"),
+                    egui::Button::new(tr!("common-browse")),
+                )
+                .clicked()
+                && let Some(path) = rfd::FileDialog::new().pick_folder()
+            {
+                config_guard.plugin_directory = Some(path);
             }
+            render_source(ui, layers, "plugin_directory");
         });
 
-        // 已启用的插件列表
-        ui.label("启用的插件:");
+        // 插件目录中发现的插件，勾选后立即加载
+        ui.horizontal(|ui| {
+            ui.label(tr!("settings-available-plugins"));
+            render_source(ui, layers, "enabled_plugins");
+        });
+        let plugins_locked = layers.is_locked("enabled_plugins");
 
-        if config_guard.enabled_plugins.is_empty() {
-            ui.label("[无]");
+        let mut reload_plugins = false;
+        let plugins =
+            crate::plugins::discover_plugins(crate::plugins::plugin_directory(&config_guard));
+        if plugins.is_empty() {
+            ui.label(tr!("common-none"));
         } else {
-            for plugin_name in &config_guard.enabled_plugins {
-                ui.label(plugin_name);
+            for plugin in plugins {
+                let plugin = match plugin {
+                    Ok(plugin) => plugin,
+                    Err(error) => {
+                        ui.colored_label(egui::Color32::RED, error);
+                        continue;
+                    }
+                };
+
+                ui.horizontal(|ui| {
+                    let mut enabled = config_guard.enabled_plugins.contains(&plugin.id);
+                    let label = match &plugin.manifest {
+                        Some(manifest) => {
+                            format!("{} ({})", manifest.display_name(), manifest.version)
+                        }
+                        None => plugin.id.clone(),
+                    };
+                    if ui
+                        .add_enabled(!plugins_locked, egui::Checkbox::new(&mut enabled, label))
+                        .changed()
+                    {
+                        if enabled {
+                            config_guard.enabled_plugins.push(plugin.id.clone());
+                        } else {
+                            config_guard.enabled_plugins.retain(|p| p != &plugin.id);
+                        }
+                        reload_plugins = true;
+                    }
+
+                    if let Some(manifest) = &plugin.manifest {
+                        if !manifest.authors.is_empty() {
+                            ui.weak(tr!(
+                                "settings-plugin-authors",
+                                authors = manifest.authors.join(", ")
+                            ));
+                        }
+                        if !manifest.permissions.is_empty() {
+                            let permissions: Vec<String> =
+                                manifest.permissions.iter().map(|p| p.to_string()).collect();
+                            ui.weak(tr!(
+                                "settings-plugin-permissions-list",
+                                permissions = permissions.join(", ")
+                            ));
+                        }
+                    }
+                });
+            }
+        }
+
+        // 插件权限：插件需要的权限经用户批准后才会加载
+        ui.horizontal(|ui| {
+            ui.label(tr!("settings-plugin-permissions"));
+            render_source(ui, layers, "plugin_permissions");
+        });
+        let permissions_locked = layers.is_locked("plugin_permissions");
+        for (name, capabilities) in app.pending_plugin_approvals() {
+            ui.horizontal(|ui| {
+                ui.colored_label(
+                    egui::Color32::YELLOW,
+                    tr!("settings-plugin-pending", name = name),
+                );
+                if ui
+                    .add_enabled(
+                        !permissions_locked,
+                        egui::Button::new(tr!("settings-approve-ellipsis")),
+                    )
+                    .clicked()
+                {
+                    PENDING_APPROVAL.with(|p| *p.borrow_mut() = Some((name.clone(), capabilities)));
+                }
+            });
+        }
+
+        let mut approved: Vec<_> = config_guard
+            .plugin_permissions
+            .iter()
+            .map(|(name, capabilities)| (name.clone(), capabilities.clone()))
+            .collect();
+        approved.sort();
+        for (name, capabilities) in approved {
+            ui.horizontal(|ui| {
+                let permissions: Vec<String> =
+                    capabilities.iter().map(|c| c.description()).collect();
+                ui.label(format!("{}: {}", name, permissions.join(", ")));
+                if ui
+                    .add_enabled(
+                        !permissions_locked,
+                        egui::Button::new(tr!("settings-revoke")),
+                    )
+                    .clicked()
+                {
+                    config_guard.plugin_permissions.remove(&name);
+                    reload_plugins = true;
+                }
+            });
+        }
+
+        // 批准权限的确认对话框
+        let pending = PENDING_APPROVAL.with(|p| p.borrow().clone());
+        if let Some((name, capabilities)) = pending {
+            let mut decision = None;
+            egui::Window::new(tr!("settings-approve-title"))
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+                .show(ctx, |ui| {
+                    ui.label(tr!("settings-approve-prompt", name = name));
+                    for capability in &capabilities {
+                        ui.label(format!("• {}", capability.description()));
+                    }
+                    ui.horizontal(|ui| {
+                        if ui.button(tr!("settings-approve")).clicked() {
+                            decision = Some(true);
+                        }
+                        if ui.button(tr!("common-cancel")).clicked() {
+                            decision = Some(false);
+                        }
+                    });
+                });
+
+            if let Some(approve) = decision {
+                if approve {
+                    config_guard
+                        .plugin_permissions
+                        .insert(name.clone(), capabilities);
+                    reload_plugins = true;
+                }
+                PENDING_APPROVAL.with(|p| *p.borrow_mut() = None);
             }
         }
 
+        if reload_plugins {
+            app.request_plugin_reload();
+        }
+
+        // 插件加载错误
+        for error in &app.plugin_errors {
+            ui.colored_label(
+                egui::Color32::RED,
+                tr!("settings-plugin-load-failed", error = error),
+            );
+        }
+
         ui.separator();
 
+        // 工具设置，按工具声明的配置规格生成
+        ui.horizontal(|ui| {
+            ui.heading(tr!("settings-tools"));
+            render_source(ui, layers, "tool_configs");
+        });
+        for problem in &app.tool_config_problems {
+            ui.colored_label(egui::Color32::YELLOW, problem);
+        }
+        ui.add_enabled_ui(!layers.is_locked("tool_configs"), |ui| {
+            let mut tools: Vec<_> = app
+                .tools()
+                .iter()
+                .filter(|tool| !tool.config_schema().is_empty())
+                .collect();
+            tools.sort_by_key(|tool| tool.localized_name());
+            if tools.is_empty() {
+                ui.label(tr!("common-none"));
+            }
+            for tool in tools {
+                ui.push_id(tool.id(), |ui| {
+                    ui.collapsing(tool.localized_name(), |ui| {
+                        render_tool_config(ui, tool.as_ref(), &mut config_guard);
+                    });
+                });
+            }
+        });
+
+        ui.separator();
+
+        // 用户配置文件无法读取时不保存，由用户确认后备份并覆盖
+        let config_error = app.config_load_error();
+        if let Some(error) = config_error {
+            ui.colored_label(
+                egui::Color32::RED,
+                tr!("settings-config-unreadable", error = error),
+            );
+        }
+
         // 底部按钮
         ui.horizontal(|ui| {
-            if ui.button("保存设置").clicked() {
-                // 保存配置到文件
-                if let Err(e) = crate::config::save_config(&config_guard, "../config.json") {
-                    eprintln!("保存配置失败: {}", e);
+            if config_error.is_some() {
+                if ui.button(tr!("settings-overwrite-config")).clicked() {
+                    overwrite_requested = true;
+                }
+            } else if ui.button(tr!("settings-save")).clicked() {
+                save_requested = true;
+            }
+
+            // 恢复为不含用户配置时的设置，系统配置、环境变量和命令行参数仍然生效
+            if ui.button(tr!("settings-reset")).clicked() {
+                *config_guard = layers.without_user();
+                if i18n::set_language(&config_guard.language).is_ok() {
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Title(tr!("app-title")));
                 }
             }
 
-            if ui.button("恢复默认").clicked() {
-                *config_guard = AppConfig::default();
+            // 保存状态，修改会在稍后自动保存
+            if app.has_unsaved_changes() {
+                ui.weak(tr!("settings-unsaved"));
+            } else {
+                match app.save_status() {
+                    Some(Ok(())) => {
+                        ui.weak(tr!("settings-saved"));
+                    }
+                    Some(Err(error)) => {
+                        ui.colored_label(
+                            egui::Color32::RED,
+                            tr!("settings-save-failed", error = error),
+                        );
+                    }
+                    None => {}
+                }
             }
         });
     });
+
+    if save_requested {
+        app.save_config();
+    }
+    if overwrite_requested {
+        app.overwrite_unreadable_config();
+    }
+}
+
+/// 渲染工具的配置项，有效的修改立即写入配置，无效的输入保留在编辑状态中并显示错误
+fn render_tool_config(ui: &mut egui::Ui, tool: &dyn Tool, config: &mut AppConfig) {
+    let current = config.tool_config(tool);
+    let mut resolved = ToolConfig {
+        values: current.values.clone(),
+        problems: Vec::new(),
+    };
+
+    TOOL_CONFIG_EDITS.with(|edits| {
+        let mut edits = edits.borrow_mut();
+        let edits = edits.entry(tool.id().to_string()).or_default();
+
+        for spec in tool.localized_config_schema() {
+            let mut value = edits
+                .get(&spec.name)
+                .or(current.values.get(&spec.name))
+                .cloned()
+                .unwrap_or_default();
+            render_parameter_input(ui, &spec, &mut value);
+
+            // 清空输入表示恢复默认值
+            let validated = match &spec.default {
+                Some(default) if value.is_empty() => spec.validate(default),
+                None if value.is_empty() => Ok(String::new()),
+                _ => spec.validate(&value),
+            };
+            match validated {
+                Ok(normalized) => {
+                    edits.remove(&spec.name);
+                    if normalized.is_empty() {
+                        resolved.values.remove(&spec.name);
+                    } else {
+                        resolved.values.insert(spec.name.clone(), normalized);
+                    }
+                }
+                Err(error) => {
+                    ui.colored_label(egui::Color32::RED, error.to_string());
+                    edits.insert(spec.name.clone(), value);
+                }
+            }
+        }
+
+        if ui.button(tr!("settings-reset")).clicked() {
+            edits.clear();
+            resolved.values.clear();
+        }
+    });
+
+    if resolved.values != current.values {
+        config.save_tool_config(tool, &resolved);
+    }
+}
+
+/// 显示配置项的来源，被锁定的配置项显示锁定它的配置层
+fn render_source(ui: &mut egui::Ui, layers: &LayeredConfig, key: &str) {
+    match layers.locked_by(key) {
+        Some(source) => ui.weak(tr!("settings-locked", source = source)),
+        None => ui.weak(tr!("settings-source", source = layers.source(key))),
+    };
 }
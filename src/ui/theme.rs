@@ -62,6 +62,6 @@ fn load_fonts(ctx: &egui::Context) {
         // 应用字体
         ctx.set_fonts(fonts);
     } else {
-        eprintln!("无法加载字体文件: {:?}", font_path);
+        log::warn!("无法加载字体文件: {:?}", font_path);
     }
 }
@@ -1,8 +1,13 @@
 use eframe::egui;
 use std::collections::HashMap;
-use unitools_core::tool::{Tool, ToolContext};
+use std::sync::Arc;
+use std::time::Duration;
+use unitools_core::error::ToolError;
+use unitools_core::task::ToolTask;
+use unitools_core::tool::{ParameterSpec, ParameterType, Tool, ToolOutput, ToolResult};
+use unitools_core::tr;
 
-// 用于存储工具参数输入的状态
+// 用于存储工具参数输入的状态，按工具标识索引
 thread_local! {
     static TOOL_INPUTS: std::cell::RefCell<HashMap<String, ToolInputState>> = std::cell::RefCell::new(HashMap::new());
 }
@@ -10,66 +15,111 @@ thread_local! {
 struct ToolInputState {
     input_text: String,
     parameters: HashMap<String, String>,
-    output_text: Option<String>,
+    output: Option<ToolOutput>,
     has_error: bool,
     error_message: String,
+    /// 导致执行失败的参数，在参数输入框上突出显示
+    error_parameter: Option<String>,
+    export_message: Option<String>,
+    textures: HashMap<String, egui::TextureHandle>,
+    task: Option<ToolTask>,
+}
+
+impl ToolInputState {
+    /// 记录工具执行结果
+    fn set_result(&mut self, result: ToolResult) {
+        self.export_message = None;
+        self.textures.clear();
+        self.error_parameter = None;
+        match result {
+            Ok(output) => {
+                self.has_error = false;
+                self.output =
+                    Some(output.unwrap_or_else(|| ToolOutput::from(tr!("common-no-output"))));
+            }
+            Err(error) => {
+                self.has_error = true;
+                self.error_message = tr!("common-error", error = error.describe());
+                self.error_parameter = error.parameter().map(str::to_string);
+            }
+        }
+    }
 }
 
 /// 渲染工具页面
-pub fn render_tool_page(ctx: &egui::Context, tool: &dyn Tool, app: &mut crate::app::UniToolsApp) {
+pub fn render_tool_page(
+    ctx: &egui::Context,
+    tool: Arc<dyn Tool>,
+    app: &mut crate::app::UniToolsApp,
+) {
     // 渲染侧边栏
     super::render_sidebar(ctx, app);
 
+    let runtime = app.runtime().clone();
+
     // 渲染工具内容区域
     egui::CentralPanel::default().show(ctx, |ui| {
         // 工具页面标题
         ui.vertical_centered(|ui| {
-            ui.heading(tool.name());
-            ui.label(tool.description());
+            ui.heading(tool.localized_name());
+            ui.label(tool.localized_description());
         });
 
         ui.separator();
 
         // 获取工具的输入状态
-        let tool_name = tool.name().to_string();
+        let tool_id = tool.id().to_string();
 
         TOOL_INPUTS.with(|tool_inputs| {
             let mut tools = tool_inputs.borrow_mut();
             let input_state = tools
-                .entry(tool_name.clone())
+                .entry(tool_id.clone())
                 .or_insert_with(|| ToolInputState {
                     input_text: String::new(),
                     parameters: HashMap::new(),
-                    output_text: None,
+                    output: None,
                     has_error: false,
                     error_message: String::new(),
+                    error_parameter: None,
+                    export_message: None,
+                    textures: HashMap::new(),
+                    task: None,
                 });
 
+            // 检查后台任务是否已完成
+            if let Some(task) = &mut input_state.task {
+                if let Some(result) = task.try_result() {
+                    input_state.task = None;
+                    input_state.set_result(result);
+                } else {
+                    ctx.request_repaint_after(Duration::from_millis(100));
+                }
+            }
+
             ui.horizontal(|ui| {
                 ui.vertical(|ui| {
                     ui.group(|ui| {
-                        ui.heading("输入");
+                        ui.heading(tr!("tool-input"));
 
                         // 添加工具参数输入
-                        let param_descriptions = tool.parameter_descriptions();
-                        if !param_descriptions.is_empty() {
-                            ui.heading("参数");
-                            for (param_name, param_desc) in param_descriptions {
-                                ui.horizontal(|ui| {
-                                    ui.label(&param_name);
-                                    ui.text_edit_singleline(
-                                        input_state
-                                            .parameters
-                                            .entry(param_name)
-                                            .or_insert(String::new()),
-                                    );
-                                    ui.label(param_desc);
-                                });
+                        let param_specs = tool.localized_parameters();
+                        if !param_specs.is_empty() {
+                            ui.heading(tr!("tool-parameters"));
+                            for spec in &param_specs {
+                                let value = input_state
+                                    .parameters
+                                    .entry(spec.name.clone())
+                                    .or_insert_with(|| spec.default.clone().unwrap_or_default());
+                                if input_state.error_parameter.as_ref() == Some(&spec.name) {
+                                    render_invalid_parameter(ui, spec, value);
+                                } else {
+                                    render_parameter_input(ui, spec, value);
+                                }
                             }
                             ui.separator();
                         }
 
-                        ui.heading("输入内容");
+                        ui.heading(tr!("tool-input-content"));
                         let text_height = ui.available_height() * 0.4;
                         egui::ScrollArea::vertical()
                             .max_height(text_height)
@@ -78,14 +128,18 @@ pub fn render_tool_page(ctx: &egui::Context, tool: &dyn Tool, app: &mut crate::a
                                     egui::TextEdit::multiline(&mut input_state.input_text)
                                         .desired_width(f32::INFINITY)
                                         .desired_rows(10)
-                                        .hint_text("输入文本内容..."),
+                                        .hint_text(tr!("tool-input-hint")),
                                 );
                             });
 
                         ui.horizontal(|ui| {
-                            if ui.button("执行").clicked() {
+                            let running = input_state.task.is_some();
+                            if ui
+                                .add_enabled(!running, egui::Button::new(tr!("common-run")))
+                                .clicked()
+                            {
                                 // 创建工具上下文
-                                let mut context = ToolContext::default();
+                                let mut context = app.tool_context();
 
                                 // 添加文本输入
                                 if !input_state.input_text.is_empty() {
@@ -100,46 +154,54 @@ pub fn render_tool_page(ctx: &egui::Context, tool: &dyn Tool, app: &mut crate::a
                                     }
                                 }
 
-                                // 执行工具
-                                match tool.execute(&context) {
-                                    Ok(result) => {
-                                        input_state.has_error = false;
-                                        if let Some(data) = result {
-                                            // 尝试将结果转换为字符串
-                                            match String::from_utf8(data) {
-                                                Ok(text) => {
-                                                    input_state.output_text = Some(text);
-                                                }
-                                                Err(_) => {
-                                                    input_state.output_text =
-                                                        Some("[二进制数据]".to_string());
-                                                }
-                                            }
-                                        } else {
-                                            input_state.output_text = Some("[无输出]".to_string());
-                                        }
-                                    }
-                                    Err(error) => {
-                                        input_state.has_error = true;
-                                        input_state.error_message = format!("错误: {}", error);
-                                    }
-                                }
+                                // 在后台校验参数并执行工具
+                                input_state.task =
+                                    Some(ToolTask::spawn(&runtime, tool.clone(), context));
                             }
 
-                            if ui.button("清除").clicked() {
+                            if ui.button(tr!("common-clear")).clicked() {
+                                if let Some(task) = input_state.task.take() {
+                                    task.cancel();
+                                }
                                 input_state.input_text.clear();
                                 input_state.parameters.clear();
-                                input_state.output_text = None;
+                                input_state.output = None;
                                 input_state.has_error = false;
+                                input_state.error_parameter = None;
+                                input_state.export_message = None;
+                                input_state.textures.clear();
                             }
                         });
+
+                        // 显示执行进度
+                        let cancel_requested = match &input_state.task {
+                            Some(task) => render_task_progress(ui, task),
+                            None => false,
+                        };
+
+                        // 取消后立即放弃等待，工具会在下一次检查取消令牌时结束
+                        if cancel_requested && let Some(task) = input_state.task.take() {
+                            task.cancel();
+                            input_state.set_result(Err(ToolError::Cancelled));
+                        }
                     });
                 });
 
                 // 输出区域
                 ui.vertical(|ui| {
                     ui.group(|ui| {
-                        ui.heading("输出");
+                        ui.horizontal(|ui| {
+                            ui.heading(tr!("tool-output"));
+                            if let Some(output) = &input_state.output
+                                && !input_state.has_error
+                                && ui.button(tr!("common-export")).clicked()
+                            {
+                                input_state.export_message = export_output(output);
+                            }
+                        });
+                        if let Some(message) = &input_state.export_message {
+                            ui.label(message);
+                        }
                         let text_height = ui.available_height() * 0.8;
                         egui::ScrollArea::vertical()
                             .max_height(text_height)
@@ -149,15 +211,10 @@ pub fn render_tool_page(ctx: &egui::Context, tool: &dyn Tool, app: &mut crate::a
                                         egui::Color32::RED,
                                         &input_state.error_message,
                                     );
-                                } else if let Some(output) = &input_state.output_text {
-                                    ui.add(
-                                        egui::TextEdit::multiline(&mut output.clone())
-                                            .desired_width(f32::INFINITY)
-                                            .desired_rows(15)
-                                            .interactive(false),
-                                    );
+                                } else if let Some(output) = &input_state.output {
+                                    render_output(ui, output, "output", &mut input_state.textures);
                                 } else {
-                                    ui.weak("运行工具后将在此显示结果...");
+                                    ui.weak(tr!("tool-output-placeholder"));
                                 }
                             });
                     });
@@ -166,3 +223,202 @@ pub fn render_tool_page(ctx: &egui::Context, tool: &dyn Tool, app: &mut crate::a
         });
     });
 }
+
+/// 根据参数规格渲染对应的输入控件
+pub(super) fn render_parameter_input(ui: &mut egui::Ui, spec: &ParameterSpec, value: &mut String) {
+    ui.horizontal(|ui| {
+        if spec.required {
+            ui.label(format!("{} *", spec.name));
+        } else {
+            ui.label(&spec.name);
+        }
+
+        match spec.kind {
+            ParameterType::Bool => {
+                let mut checked = value == "true";
+                if ui.checkbox(&mut checked, "").changed() {
+                    *value = checked.to_string();
+                }
+            }
+            ParameterType::Enum => {
+                let selected = if value.is_empty() {
+                    tr!("param-not-selected")
+                } else {
+                    value.clone()
+                };
+                egui::ComboBox::from_id_salt(&spec.name)
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        for allowed in &spec.allowed_values {
+                            ui.selectable_value(value, allowed.clone(), allowed);
+                        }
+                    });
+            }
+            ParameterType::Multiline => {
+                ui.add(
+                    egui::TextEdit::multiline(value)
+                        .desired_rows(3)
+                        .hint_text(spec.default.clone().unwrap_or_default()),
+                );
+            }
+            ParameterType::Path => {
+                ui.add(
+                    egui::TextEdit::singleline(value)
+                        .hint_text(spec.default.clone().unwrap_or_default()),
+                );
+                if ui.button(tr!("common-pick-file")).clicked()
+                    && let Some(path) = rfd::FileDialog::new().pick_file()
+                {
+                    *value = path.to_string_lossy().to_string();
+                }
+                if ui.button(tr!("common-pick-folder")).clicked()
+                    && let Some(path) = rfd::FileDialog::new().pick_folder()
+                {
+                    *value = path.to_string_lossy().to_string();
+                }
+            }
+            ParameterType::String | ParameterType::Int | ParameterType::Float => {
+                ui.add(
+                    egui::TextEdit::singleline(value)
+                        .hint_text(spec.default.clone().unwrap_or_default()),
+                );
+            }
+        }
+
+        ui.label(&spec.description);
+    });
+}
+
+/// 以错误颜色的边框突出显示导致执行失败的参数
+fn render_invalid_parameter(ui: &mut egui::Ui, spec: &ParameterSpec, value: &mut String) {
+    ui.scope(|ui| {
+        let stroke = egui::Stroke::new(1.5, ui.visuals().error_fg_color);
+        let widgets = &mut ui.visuals_mut().widgets;
+        widgets.inactive.bg_stroke = stroke;
+        widgets.hovered.bg_stroke = stroke;
+        ui.visuals_mut().override_text_color = Some(ui.visuals().error_fg_color);
+        render_parameter_input(ui, spec, value);
+    });
+}
+
+/// 渲染后台任务的进度条和取消按钮，返回是否点击了取消
+pub(super) fn render_task_progress(ui: &mut egui::Ui, task: &ToolTask) -> bool {
+    let progress = task.progress();
+    let mut cancel_requested = false;
+
+    ui.horizontal(|ui| {
+        match progress.fraction {
+            Some(fraction) => {
+                ui.add(
+                    egui::ProgressBar::new(fraction)
+                        .desired_width(200.0)
+                        .show_percentage(),
+                );
+            }
+            None => {
+                ui.spinner();
+            }
+        }
+        ui.label(if progress.message.is_empty() {
+            tr!("tool-running")
+        } else {
+            progress.message
+        });
+        if ui.button(tr!("common-cancel")).clicked() {
+            cancel_requested = true;
+        }
+    });
+
+    cancel_requested
+}
+
+/// 按输出类型渲染工具结果
+pub(super) fn render_output(
+    ui: &mut egui::Ui,
+    output: &ToolOutput,
+    id: &str,
+    textures: &mut HashMap<String, egui::TextureHandle>,
+) {
+    match output {
+        ToolOutput::Text(text) => {
+            ui.add(
+                egui::TextEdit::multiline(&mut text.as_str())
+                    .desired_width(f32::INFINITY)
+                    .desired_rows(15),
+            );
+        }
+        ToolOutput::Json(_) => {
+            ui.add(
+                egui::TextEdit::multiline(&mut output.to_text().as_str())
+                    .code_editor()
+                    .desired_width(f32::INFINITY)
+                    .desired_rows(15),
+            );
+        }
+        ToolOutput::Table { headers, rows } => {
+            ui.label(tr!("output-rows", count = rows.len()));
+            egui::Grid::new(id).striped(true).show(ui, |ui| {
+                for header in headers {
+                    ui.strong(header);
+                }
+                ui.end_row();
+
+                for row in rows {
+                    for cell in row {
+                        ui.label(cell);
+                    }
+                    ui.end_row();
+                }
+            });
+        }
+        ToolOutput::Binary { .. } => {
+            ui.label(output.to_text());
+            ui.weak(tr!("output-binary-hint"));
+        }
+        ToolOutput::Image { data, .. } => {
+            if !textures.contains_key(id) {
+                match image::load_from_memory(data) {
+                    Ok(image) => {
+                        let rgba = image.to_rgba8();
+                        let size = [rgba.width() as usize, rgba.height() as usize];
+                        let color_image =
+                            egui::ColorImage::from_rgba_unmultiplied(size, rgba.as_raw());
+                        let texture =
+                            ui.ctx()
+                                .load_texture(id, color_image, egui::TextureOptions::default());
+                        textures.insert(id.to_string(), texture);
+                    }
+                    Err(e) => {
+                        ui.colored_label(egui::Color32::RED, tr!("output-image-error", error = e));
+                        return;
+                    }
+                }
+            }
+
+            if let Some(texture) = textures.get(id) {
+                ui.label(output.to_text());
+                ui.add(egui::Image::new(texture).shrink_to_fit());
+            }
+        }
+        ToolOutput::Multiple(outputs) => {
+            for (i, child) in outputs.iter().enumerate() {
+                if i > 0 {
+                    ui.separator();
+                }
+                render_output(ui, child, &format!("{}.{}", id, i), textures);
+            }
+        }
+    }
+}
+
+/// 将工具输出导出到用户选择的文件，返回提示信息
+pub(super) fn export_output(output: &ToolOutput) -> Option<String> {
+    let path = rfd::FileDialog::new()
+        .set_file_name(format!("output.{}", output.file_extension()))
+        .save_file()?;
+
+    match std::fs::write(&path, output.to_bytes()) {
+        Ok(()) => Some(tr!("export-done", path = path.to_string_lossy())),
+        Err(e) => Some(tr!("export-failed", error = e)),
+    }
+}